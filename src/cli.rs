@@ -56,6 +56,15 @@ EXAMPLES:
     kabu remove --dry-run ../test
         Preview what would be removed without executing
 
+    kabu move ../feature-x ../feature-y
+        Move or rename a worktree, keeping its setup intact
+
+    kabu setup --all
+        Re-apply setup from config file to existing worktrees
+
+    kabu prune
+        Select merged worktrees to remove interactively
+
     kabu trust
         Trust hooks in config file (required for hook execution)
 
@@ -87,10 +96,35 @@ pub(crate) enum Command {
     #[command(visible_alias = "rm")]
     Remove(RemoveArgs),
 
+    /// Move or rename a worktree/workspace, keeping kabu's setup intact
+    #[command(visible_alias = "mv")]
+    Move(MoveArgs),
+
     /// List all worktrees/workspaces
     #[command(visible_alias = "ls")]
     List(ListArgs),
 
+    /// Re-apply mkdir/link/copy setup to existing worktrees/workspaces
+    Setup(SetupArgs),
+
+    /// Remove worktrees/workspaces whose branch is merged or whose upstream is gone
+    Prune(PruneArgs),
+
+    /// Lock a worktree/workspace so it is not removed, moved or pruned
+    Lock(LockArgs),
+
+    /// Unlock a worktree/workspace locked with `kabu lock`
+    Unlock(UnlockArgs),
+
+    /// Check worktrees/workspaces, setup files and trust entries for problems
+    Doctor(DoctorArgs),
+
+    /// Run on_enter hooks for the current worktree/workspace (called by shell integration)
+    Enter(EnterArgs),
+
+    /// Show the output of the last hook run of a worktree/workspace
+    Logs(LogsArgs),
+
     /// Select a worktree/workspace and print its path
     Path(PathArgs),
 
@@ -123,6 +157,9 @@ pub(crate) enum Command {
 #[derive(Parser, Debug)]
 #[command(after_help = "\
 CONFIG FORMAT:
+    extends:                 # Optional, see EXTENDS below
+      - .kabu/shared.yaml
+
     defaults:
       on_conflict: backup    # Optional, see CONFLICT MODES below
 
@@ -143,20 +180,68 @@ CONFIG FORMAT:
         ignore_tracked: true # Optional, skip git-tracked files (for glob patterns)
         description: ...
 
+      - source: .env         # Relative to source_root instead of the repo root
+        source_root: ~/secrets/{{repository}}  # Optional, see SOURCE ROOTS below
+
     copy:
       - source: .env.example # Required, relative to repo root
         target: .env         # Optional, defaults to source
         on_conflict: backup  # Optional, overrides global
+        render: true         # Optional, expand template variables in contents
+        description: ...     # Optional
+        when:                # Optional, see CONDITIONS below
+          file_exists: .env.example
+
+      - source: config/*.local.json  # Glob patterns work like for link
+        ignore_tracked: true # Optional, skip git-tracked files
+
+      - source: .venv        # Directory sources can be filtered
+        mode: auto           # Optional, see COPY MODES below
+        exclude: [__pycache__]  # Optional, see COPY FILTERS below
+        include: [...]       # Optional
+        preserve: [symlinks, mode, mtime]  # Optional
+        max_size: 500MB      # Optional, fail instead of copying more
+
+    include_file:            # Optional, see INCLUDE FILE below
+      mode: copy             # Required, link or copy
+      path: .worktreeinclude # Optional, defaults to .worktreeinclude, then
+                             # .kabu/include
+
+    prune:
+      base: main             # Optional, branch (git) or revset (jj) for kabu prune
+
+    default_profile: full    # Optional, see PROFILES below
+    profiles:
+      full:
+        hooks:
+          post_add:
+            - command: npm install
+      review:
         description: ...     # Optional
+        copy:
+          - source: .env.review
+            target: .env
+
+    ports:
+      web: 3000              # Port per worktree: {{port.web}} = 3000 + slot
+      debug:
+        base: 9200           # Block of ports per worktree
+        size: 10             # {{port.debug}}..{{port.debug.end}}
 
     hooks:
       pre_add:
         - command: echo 'Setting up {{worktree_name}}'
 
+      post_create:
+        - command: git lfs pull
+
       post_add:
         - command: npm install
           description: Install dependencies  # Optional
 
+      on_enter:
+        - command: git fetch --quiet
+
       pre_remove:
         - command: echo 'Cleaning up {{worktree_name}}'
 
@@ -173,14 +258,112 @@ CONFLICT MODES:
     Default: prompt interactively (error if non-interactive, use --on-conflict)
 
 GLOB PATTERNS:
-    link entries support glob patterns in the source field:
+    link and copy entries support glob patterns in the source field:
         source: fixtures/*       Match all files in fixtures/
         source: file?.txt        Match single character
         source: file[0-9].txt    Match character ranges
 
-    With ignore_tracked: true, only git-ignored files are linked, while
+    Each match is linked or copied to the same path in the worktree. With
+    ignore_tracked: true, only git-ignored files are linked or copied, while
     git-tracked files (like .gitkeep) are skipped. This keeps git status clean.
 
+    Only the directory before the first wildcard is searched (fixtures/ for
+    fixtures/*), and git-ignored directories below it, like node_modules/ or
+    target/, are left out unless the pattern names them: **/.env finds no
+    node_modules/pkg/.env, node_modules/pkg/.env* does.
+
+INCLUDE FILE:
+    include_file: links or copies the files listed in an include file, so a
+    .worktreeinclude maintained for other tools need not be repeated in the
+    config. The file uses .gitignore syntax, including ! to exclude files
+    again. Tracked files are skipped, and a file that is also the target of
+    a link or copy entry is left to that entry.
+
+SOURCE ROOTS:
+    source_root: sets the directory a link/copy source is relative to:
+        repo                     Repository root (default)
+        main_worktree            Main worktree (git) or default workspace (jj)
+        home                     Your home directory
+        ~/secrets/{{repository}} Absolute or ~/ path; {{repository}} is expanded
+
+    Sources under home or an absolute path can come from outside the
+    repository, so such entries need kabu trust like hooks do, and the trust
+    review lists them.
+
+RENDERED COPIES:
+    copy entries with render: true expand variables in the file contents:
+        {{worktree_name}}        Worktree/workspace directory name
+        {{branch}}               Branch (git) or bookmark (jj) name
+        {{repository}}           Repository name
+
+    Other {{...}} sequences are left as-is. Sources must be UTF-8 text.
+
+COPY MODES:
+    mode: sets how copy entries write files:
+        copy                     Plain copy (default)
+        reflink                  Copy-on-write clone, falling back to a plain
+                                 copy where the file system has no support
+                                 (clones are Linux only)
+        hardlink                 Hard link; edits show up in both places
+        auto                     Same as reflink
+
+    mode cannot be combined with render: true.
+
+COPY FILTERS:
+    include:/exclude: select what a directory source copies. Patterns
+    without a / match a name at any depth (like .gitignore), others the
+    path below the source. Excluded directories are skipped entirely.
+    With include:, only matching files and the contents of matching
+    directories are copied.
+
+    preserve: carries over more than file contents:
+        symlinks                 Copy symlinks as symlinks (default: copy
+                                 what they point to)
+        mode                     Permissions, including of directories
+        mtime                    File modification times
+
+    max_size: limits the bytes to copy (e.g. 4096, 500MB, 1.5GB). A larger
+    source fails the entry before anything is written.
+
+PROFILES:
+    A profile adds hooks and mkdir/link/copy entries on top of the
+    top-level ones. Select one with kabu add --profile NAME or in the
+    interactive confirm step; default_profile applies otherwise. kabu
+    remembers the profile of each worktree for setup/enter/remove/move.
+    Profiles are part of the trusted configuration.
+
+EXTENDS:
+    extends: lists config files to build on, merged before this one:
+        global:rust.yaml         In the global config directory
+        .kabu/shared.yaml        Other relative paths (bare names too): from
+                                 the repository root
+        ~/kabu/team.yaml         Absolute and ~/ paths as is
+
+    Later files override settings, append hooks and replace mkdir/link/copy
+    entries with the same target. Extended files are part of the trusted
+    configuration.
+
+CONDITIONS:
+    mkdir/link/copy entries and hooks accept a when: clause. All conditions
+    set must hold, otherwise the entry is skipped (and reported as skipped):
+        file_exists: package.json  Path exists in the worktree (hooks: in
+                                   the directory the hook runs in)
+        vcs: git                   git or jj
+        branch: release/*          Branch/bookmark matches a glob pattern
+        os: linux                  linux, macos, windows, freebsd or unix
+        env: CI                    Variable is set and non-empty (or CI=true)
+
+    Conditions are part of the trusted configuration.
+
+PORTS:
+    With ports configured, kabu add gives each worktree a slot (1, 2, ...)
+    that is unique within the repository. Slot 0 is the main worktree.
+    Ports are base + slot * size, so concurrent dev servers never collide.
+
+    Slots are stored in kabu's data directory (override with KABU_STATE_DIR)
+    and released by kabu remove. kabu setup --run-hooks assigns slots to
+    worktrees created before ports was configured.
+
 HOOKS:
     Hooks run custom commands before/after worktree operations.
     Require explicit trust via 'kabu trust' before execution.
@@ -194,20 +377,71 @@ HOOKS:
           pre_add:
             - command: ...       # Required: shell command to execute
               description: ...   # Optional: human-readable description
+              timeout: 600       # Optional: kill after N seconds
+              cwd: frontend      # Optional: relative to where the hook runs
+              env:               # Optional: extra environment variables
+                NODE_ENV: development
+              retries: 2         # Optional: re-run up to N times on failure
+              continue_on_error: true  # Optional: warn and run the next hook
+              when:              # Optional: see CONDITIONS above
+                os: linux
+
+    Parallel groups run their commands at the same time:
+        hooks:
+          post_add:
+            - description: Install dependencies
+              output: prefix     # Optional: prefix (default) or buffer
+              parallel:
+                - command: npm install
+                - command: cargo fetch
+
+    With output: prefix, each line is printed as it arrives, prefixed with
+    the command's description (or command). With output: buffer, a command's
+    output is printed in one block when it finishes. Every command of the
+    group runs to completion, and all failures are reported together.
+
+    A hook is killed on timeout or Ctrl-C (SIGTERM, then SIGKILL after 2s).
+    Hooks with a timeout run in their own session (Unix) or job object
+    (Windows), so processes they started are killed too. On Unix such hooks
+    have no controlling terminal: they can use the terminal through
+    stdin/stdout, but cannot open /dev/tty. env values support the template
+    variables below without shell-escaping.
 
     Execution order (kabu add):
         1. pre_add (repo_root) → 2. git worktree add →
-        3. mkdir/link/copy → 4. post_add (worktree_path)
+        3. post_create (worktree_path) → 4. mkdir/link/copy →
+        5. post_add (worktree_path)
+    A failing post_create hook removes the new worktree again.
+
+    on_enter hooks run in the worktree after shell integration changes into
+    it (kabu cd, kabu add), via `kabu enter`.
 
     Execution order (kabu remove):
         1. pre_remove (worktree_path) → 2. git worktree remove →
         3. post_remove (repo_root)
 
+    Execution order (kabu move):
+        1. pre_move (old path) → 2. git worktree move →
+        3. post_move (new path)
+
     Template variables (automatically shell-escaped):
         {{worktree_path}}    Full path to the worktree
         {{worktree_name}}    Worktree directory name
         {{branch}}           Branch name
-        {{repo_root}}        Repository root path")]
+        {{repo_root}}        Repository root path
+        {{change_id}}        jj change ID (empty for git and in pre_add)
+        {{commit_id}}        Commit ID (empty in pre_add)
+        {{slot}}             Slot number (with ports configured)
+        {{port.NAME}}        First port allocated for NAME
+        {{port.NAME.end}}    Last port allocated for NAME
+
+    Environment variables (exported to every hook, e.g. for script files):
+        KABU_HOOK_EVENT      pre_add, post_create, post_add, pre_remove,
+                             post_remove, pre_move, post_move or on_enter
+        KABU_WORKTREE_PATH   KABU_WORKTREE_NAME   KABU_BRANCH
+        KABU_REPO_ROOT       KABU_VCS_TYPE        KABU_CHANGE_ID
+        KABU_COMMIT_ID       KABU_SLOT            KABU_PORT_NAME[_END]
+    Unavailable values (e.g. KABU_BRANCH on a detached HEAD) are unset.")]
 pub(crate) struct ConfigArgs {
     #[command(subcommand)]
     pub command: Option<ConfigCommand>,
@@ -301,6 +535,9 @@ EXAMPLES:
     kabu add --no-setup ../quick
         Create worktree/workspace without running setup
 
+    kabu add --profile review ../review
+        Set up with the extra hooks and files of the \"review\" profile
+
 CONFLICT MODES:
     abort      Stop immediately when a conflict is found (default in non-interactive)
     skip       Skip the conflicting file and continue
@@ -339,6 +576,10 @@ pub(crate) struct AddArgs {
     #[arg(long, help_heading = "kabu Options")]
     pub no_setup: bool,
 
+    /// Apply a profile from the config on top of the regular setup
+    #[arg(long, value_name = "NAME", help_heading = "kabu Options")]
+    pub profile: Option<String>,
+
     /// Windows-only: select hook shell (pwsh, powershell, bash, cmd, wsl)
     #[cfg(windows)]
     #[arg(
@@ -518,6 +759,472 @@ pub(crate) struct RemoveArgs {
     pub no_color: bool,
 }
 
+/// Arguments for the `lock` subcommand.
+#[derive(Parser, Debug)]
+#[command(after_help = "\
+VCS SUPPORT:
+    Works with both git worktree and jj workspace:
+    - Git: Locks worktree using `git worktree lock`
+    - jj:  jj has no locks; kabu records the lock in its data directory
+
+Locked worktrees/workspaces are marked [locked: reason] in `kabu list` and
+the selector. `kabu remove` refuses them unless --force is given,
+`kabu move` refuses them and `kabu prune` skips them.
+
+EXAMPLES:
+    kabu lock
+        Lock the current worktree/workspace
+
+    kabu lock ../feature-x --reason \"on a USB drive\"
+        Lock a worktree/workspace with a reason
+
+    kabu unlock ../feature-x
+        Unlock it again")]
+pub(crate) struct LockArgs {
+    /// Worktree/workspace path (defaults to the current one)
+    pub path: Option<PathBuf>,
+
+    // --- kabu Options ---
+    /// Why the worktree/workspace is locked (shown by `kabu list`)
+    #[arg(long, help_heading = "kabu Options")]
+    pub reason: Option<String>,
+
+    // --- Shared Options ---
+    /// Suppress output
+    #[arg(short, long, help_heading = "Shared Options")]
+    pub quiet: bool,
+}
+
+/// Arguments for the `unlock` subcommand.
+#[derive(Parser, Debug)]
+pub(crate) struct UnlockArgs {
+    /// Worktree/workspace path (defaults to the current one)
+    pub path: Option<PathBuf>,
+
+    // --- Shared Options ---
+    /// Suppress output
+    #[arg(short, long, help_heading = "Shared Options")]
+    pub quiet: bool,
+}
+
+/// Arguments for the `move` subcommand.
+#[derive(Parser, Debug)]
+#[command(after_help = "\
+VCS SUPPORT:
+    Works with both git worktree and jj workspace:
+    - Git: Moves worktree using `git worktree move`
+    - jj:  Moves the directory and renames the workspace after it
+           using `jj workspace rename`
+
+Moving a worktree/workspace by hand breaks its VCS metadata. kabu move also
+keeps what kabu knows about it: its slot and ports, its setup manifest and
+its hook logs. Symlinks created by kabu that point into the moved
+worktree/workspace (from it or from other worktrees/workspaces) are
+re-created to point at the new location.
+
+HOOKS:
+    pre_move hooks run in the worktree/workspace before it is moved and
+    abort the move on failure. post_move hooks run in it after the move.
+
+EXAMPLES:
+    kabu move ../feature-x ../feature-y
+        Rename a worktree/workspace
+
+    kabu move ../feature-x ~/worktrees/feature-x
+        Move a worktree/workspace to another directory
+
+    kabu move --dry-run ../feature-x ../feature-y
+        Preview the move without executing")]
+pub(crate) struct MoveArgs {
+    /// Worktree/workspace to move
+    pub old_path: PathBuf,
+
+    /// New path (must not exist yet)
+    pub new_path: PathBuf,
+
+    // --- kabu Options ---
+    /// Preview actions without executing
+    #[arg(long, help_heading = "kabu Options")]
+    pub dry_run: bool,
+
+    /// Windows-only: select hook shell (pwsh, powershell, bash, cmd, wsl)
+    #[cfg(windows)]
+    #[arg(
+        long,
+        value_name = "SHELL",
+        help_heading = "kabu Options",
+        value_parser = [
+            "pwsh",
+            "powershell",
+            "bash",
+            "git-bash",
+            "gitbash",
+            "cmd",
+            "cmd.exe",
+            "wsl"
+        ]
+    )]
+    pub hook_shell: Option<String>,
+
+    // --- Shared Options ---
+    /// Suppress output
+    #[arg(short, long, help_heading = "Shared Options")]
+    pub quiet: bool,
+
+    /// When to use colored output (always, auto, never)
+    #[arg(
+        long,
+        value_name = "WHEN",
+        default_value = "auto",
+        conflicts_with = "no_color",
+        help_heading = "Shared Options"
+    )]
+    pub color: clap::ColorChoice,
+
+    /// Disable colored output (equivalent to --color=never)
+    #[arg(long, help_heading = "Shared Options")]
+    pub no_color: bool,
+}
+
+/// Arguments for the `setup` subcommand.
+#[derive(Parser, Debug)]
+#[command(after_help = "\
+Runs the same mkdir/link/copy setup as `kabu add` against worktrees/workspaces
+that already exist, e.g. after a new `link:` entry was added to the config.
+Symlinks that already point at their source are left unchanged; other existing
+targets go through the usual conflict handling.
+
+EXAMPLES:
+    kabu setup
+        Set up the worktree/workspace containing the current directory
+
+    kabu setup ../feature-branch
+        Set up a specific worktree/workspace
+
+    kabu setup --all --on-conflict skip
+        Set up every non-main worktree/workspace, keeping existing files
+
+    kabu setup --run-hooks
+        Also re-run post_add hooks after setup (requires trust)
+
+    kabu setup --profile full
+        Switch the current worktree/workspace to another profile
+
+    kabu setup --all --dry-run
+        Preview what would be done without executing
+
+CONFLICT MODES:
+    abort      Stop immediately when a conflict is found (default in non-interactive)
+    skip       Skip the conflicting file and continue
+    overwrite  Replace the existing file
+    backup     Rename existing file with .bak suffix before creating new one
+
+ENVIRONMENT VARIABLES:
+    KABU_ON_CONFLICT    Default conflict resolution mode (e.g., KABU_ON_CONFLICT=backup)
+    KABUHOOK_SHELL      Windows-only hook shell override (pwsh, powershell, bash, cmd, wsl)")]
+pub(crate) struct SetupArgs {
+    /// Worktree/workspace path to set up (defaults to the current one)
+    #[arg(conflicts_with = "all")]
+    pub path: Option<PathBuf>,
+
+    // --- kabu Options ---
+    /// Set up all worktrees/workspaces except the main one
+    #[arg(short, long, help_heading = "kabu Options")]
+    pub all: bool,
+
+    /// Also re-run post_add hooks after setup
+    #[arg(long, help_heading = "kabu Options")]
+    pub run_hooks: bool,
+
+    /// Apply this profile instead of the one the worktree/workspace was set up with
+    #[arg(long, value_name = "NAME", help_heading = "kabu Options")]
+    pub profile: Option<String>,
+
+    /// How to handle conflicts: abort, skip, overwrite, backup
+    #[arg(
+        long,
+        value_name = "MODE",
+        help_heading = "kabu Options",
+        env = "KABU_ON_CONFLICT"
+    )]
+    pub on_conflict: Option<OnConflictArg>,
+
+    /// Preview actions without executing
+    #[arg(long, help_heading = "kabu Options")]
+    pub dry_run: bool,
+
+    /// Windows-only: select hook shell (pwsh, powershell, bash, cmd, wsl)
+    #[cfg(windows)]
+    #[arg(
+        long,
+        value_name = "SHELL",
+        help_heading = "kabu Options",
+        value_parser = [
+            "pwsh",
+            "powershell",
+            "bash",
+            "git-bash",
+            "gitbash",
+            "cmd",
+            "cmd.exe",
+            "wsl"
+        ]
+    )]
+    pub hook_shell: Option<String>,
+
+    // --- Shared Options ---
+    /// Suppress output
+    #[arg(short, long, help_heading = "Shared Options")]
+    pub quiet: bool,
+
+    /// When to use colored output (always, auto, never)
+    #[arg(
+        long,
+        value_name = "WHEN",
+        default_value = "auto",
+        conflicts_with = "no_color",
+        help_heading = "Shared Options"
+    )]
+    pub color: clap::ColorChoice,
+
+    /// Disable colored output (equivalent to --color=never)
+    #[arg(long, help_heading = "Shared Options")]
+    pub no_color: bool,
+}
+
+/// Arguments for the `prune` subcommand.
+#[derive(Parser, Debug)]
+#[command(after_help = "\
+VCS SUPPORT:
+    Works with both git worktree and jj workspace. A branch/bookmark is
+    merged if it is an ancestor of the base but not on the base's first-parent
+    history, i.e. it was merged with a merge commit. A new branch/bookmark
+    without commits of its own is kept, and so is one that was fast-forwarded.
+    - Git: A branch whose upstream was deleted on the remote is reported as
+           \"upstream gone\".
+
+    Detached and locked worktrees/workspaces are never proposed.
+
+BASE:
+    --base > prune.base in config > default
+    Default: the branch checked out in the main worktree (git), trunk() (jj)
+
+EXAMPLES:
+    kabu prune
+        Select merged worktrees/workspaces to remove interactively
+
+    kabu prune --base develop
+        Check branches against develop instead of the default base
+
+    kabu prune --dry-run
+        List candidates and preview what would be removed
+
+    kabu prune --yes
+        Remove all candidates without selecting (safety checks still apply)
+
+SAFETY CHECKS:
+    Same as kabu remove: worktrees/workspaces with uncommitted changes or
+    unpushed commits require confirmation. Use --force to skip the checks.
+    pre_remove/post_remove hooks run for each removed worktree/workspace.")]
+pub(crate) struct PruneArgs {
+    // --- kabu Options ---
+    /// Branch (git) or revset (jj) to check for merged branches/bookmarks
+    #[arg(long, value_name = "REV", help_heading = "kabu Options")]
+    pub base: Option<String>,
+
+    /// Remove all candidates without interactive selection
+    #[arg(short, long, help_heading = "kabu Options")]
+    pub yes: bool,
+
+    /// Preview actions without executing
+    #[arg(long, help_heading = "kabu Options")]
+    pub dry_run: bool,
+
+    /// Windows-only: select hook shell (pwsh, powershell, bash, cmd, wsl)
+    #[cfg(windows)]
+    #[arg(
+        long,
+        value_name = "SHELL",
+        help_heading = "kabu Options",
+        value_parser = [
+            "pwsh",
+            "powershell",
+            "bash",
+            "git-bash",
+            "gitbash",
+            "cmd",
+            "cmd.exe",
+            "wsl"
+        ]
+    )]
+    pub hook_shell: Option<String>,
+
+    // --- git worktree Options ---
+    /// Force removal even if worktree/workspace is dirty
+    #[arg(short, long, help_heading = "git worktree Options")]
+    pub force: bool,
+
+    // --- Shared Options ---
+    /// Suppress output
+    #[arg(short, long, help_heading = "Shared Options")]
+    pub quiet: bool,
+
+    /// When to use colored output (always, auto, never)
+    #[arg(
+        long,
+        value_name = "WHEN",
+        default_value = "auto",
+        conflicts_with = "no_color",
+        help_heading = "Shared Options"
+    )]
+    pub color: clap::ColorChoice,
+
+    /// Disable colored output (equivalent to --color=never)
+    #[arg(long, help_heading = "Shared Options")]
+    pub no_color: bool,
+}
+
+/// Arguments for the `doctor` subcommand.
+#[derive(Parser, Debug)]
+#[command(after_help = "\
+CHECKS:
+    - Link/copy sources in the config that no longer exist in the repository
+    - Worktrees/workspaces still known to git/jj whose directory is gone
+    - Configured symlinks that are missing, or dangling (e.g. after the main
+      repository moved), and kabu-created symlinks whose source was deleted
+    - Configured directories and copies missing from a worktree/workspace
+    - Trust entries for repositories that no longer exist
+
+FIXES (--fix):
+    - Re-create missing and dangling symlinks
+    - Remove kabu-created symlinks whose source was deleted
+    - Prune metadata of deleted worktrees (`git worktree prune`) or forget
+      deleted workspaces (`jj workspace forget`), releasing their kabu state
+    - Remove stale trust entries
+
+    Missing directories and copies are reported with a hint to run `kabu setup`.
+
+EXAMPLES:
+    kabu doctor
+        Report problems (exits with status 1 if any are found)
+
+    kabu doctor --fix
+        Report problems and repair what can be fixed automatically")]
+pub(crate) struct DoctorArgs {
+    // --- kabu Options ---
+    /// Repair the problems that can be fixed automatically
+    #[arg(long, help_heading = "kabu Options")]
+    pub fix: bool,
+
+    // --- Shared Options ---
+    /// Suppress output
+    #[arg(short, long, help_heading = "Shared Options")]
+    pub quiet: bool,
+
+    /// When to use colored output (always, auto, never)
+    #[arg(
+        long,
+        value_name = "WHEN",
+        default_value = "auto",
+        conflicts_with = "no_color",
+        help_heading = "Shared Options"
+    )]
+    pub color: clap::ColorChoice,
+
+    /// Disable colored output (equivalent to --color=never)
+    #[arg(long, help_heading = "Shared Options")]
+    pub no_color: bool,
+}
+
+/// Arguments for the `enter` subcommand.
+#[derive(Parser, Debug)]
+#[command(after_help = "\
+ON_ENTER HOOKS:
+    Runs the on_enter hooks from the config file in the current
+    worktree/workspace. Shell integration (`kabu init`) calls this after
+    `kabu cd` and `kabu add` change into a worktree/workspace, so there is
+    usually no need to run it by hand.
+
+    Without on_enter hooks this does nothing. With untrusted hooks it prints
+    a one-line warning instead of the trust review.
+
+    Hooks run in a child process: they cannot change the environment or the
+    working directory of the calling shell.
+
+EXAMPLES:
+    kabu enter
+        Run on_enter hooks for the current worktree/workspace")]
+pub(crate) struct EnterArgs {
+    // --- Shared Options ---
+    /// Suppress output
+    #[arg(short, long, help_heading = "Shared Options")]
+    pub quiet: bool,
+
+    /// When to use colored output (always, auto, never)
+    #[arg(
+        long,
+        value_name = "WHEN",
+        default_value = "auto",
+        conflicts_with = "no_color",
+        help_heading = "Shared Options"
+    )]
+    pub color: clap::ColorChoice,
+
+    /// Disable colored output (equivalent to --color=never)
+    #[arg(long, help_heading = "Shared Options")]
+    pub no_color: bool,
+}
+
+/// Arguments for the `logs` subcommand.
+#[derive(Parser, Debug)]
+#[command(after_help = "\
+LOGS:
+    Every hook run (e.g. all post_add hooks of one `kabu add`) writes a log
+    file in kabu's data directory, keyed by repository, worktree/workspace
+    and start time. The last 20 runs of each hook event are kept per
+    worktree/workspace, also after it was removed; successful on_enter runs
+    that printed nothing are not kept. Hook output is shown as it arrives and
+    logged at the same time. When kabu runs in a terminal, hooks write to a
+    pseudo terminal (Unix), so colors, progress bars and prompts still work.
+
+EXAMPLES:
+    kabu logs
+        Show the last hook run of the current worktree/workspace
+
+    kabu logs --event post_add
+        Show the last post_add run, also after later on_enter runs
+
+    kabu logs ../feature-x
+        Show the last hook run of another worktree/workspace
+
+    kabu logs --list
+        List all kept logs of the current worktree/workspace, oldest first")]
+pub(crate) struct LogsArgs {
+    /// Worktree/workspace path (defaults to the current one)
+    pub path: Option<PathBuf>,
+
+    /// List the paths of all kept logs instead of showing the last one
+    #[arg(long)]
+    pub list: bool,
+
+    /// Only consider runs of this hook event
+    #[arg(
+        long,
+        value_name = "EVENT",
+        value_parser = [
+            "pre_add",
+            "post_create",
+            "post_add",
+            "pre_remove",
+            "post_remove",
+            "pre_move",
+            "post_move",
+            "on_enter"
+        ]
+    )]
+    pub event: Option<String>,
+}
+
 /// Arguments for the `list` subcommand.
 #[derive(Parser, Debug)]
 #[command(after_help = "\
@@ -546,12 +1253,30 @@ EXAMPLES:
         List only paths (useful for scripting)
 
     kabu ls -p --header
-        Combine options using the short alias")]
+        Combine options using the short alias
+
+    kabu list --format json
+        Print all fields as a JSON array (same schema for git and jj)
+
+    kabu list --format tsv --header
+        Print all fields as tab-separated values with a header row
+
+MACHINE-READABLE FIELDS (json/tsv):
+    path, branch, head, is_main, is_locked, workspace_name,
+    has_uncommitted_changes, modified, deleted, untracked,
+    has_unpushed, unpushed, upstream, lock_reason, is_prunable
+
+    Missing values are null in JSON and empty in TSV.
+    branch is the bare branch (git) or bookmark (jj) name.")]
 pub(crate) struct ListArgs {
     /// Show only worktree/workspace paths
-    #[arg(short, long)]
+    #[arg(short, long, conflicts_with = "format")]
     pub path_only: bool,
 
+    /// Output format: text, json, or tsv (default: text)
+    #[arg(long, value_name = "FORMAT", default_value = "text")]
+    pub format: ListFormatArg,
+
     /// Show header row
     #[arg(long)]
     pub header: bool,
@@ -570,6 +1295,30 @@ pub(crate) struct ListArgs {
     pub no_color: bool,
 }
 
+/// Output format for the `list` subcommand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum ListFormatArg {
+    #[default]
+    Text,
+    Json,
+    Tsv,
+}
+
+impl std::str::FromStr for ListFormatArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "tsv" => Ok(Self::Tsv),
+            _ => Err(format!(
+                "Invalid format: {s}. Valid values: text, json, tsv"
+            )),
+        }
+    }
+}
+
 /// Arguments for the `trust` subcommand.
 #[derive(Parser, Debug)]
 #[command(after_help = "\
@@ -588,16 +1337,25 @@ HOOKS:
     Git Bash, or cmd). Override with --hook-shell or KABUHOOK_SHELL.
 
     pre_add      Run before worktree creation (in repo_root)
-    post_add     Run after worktree creation (in worktree_path)
+    post_create  Run after worktree creation, before setup (in worktree_path)
+    post_add     Run after worktree setup (in worktree_path)
     pre_remove   Run before worktree removal (in worktree_path)
     post_remove  Run after worktree removal (in repo_root)
+    pre_move     Run before kabu move moves a worktree (in the old path)
+    post_move    Run after kabu move moved a worktree (in the new path)
+    on_enter     Run after shell integration cds into a worktree (kabu enter)
 
     Execution order (kabu add):
-      1. pre_add → 2. git worktree add → 3. mkdir/link/copy → 4. post_add
+      1. pre_add → 2. git worktree add → 3. post_create →
+      4. mkdir/link/copy → 5. post_add
+    A failing post_create hook removes the new worktree again.
 
     Execution order (kabu remove):
       1. pre_remove → 2. git worktree remove → 3. post_remove
 
+    Execution order (kabu move):
+      1. pre_move → 2. git worktree move → 3. post_move
+
     Hooks can use template variables (see kabu config for details):
     {{worktree_path}}, {{worktree_name}}, {{branch}}, {{repo_root}}
 
@@ -615,11 +1373,18 @@ EXAMPLES:
         Exit 0 if hooks are trusted, 1 if trust is required
 
     kabu trust /path/to/repo
-        Trust hooks for a specific repository")]
+        Trust hooks for a specific repository
+
+    kabu trust --global
+        Trust hooks in the global config; they run in every repository")]
 pub(crate) struct TrustArgs {
     /// Path to repository (defaults to current directory)
     pub path: Option<PathBuf>,
 
+    /// Trust hooks in the global config instead of a repository's
+    #[arg(long, conflicts_with = "path")]
+    pub global: bool,
+
     /// Trust without confirmation prompt
     #[arg(short = 'y', long)]
     pub yes: bool,
@@ -754,6 +1519,10 @@ pub(crate) struct UntrustArgs {
     /// Path to repository (defaults to current directory)
     pub path: Option<PathBuf>,
 
+    /// Revoke trust for hooks in the global config
+    #[arg(long, conflicts_with_all = ["path", "list"])]
+    pub global: bool,
+
     /// List all trusted repositories
     #[arg(long)]
     pub list: bool,
@@ -2,25 +2,41 @@ mod add;
 mod cd;
 mod completions;
 mod config;
+mod doctor;
+mod enter;
 mod init;
 mod list;
+mod lock;
+mod logs;
 mod man;
+mod mv;
 mod path;
+mod prune;
 mod remove;
 mod schema;
+mod setup;
 mod trust;
 mod trust_check;
+mod unlock;
 mod untrust;
 
 pub(crate) use add::run as add;
 pub(crate) use cd::run as cd;
 pub(crate) use completions::run as completions;
 pub(crate) use config::run as config;
+pub(crate) use doctor::run as doctor;
+pub(crate) use enter::run as enter;
 pub(crate) use init::run as init;
 pub(crate) use list::run as list;
+pub(crate) use lock::run as lock;
+pub(crate) use logs::run as logs;
 pub(crate) use man::run as man;
+pub(crate) use mv::run as mv;
 pub(crate) use path::run as path;
+pub(crate) use prune::run as prune;
 pub(crate) use remove::run as remove;
 pub(crate) use schema::run as schema;
+pub(crate) use setup::run as setup;
 pub(crate) use trust::run as trust;
+pub(crate) use unlock::run as unlock;
 pub(crate) use untrust::run as untrust;
@@ -3,21 +3,29 @@
 //! Creates a new git worktree or jj workspace with automated setup from `.kabu/config.yaml`.
 //! Supports both interactive and non-interactive modes, with rollback on failure.
 
-use crate::cli::AddArgs;
+use crate::cli::{AddArgs, OnConflictArg};
 use crate::color::{self, ColorConfig};
 use crate::command::trust_check::{TrustHint, load_config_with_trust_check};
-use crate::config::{self, Config, Link, OnConflict};
+use crate::config::{self, Config, OnConflict, SourceContext, SourceRoot, When, WhenContext};
 use crate::error::{Error, Result};
 use crate::hook::{self, HookEnv};
 use crate::interactive;
 use crate::interactive::ConflictChoice;
-use crate::operation::{self, ConflictAction, check_conflict, create_directory, resolve_conflict};
+use crate::operation::{
+    self, ConflictAction, CopyOptions, RenderVars, check_conflict, create_directory,
+    resolve_conflict,
+};
 use crate::output::Output;
+use crate::state::{self, ManifestEntry, ManifestOp};
+use crate::trust;
 use crate::vcs::{self, VcsProvider};
 
-use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
+mod expand;
+
+pub(super) use expand::expand_entries;
+
 /// Execute the `add` subcommand.
 pub(crate) fn run(mut args: AddArgs, color: ColorConfig) -> Result<()> {
     let output = Output::new(args.quiet, color);
@@ -45,6 +53,11 @@ pub(crate) fn run(mut args: AddArgs, color: ColorConfig) -> Result<()> {
     )?;
     color::set_cli_theme(&config.ui.colors);
 
+    // Fail on an unknown --profile before anything interactive happens
+    if let Some(name) = &args.profile {
+        config.with_profile(Some(name))?;
+    }
+
     // Handle interactive mode
     let worktree_path = if args.interactive {
         run_interactive(&mut args, &config, provider.as_ref())?
@@ -91,27 +104,11 @@ pub(crate) fn run(mut args: AddArgs, color: ColorConfig) -> Result<()> {
         return Ok(());
     }
 
+    // The chosen profile, or the default one, adds to the setup
+    let config = config.with_profile(args.profile.as_deref())?;
+
     // Pre-validate: Check all source files exist BEFORE creating worktree
-    for link in &config.link {
-        // Skip validation for glob patterns - they will be expanded later
-        if contains_glob_pattern(&link.source) {
-            continue;
-        }
-        let source = repo_root.join(&link.source);
-        if !source.exists() {
-            return Err(Error::SourceNotFound {
-                path: link.source.to_string_lossy().to_string(),
-            });
-        }
-    }
-    for copy in &config.copy {
-        let source = repo_root.join(&copy.source);
-        if !source.exists() {
-            return Err(Error::SourceNotFound {
-                path: copy.source.to_string_lossy().to_string(),
-            });
-        }
-    }
+    validate_sources(&config, &source_context(&repo_root, provider.as_ref())?)?;
 
     // Create hook environment
     // Use empty string as fallback for non-UTF8 file names (rare edge case)
@@ -142,7 +139,24 @@ pub(crate) fn run(mut args: AddArgs, color: ColorConfig) -> Result<()> {
         }
     };
 
-    let hook_env = HookEnv {
+    // Hand out a slot (and ports) before any hook runs; released again if add fails
+    let allocation = if config.ports.is_empty() || args.dry_run {
+        None
+    } else {
+        Some(state::allocate_slot(
+            &main_worktree_path,
+            &worktree_path,
+            &config.ports,
+        )?)
+    };
+    let release_allocation = || {
+        if allocation.is_some() {
+            let _ = state::release_slot(&main_worktree_path, &worktree_path);
+        }
+    };
+
+    // change_id/commit_id are only known once the worktree exists (for post_add)
+    let mut hook_env = HookEnv {
         worktree_path: worktree_path.to_string_lossy().to_string(),
         worktree_name,
         branch,
@@ -150,23 +164,40 @@ pub(crate) fn run(mut args: AddArgs, color: ColorConfig) -> Result<()> {
         vcs_type: provider.name().to_string(),
         change_id: None,
         commit_id: None,
+        allocation: allocation.clone(),
+        log_dir: state::hook_log_dir(&main_worktree_path, &worktree_path).ok(),
         hook_shell,
     };
 
     // Run pre_add hooks
+    let mut pre_add_succeeded = 0;
     if !config.hooks.pre_add.is_empty() {
         if args.dry_run {
             if !args.quiet {
                 hook::dry_run_hooks("pre_add", &config.hooks.pre_add, &output);
             }
         } else {
-            hook::run_pre_add(&config.hooks, &hook_env, &repo_root, &output)?;
+            match hook::run_pre_add(&config.hooks, &hook_env, &repo_root, &output) {
+                Ok(succeeded) => pre_add_succeeded = succeeded,
+                Err(e) => {
+                    release_allocation();
+                    return Err(e);
+                }
+            }
         }
     }
 
     // Run workspace add
     if !args.dry_run {
-        provider.workspace_add(&args, &worktree_path)?;
+        if let Err(e) = provider.workspace_add(&args, &worktree_path) {
+            release_allocation();
+            return Err(e);
+        }
+        let revision = provider
+            .workspace_revision(&worktree_path)
+            .unwrap_or_default();
+        hook_env.change_id = revision.change_id;
+        hook_env.commit_id = revision.commit_id;
     } else {
         output.dry_run(&format!(
             "Would run: {} {} add {}",
@@ -176,12 +207,37 @@ pub(crate) fn run(mut args: AddArgs, color: ColorConfig) -> Result<()> {
         ));
     }
 
+    // Run post_create hooks; like a failed setup, a failure rolls the worktree back
+    let mut post_create_succeeded = 0;
+    if !config.hooks.post_create.is_empty() {
+        if args.dry_run {
+            if !args.quiet {
+                hook::dry_run_hooks("post_create", &config.hooks.post_create, &output);
+            }
+        } else {
+            match hook::run_post_create(&config.hooks, &hook_env, &worktree_path, &output) {
+                Ok(succeeded) => post_create_succeeded = succeeded,
+                Err(e) => {
+                    eprintln!("post_create hooks failed, rolling back workspace creation...");
+                    let _ = provider.workspace_remove(&worktree_path, true);
+                    release_allocation();
+                    return Err(e);
+                }
+            }
+        }
+    }
+
     // Process links and copies with rollback on failure
+    let setup_options = SetupOptions {
+        on_conflict: conflict_override(args.on_conflict),
+        dry_run: args.dry_run,
+        branch: hook_env.branch.clone(),
+    };
     if let Err(e) = run_setup(
-        &args,
         &config,
         &repo_root,
         &worktree_path,
+        &setup_options,
         &output,
         provider.as_ref(),
     ) {
@@ -189,13 +245,14 @@ pub(crate) fn run(mut args: AddArgs, color: ColorConfig) -> Result<()> {
         if !args.dry_run {
             eprintln!("Setup failed, rolling back workspace creation...");
             let _ = provider.workspace_remove(&worktree_path, true);
+            release_allocation();
         }
         return Err(e);
     }
 
-    // Run post_add hooks and track failure
+    // Run post_add hooks and track failures (several when a parallel group fails)
     let mut post_add_failed = false;
-    let mut post_add_error: Option<(String, Option<String>, Option<i32>)> = None;
+    let mut post_add_errors: Vec<(String, Option<String>, Option<i32>)> = Vec::new();
 
     if !config.hooks.post_add.is_empty() {
         if args.dry_run {
@@ -207,22 +264,36 @@ pub(crate) fn run(mut args: AddArgs, color: ColorConfig) -> Result<()> {
             post_add_failed = true;
 
             // Extract error details
-            let (command, exit_code) = match &e {
-                Error::HookFailed {
-                    command, exit_code, ..
-                } => (command.clone(), *exit_code),
-                _ => (String::new(), None),
+            let exit_code = match &e {
+                Error::HookFailed { exit_code, .. } => *exit_code,
+                _ => None,
             };
-
-            // Find the description for the failed command
-            let description = config
-                .hooks
-                .post_add
-                .iter()
-                .find(|entry| entry.command == command)
-                .and_then(|entry| entry.description.clone());
-
-            post_add_error = Some((command, description, exit_code));
+            match &e {
+                Error::HookFailed { command, .. }
+                | Error::HookTimedOut { command, .. }
+                | Error::HookExecutionFailed { command, .. }
+                | Error::HookInterrupted { command } => {
+                    // Find the description for the failed command
+                    let description = config
+                        .hooks
+                        .post_add
+                        .iter()
+                        .flat_map(|entry| entry.commands())
+                        .find(|entry| &entry.command == command)
+                        .and_then(|entry| entry.description.clone());
+                    post_add_errors.push((command.clone(), description, exit_code));
+                }
+                Error::ParallelHooksFailed { failures } => {
+                    post_add_errors.extend(failures.iter().map(|failure| {
+                        (
+                            failure.command.clone(),
+                            failure.description.clone(),
+                            failure.exit_code,
+                        )
+                    }));
+                }
+                _ => {}
+            }
 
             output.hook_warning("post_add", &e.to_string(), exit_code);
             output.hook_note("Worktree was created but post-setup may be incomplete.");
@@ -237,21 +308,35 @@ pub(crate) fn run(mut args: AddArgs, color: ColorConfig) -> Result<()> {
 
             if !config.hooks.pre_add.is_empty() {
                 output.results_item_success(&format!(
-                    "pre_add hooks ({} succeeded)",
-                    config.hooks.pre_add.len()
+                    "pre_add hooks ({pre_add_succeeded} succeeded)"
                 ));
             }
 
             output.results_item_success("Worktree created");
+            if !config.hooks.post_create.is_empty() {
+                output.results_item_success(&format!(
+                    "post_create hooks ({post_create_succeeded} succeeded)"
+                ));
+            }
             output.results_item_success("Setup operations completed");
 
             output.results_item_failed(&format!(
                 "post_add hooks ({} failed)",
-                if post_add_error.is_some() { 1 } else { 0 }
+                post_add_errors.len()
             ));
 
-            if let Some((command, description, exit_code)) = post_add_error {
-                output.results_failed_detail(description.as_deref(), &command, exit_code);
+            // The post_add run just finished, so its log is the latest one
+            let log = hook_env
+                .log_dir
+                .as_deref()
+                .and_then(|dir| state::latest_hook_log(dir).ok().flatten());
+            for (command, description, exit_code) in &post_add_errors {
+                output.results_failed_detail(
+                    description.as_deref(),
+                    command,
+                    *exit_code,
+                    log.as_deref(),
+                );
             }
         } else {
             // All succeeded - show message with path
@@ -342,6 +427,19 @@ fn run_interactive(
         suggest_branch_name,
         validate_branch_name,
         theme: interactive::UiTheme::from_ui(&config.ui),
+        profiles: config
+            .profiles
+            .iter()
+            .map(|(name, profile)| interactive::ProfileSummary {
+                name: name.clone(),
+                description: profile.description.clone(),
+            })
+            .collect(),
+        initial_profile: args
+            .profile
+            .clone()
+            .or_else(|| config.default_profile.clone()),
+        profile_required: config.default_profile.is_some(),
     })?;
 
     let branch_choice = result.branch_choice;
@@ -355,6 +453,7 @@ fn run_interactive(
     }
 
     args.path = Some(result.path.clone());
+    args.profile = result.profile;
 
     let worktree_path = if result.path.is_absolute() {
         result.path
@@ -365,86 +464,338 @@ fn run_interactive(
     Ok(worktree_path)
 }
 
+/// Convert the `--on-conflict` CLI value into a conflict mode override.
+pub(super) fn conflict_override(arg: Option<OnConflictArg>) -> Option<OnConflict> {
+    arg.map(|m| match m {
+        OnConflictArg::Abort => OnConflict::Abort,
+        OnConflictArg::Skip => OnConflict::Skip,
+        OnConflictArg::Overwrite => OnConflict::Overwrite,
+        OnConflictArg::Backup => OnConflict::Backup,
+    })
+}
+
+/// What link/copy sources of a repository are resolved against.
+pub(super) fn source_context(
+    repo_root: &Path,
+    provider: &dyn VcsProvider,
+) -> Result<SourceContext> {
+    let main_worktree_path = provider.main_workspace_path_for(repo_root)?;
+    // Named after the main worktree, also when run from a linked one
+    let repository = main_worktree_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| Error::NotInRepo {
+            vcs: provider.name().to_string(),
+        })?
+        .to_string();
+    Ok(SourceContext {
+        repo_root: repo_root.to_path_buf(),
+        main_worktree_path,
+        repository,
+    })
+}
+
+/// Check that all link and copy sources exist.
+///
+/// Glob sources are skipped here; they are expanded during setup. Entries with a
+/// `when:` clause are checked during setup once it is known whether they apply.
+pub(super) fn validate_sources(config: &Config, ctx: &SourceContext) -> Result<()> {
+    for link in &config.link {
+        // Skip validation for glob patterns - they will be expanded later. Global config
+        // entries apply to every repository; a missing source only skips the entry.
+        if contains_glob_pattern(&link.source) || link.when.is_some() || link.global {
+            continue;
+        }
+        require_source(&link.source_root, &link.source, ctx)?;
+    }
+    for copy in &config.copy {
+        if contains_glob_pattern(&copy.source) || copy.when.is_some() || copy.global {
+            continue;
+        }
+        require_source(&copy.source_root, &copy.source, ctx)?;
+    }
+    Ok(())
+}
+
+/// Whether a setup entry's `when:` clause (if any) fails, reporting it as skipped.
+fn skipped_by_condition(
+    when: Option<&When>,
+    ctx: &WhenContext,
+    target: &Path,
+    output: &Output,
+) -> bool {
+    match when {
+        Some(when) if !when.matches(ctx) => {
+            output.skip_condition(target, &when.to_string());
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Fail with [`Error::SourceNotFound`] if the source of an entry is missing.
+///
+/// Sources outside the repository are reported with their full path.
+fn require_source(root: &SourceRoot, source: &Path, ctx: &SourceContext) -> Result<()> {
+    let path = root.resolve(ctx)?.join(source);
+    if path.exists() {
+        return Ok(());
+    }
+    let shown = if root.is_outside_repo() {
+        path.as_path()
+    } else {
+        source
+    };
+    Err(Error::SourceNotFound {
+        path: shown.to_string_lossy().to_string(),
+    })
+}
+
+/// Options for [`run_setup`].
+pub(super) struct SetupOptions {
+    pub on_conflict: Option<OnConflict>,
+    pub dry_run: bool,
+    /// Branch (git) or bookmark (jj) expanded into `render: true` copies
+    pub branch: Option<String>,
+}
+
 /// Run the setup operations (mkdir, symlinks and copies)
-fn run_setup(
-    args: &AddArgs,
+///
+/// Everything created is recorded in the worktree's setup manifest, including
+/// operations that completed before a failure.
+pub(super) fn run_setup(
     config: &Config,
     repo_root: &Path,
     worktree_path: &Path,
+    options: &SetupOptions,
     output: &Output,
     provider: &dyn VcsProvider,
 ) -> Result<()> {
-    let mut conflict_mode_override: Option<OnConflict> = args.on_conflict.map(|m| match m {
-        crate::cli::OnConflictArg::Abort => OnConflict::Abort,
-        crate::cli::OnConflictArg::Skip => OnConflict::Skip,
-        crate::cli::OnConflictArg::Overwrite => OnConflict::Overwrite,
-        crate::cli::OnConflictArg::Backup => OnConflict::Backup,
-    });
+    let mut entries = Vec::new();
+    let result = apply_setup(
+        config,
+        repo_root,
+        worktree_path,
+        options,
+        output,
+        provider,
+        &mut entries,
+    );
+
+    if options.dry_run {
+        return result;
+    }
+
+    let recorded = provider
+        .main_workspace_path_for(repo_root)
+        .and_then(|main_worktree_path| {
+            let config_hash = trust::compute_hash(&main_worktree_path, config)?;
+            state::record_manifest(
+                &main_worktree_path,
+                worktree_path,
+                &config_hash,
+                config.profile.as_deref(),
+                entries,
+            )
+        });
+    result.and(recorded)
+}
+
+fn apply_setup(
+    config: &Config,
+    repo_root: &Path,
+    worktree_path: &Path,
+    options: &SetupOptions,
+    output: &Output,
+    provider: &dyn VcsProvider,
+    entries: &mut Vec<ManifestEntry>,
+) -> Result<()> {
+    let dry_run = options.dry_run;
+    let mut conflict_mode_override = options.on_conflict;
+    let sources = source_context(repo_root, provider)?;
+
+    // `file_exists` is checked in the new worktree; it doesn't exist yet in a dry run
+    let when_ctx = WhenContext {
+        vcs: provider.name(),
+        branch: options.branch.as_deref(),
+        base_dir: if worktree_path.exists() {
+            worktree_path
+        } else {
+            repo_root
+        },
+    };
 
     // Process mkdir
     for mkdir in &config.mkdir {
         let target = worktree_path.join(&mkdir.path);
+        if skipped_by_condition(mkdir.when.as_ref(), &when_ctx, &target, output) {
+            continue;
+        }
 
-        if args.dry_run {
+        if dry_run {
             output.dry_run(&format!("Would create directory: {}", target.display()));
         } else {
+            // Only directories kabu actually created are recorded
+            if !target.exists() {
+                entries.push(ManifestEntry {
+                    op: ManifestOp::Mkdir,
+                    source: None,
+                    target: mkdir.path.clone(),
+                    on_conflict: None,
+                    backup: None,
+                    content_hash: None,
+                });
+            }
             create_directory(&target)?;
             output.mkdir(&target, mkdir.description.as_deref());
         }
     }
 
-    // Process symlinks (expand glob patterns first)
-    for link in &config.link {
-        let expanded_links = expand_link(link, repo_root, provider)?;
+    // `when:` is checked per entry below, so `file_exists` sees what earlier entries created
+    let expanded = expand_entries(config, &sources, provider)?;
+
+    // Process symlinks
+    for (link, expanded_links) in config.link.iter().zip(expanded.links) {
+        let target = worktree_path.join(&link.target);
+        if skipped_by_condition(link.when.as_ref(), &when_ctx, &target, output) {
+            continue;
+        }
+        if !contains_glob_pattern(&link.source) {
+            if link.global {
+                if !link.source_path(&sources)?.exists() {
+                    output.skip_missing_source(&target, &link.source);
+                    continue;
+                }
+            } else if link.when.is_some() {
+                require_source(&link.source_root, &link.source, &sources)?;
+            }
+        }
+        let source_dir = link.source_root.resolve(&sources)?;
         for expanded_link in expanded_links {
             let params = OperationParams {
-                source: &repo_root.join(&expanded_link.source),
+                source: &source_dir.join(&expanded_link.source),
                 target: &worktree_path.join(&expanded_link.target),
                 op_type: FileOp::Link,
                 config_mode: expanded_link.on_conflict.or(config.on_conflict),
                 description: expanded_link.description.as_deref(),
             };
-            process_operation(&params, &mut conflict_mode_override, args.dry_run, output)?;
+            let outcome = process_operation(&params, &mut conflict_mode_override, dry_run, output)?;
+            entries.extend(manifest_entry(&params, &expanded_link.target, outcome)?);
         }
     }
+    for link in &expanded.included_links {
+        let params = OperationParams {
+            source: &link.source_path(&sources)?,
+            target: &worktree_path.join(&link.target),
+            op_type: FileOp::Link,
+            config_mode: config.on_conflict,
+            description: None,
+        };
+        let outcome = process_operation(&params, &mut conflict_mode_override, dry_run, output)?;
+        entries.extend(manifest_entry(&params, &link.target, outcome)?);
+    }
+
+    // Variables for rendered copies
+    let render_vars = if config.copy.iter().any(|c| c.render) {
+        Some(RenderVars {
+            worktree_name: worktree_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("")
+                .to_string(),
+            branch: options.branch.clone(),
+            repository: sources.repository.clone(),
+        })
+    } else {
+        None
+    };
 
     // Process copies
-    for copy in &config.copy {
+    for (copy, expanded_copies) in config.copy.iter().zip(expanded.copies) {
+        let target = worktree_path.join(&copy.target);
+        if skipped_by_condition(copy.when.as_ref(), &when_ctx, &target, output) {
+            continue;
+        }
+        if !contains_glob_pattern(&copy.source) {
+            if copy.global {
+                if !copy.source_path(&sources)?.exists() {
+                    output.skip_missing_source(&target, &copy.source);
+                    continue;
+                }
+            } else if copy.when.is_some() {
+                require_source(&copy.source_root, &copy.source, &sources)?;
+            }
+        }
+        let source_dir = copy.source_root.resolve(&sources)?;
+        let options = CopyOptions::new(copy)?;
+        for expanded_copy in expanded_copies {
+            let op_type = match &render_vars {
+                Some(vars) if copy.render => FileOp::Render(vars, &options),
+                _ => FileOp::Copy(&options),
+            };
+            let params = OperationParams {
+                source: &source_dir.join(&expanded_copy.source),
+                target: &worktree_path.join(&expanded_copy.target),
+                op_type,
+                config_mode: expanded_copy.on_conflict.or(config.on_conflict),
+                description: expanded_copy.description.as_deref(),
+            };
+            let outcome = process_operation(&params, &mut conflict_mode_override, dry_run, output)?;
+            entries.extend(manifest_entry(&params, &expanded_copy.target, outcome)?);
+        }
+    }
+    let plain_copy = CopyOptions::default();
+    for copy in &expanded.included_copies {
         let params = OperationParams {
-            source: &repo_root.join(&copy.source),
+            source: &copy.source_path(&sources)?,
             target: &worktree_path.join(&copy.target),
-            op_type: FileOp::Copy,
-            config_mode: copy.on_conflict.or(config.on_conflict),
-            description: copy.description.as_deref(),
+            op_type: FileOp::Copy(&plain_copy),
+            config_mode: config.on_conflict,
+            description: None,
         };
-        process_operation(&params, &mut conflict_mode_override, args.dry_run, output)?;
+        let outcome = process_operation(&params, &mut conflict_mode_override, dry_run, output)?;
+        entries.extend(manifest_entry(&params, &copy.target, outcome)?);
     }
 
     Ok(())
 }
 
 /// File operation type.
-enum FileOp {
+enum FileOp<'a> {
     Link,
-    Copy,
+    Copy(&'a CopyOptions),
+    /// Copy with template variables expanded in file contents
+    Render(&'a RenderVars, &'a CopyOptions),
 }
 
 /// Parameters for a file operation.
 struct OperationParams<'a> {
     source: &'a Path,
     target: &'a Path,
-    op_type: FileOp,
+    op_type: FileOp<'a>,
     config_mode: Option<OnConflict>,
     description: Option<&'a str>,
 }
 
+/// What [`process_operation`] did with its target.
+enum OperationOutcome {
+    /// Target was created, after resolving a conflict with the given mode if any
+    Created(Option<OnConflict>),
+    /// Target existed and the conflict was skipped
+    Skipped,
+    /// Symlink already pointed at the source
+    UpToDate,
+    /// Nothing was done (dry run)
+    DryRun,
+}
+
 /// Process a single operation (symlink or copy) with conflict handling.
 fn process_operation(
     params: &OperationParams,
     override_mode: &mut Option<OnConflict>,
     dry_run: bool,
     output: &Output,
-) -> Result<()> {
+) -> Result<OperationOutcome> {
     let OperationParams {
         source,
         target,
@@ -452,7 +803,22 @@ fn process_operation(
         config_mode,
         description,
     } = params;
+
+    // Resolving a conflict would delete the source before linking or copying it
+    if is_same_path(source, target) {
+        return Err(Error::SourceIsTarget {
+            path: target.to_path_buf(),
+        });
+    }
+
+    // A symlink already pointing at the source needs no work (e.g. `kabu setup` re-runs)
+    if matches!(op_type, FileOp::Link) && is_linked_to(target, source) {
+        output.up_to_date(target);
+        return Ok(OperationOutcome::UpToDate);
+    }
+
     // Check for conflict
+    let mut resolution = None;
     if check_conflict(target) {
         // Determine conflict mode
         let mode = if let Some(mode) = *override_mode {
@@ -474,10 +840,10 @@ fn process_operation(
             ConflictAction::Abort => return Err(Error::Aborted),
             ConflictAction::Skip => {
                 output.skip(target);
-                return Ok(());
+                return Ok(OperationOutcome::Skipped);
             }
             ConflictAction::Proceed => {
-                // Continue with operation
+                resolution = Some(mode);
             }
         }
     }
@@ -486,7 +852,8 @@ fn process_operation(
     if dry_run {
         let op_name = match op_type {
             FileOp::Link => "link",
-            FileOp::Copy => "copy",
+            FileOp::Copy(_) => "copy",
+            FileOp::Render(..) => "render",
         };
         output.dry_run(&format!(
             "Would {}: {} -> {}",
@@ -494,119 +861,111 @@ fn process_operation(
             source.display(),
             target.display()
         ));
+        Ok(OperationOutcome::DryRun)
     } else {
         match op_type {
             FileOp::Link => {
                 operation::create_symlink(source, target)?;
                 output.link(source, target, *description);
             }
-            FileOp::Copy => {
-                operation::copy_file(source, target)?;
+            FileOp::Copy(options) => {
+                operation::copy_file(source, target, options)?;
                 output.copy(source, target, *description);
             }
+            FileOp::Render(vars, options) => {
+                operation::render_file(source, target, vars, options)?;
+                output.render(source, target, *description);
+            }
         }
+        Ok(OperationOutcome::Created(resolution))
     }
-
-    Ok(())
 }
 
-/// Check if a path contains glob patterns.
-fn contains_glob_pattern(path: &Path) -> bool {
-    path.to_str()
-        .map(|s| s.contains('*') || s.contains('?') || s.contains('['))
-        .unwrap_or(false)
-}
-
-/// Expand a link entry with glob patterns into multiple concrete link entries.
-/// If ignore_tracked is true, filter out VCS-tracked files.
-fn expand_link(link: &Link, repo_root: &Path, provider: &dyn VcsProvider) -> Result<Vec<Link>> {
-    let source_str = link.source.to_string_lossy();
-
-    if !contains_glob_pattern(&link.source) {
-        // No glob pattern, return as-is
-        return Ok(vec![link.clone()]);
-    }
-
-    // Build glob matcher
-    let glob = globset::GlobBuilder::new(&source_str)
-        .literal_separator(true)
-        .build()
-        .map_err(|e| Error::ConfigValidation {
-            message: format!("Invalid glob pattern '{}': {}", source_str, e),
-        })?;
-    let matcher = glob.compile_matcher();
-
-    // Get VCS-tracked files if needed
-    let tracked_files: HashSet<PathBuf> = if link.ignore_tracked {
-        provider
-            .list_tracked_files(repo_root)?
-            .into_iter()
-            .collect()
-    } else {
-        HashSet::new()
+/// Build the manifest entry for a processed operation (none for dry runs).
+///
+/// `relative_target` is the configured target path, relative to the worktree.
+fn manifest_entry(
+    params: &OperationParams,
+    relative_target: &Path,
+    outcome: OperationOutcome,
+) -> Result<Option<ManifestEntry>> {
+    let op = match params.op_type {
+        FileOp::Link => ManifestOp::Link,
+        FileOp::Copy(_) => ManifestOp::Copy,
+        FileOp::Render(..) => ManifestOp::Render,
+    };
+    let mut entry = ManifestEntry {
+        op,
+        source: Some(params.source.to_path_buf()),
+        target: relative_target.to_path_buf(),
+        on_conflict: None,
+        backup: None,
+        content_hash: None,
     };
 
-    // Walk the repository and find matching files and directories
-    // Collect matched directories to avoid processing their contents
-    let mut matched_dirs: HashSet<PathBuf> = HashSet::new();
-    let mut results = Vec::new();
-
-    for entry in walkdir::WalkDir::new(repo_root)
-        .follow_links(false)
-        .into_iter()
-        .filter_entry(|e| e.file_name() != ".git")
-        .filter_map(|e| e.ok())
-    {
-        let path = entry.path();
-
-        // Get relative path from repo root
-        let rel_path = match path.strip_prefix(repo_root) {
-            Ok(p) => p,
-            Err(_) => continue,
-        };
-
-        // Skip if parent directory was already matched
-        let mut should_skip = false;
-        for matched_dir in &matched_dirs {
-            if rel_path.starts_with(matched_dir) && rel_path != matched_dir {
-                should_skip = true;
-                break;
+    match outcome {
+        OperationOutcome::DryRun => return Ok(None),
+        OperationOutcome::UpToDate => {}
+        OperationOutcome::Skipped => entry.on_conflict = Some(OnConflict::Skip),
+        OperationOutcome::Created(resolution) => {
+            entry.on_conflict = resolution;
+            if resolution == Some(OnConflict::Backup) {
+                entry.backup = Some(operation::backup_path(relative_target));
+            }
+            if op != ManifestOp::Link {
+                entry.content_hash = Some(state::hash_path(params.target)?);
             }
         }
-        if should_skip {
-            continue;
-        }
+    }
 
-        // Check if it matches the glob pattern
-        if !matcher.is_match(rel_path) {
-            continue;
-        }
+    Ok(Some(entry))
+}
 
-        // Skip if it's tracked and ignore_tracked is true
-        if link.ignore_tracked && tracked_files.contains(rel_path) {
-            continue;
-        }
+/// Check if target is a symlink pointing at source.
+pub(super) fn is_linked_to(target: &Path, source: &Path) -> bool {
+    std::fs::read_link(target)
+        .map(|link| link == source)
+        .unwrap_or(false)
+}
 
-        // If it's a directory, add to matched_dirs to skip its contents
-        if path.is_dir() {
-            matched_dirs.insert(rel_path.to_path_buf());
+/// Whether two paths name the same file, comparing their directories canonicalized.
+///
+/// The last component is not resolved, so a symlink and the file it points at differ.
+fn is_same_path(a: &Path, b: &Path) -> bool {
+    fn normalize(path: &Path) -> PathBuf {
+        match (path.parent(), path.file_name()) {
+            (Some(parent), Some(name)) => std::fs::canonicalize(parent)
+                .map(|parent| parent.join(name))
+                .unwrap_or_else(|_| path.to_path_buf()),
+            _ => path.to_path_buf(),
         }
-
-        // Create a link entry for this file or directory
-        let mut file_link = link.clone();
-        file_link.source = rel_path.to_path_buf();
-        file_link.target = rel_path.to_path_buf();
-        file_link.ignore_tracked = false; // Already filtered, no need to check again
-        results.push(file_link);
     }
+    a == b || normalize(a) == normalize(b)
+}
 
-    Ok(results)
+/// Check if a path contains glob patterns.
+pub(super) fn contains_glob_pattern(path: &Path) -> bool {
+    path.to_str()
+        .map(|s| s.contains('*') || s.contains('?') || s.contains('['))
+        .unwrap_or(false)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_same_path() {
+        assert!(is_same_path(
+            Path::new("/repo/.env"),
+            Path::new("/repo/.env")
+        ));
+        assert!(!is_same_path(
+            Path::new("/repo/.env"),
+            Path::new("/wt/.env")
+        ));
+    }
+
     #[test]
     fn test_contains_glob_pattern_with_asterisk() {
         assert!(contains_glob_pattern(Path::new("secrets/*")));
@@ -627,202 +986,3 @@ mod tests {
         assert!(!contains_glob_pattern(Path::new("secrets/config.json")));
     }
 }
-
-#[cfg(all(test, feature = "impure-test"))]
-mod impure_tests {
-    use super::*;
-
-    #[test]
-    fn test_expand_link_no_glob() {
-        use tempfile::TempDir;
-        let temp_dir = TempDir::new().unwrap();
-        let repo_root = temp_dir.path();
-
-        // Create test file
-        std::fs::write(repo_root.join("test.txt"), "content").unwrap();
-
-        let link = Link {
-            source: PathBuf::from("test.txt"),
-            target: PathBuf::from("test.txt"),
-            on_conflict: None,
-            description: None,
-            ignore_tracked: false,
-        };
-
-        let provider = vcs::GitProvider;
-        let result = expand_link(&link, repo_root, &provider).unwrap();
-        assert_eq!(result.len(), 1);
-        assert_eq!(result[0].source, PathBuf::from("test.txt"));
-    }
-
-    #[test]
-    fn test_expand_link_with_glob() {
-        use tempfile::TempDir;
-        let temp_dir = TempDir::new().unwrap();
-        let repo_root = temp_dir.path();
-
-        // Create test files
-        std::fs::create_dir_all(repo_root.join("fixtures")).unwrap();
-        std::fs::write(repo_root.join("fixtures/file1.txt"), "content1").unwrap();
-        std::fs::write(repo_root.join("fixtures/file2.txt"), "content2").unwrap();
-        std::fs::write(repo_root.join("fixtures/data.json"), "{}").unwrap();
-
-        let link = Link {
-            source: PathBuf::from("fixtures/*.txt"),
-            target: PathBuf::from("fixtures/*.txt"),
-            on_conflict: None,
-            description: None,
-            ignore_tracked: false,
-        };
-
-        let provider = vcs::GitProvider;
-        let result = expand_link(&link, repo_root, &provider).unwrap();
-        assert_eq!(result.len(), 2);
-
-        let mut sources: Vec<_> = result.iter().map(|l| l.source.clone()).collect();
-        sources.sort();
-        assert_eq!(sources[0], PathBuf::from("fixtures/file1.txt"));
-        assert_eq!(sources[1], PathBuf::from("fixtures/file2.txt"));
-    }
-
-    #[test]
-    fn test_expand_link_ignore_tracked() {
-        use tempfile::TempDir;
-        let temp_dir = TempDir::new().unwrap();
-        let repo_root = temp_dir.path();
-
-        // Initialize git repo
-        let init_result = std::process::Command::new("git")
-            .args(["init"])
-            .current_dir(repo_root)
-            .output();
-
-        if init_result.is_err() {
-            eprintln!("Skipping test: git not available");
-            return;
-        }
-
-        // Configure git user for the test repo
-        std::process::Command::new("git")
-            .args(["config", "user.email", "test@example.com"])
-            .current_dir(repo_root)
-            .output()
-            .ok();
-        std::process::Command::new("git")
-            .args(["config", "user.name", "Test User"])
-            .current_dir(repo_root)
-            .output()
-            .ok();
-
-        // Create and track a file
-        std::fs::create_dir_all(repo_root.join("fixtures")).unwrap();
-        std::fs::write(repo_root.join("fixtures/tracked.txt"), "tracked").unwrap();
-        std::fs::write(repo_root.join("fixtures/untracked.txt"), "untracked").unwrap();
-
-        // Add and commit the tracked file
-        let add_result = std::process::Command::new("git")
-            .args(["add", "fixtures/tracked.txt"])
-            .current_dir(repo_root)
-            .output();
-
-        if add_result.is_err() || !add_result.unwrap().status.success() {
-            eprintln!("Skipping test: git add failed");
-            return;
-        }
-
-        let commit_result = std::process::Command::new("git")
-            .args(["commit", "-m", "Add tracked file"])
-            .current_dir(repo_root)
-            .output();
-
-        if commit_result.is_err() || !commit_result.unwrap().status.success() {
-            eprintln!("Skipping test: git commit failed");
-            return;
-        }
-
-        let link = Link {
-            source: PathBuf::from("fixtures/*.txt"),
-            target: PathBuf::from("fixtures/*.txt"),
-            on_conflict: None,
-            description: None,
-            ignore_tracked: true,
-        };
-
-        let provider = vcs::GitProvider;
-        let result = expand_link(&link, repo_root, &provider).unwrap();
-
-        // Should only include untracked file
-        // Note: This test may be flaky in some environments
-        if result.len() == 1 {
-            assert_eq!(result[0].source, PathBuf::from("fixtures/untracked.txt"));
-        } else {
-            eprintln!(
-                "Warning: Expected 1 result but got {}. This may be environment-dependent.",
-                result.len()
-            );
-        }
-    }
-
-    #[test]
-    fn test_expand_link_with_directory() {
-        use tempfile::TempDir;
-        let temp_dir = TempDir::new().unwrap();
-        let repo_root = temp_dir.path();
-
-        // Create test directory with files inside
-        std::fs::create_dir_all(repo_root.join("testdir")).unwrap();
-        std::fs::write(repo_root.join("testdir/file1.txt"), "content1").unwrap();
-        std::fs::write(repo_root.join("testdir/file2.txt"), "content2").unwrap();
-
-        // Pattern matching the directory
-        let link = Link {
-            source: PathBuf::from("testdir"),
-            target: PathBuf::from("testdir"),
-            on_conflict: None,
-            description: None,
-            ignore_tracked: false,
-        };
-
-        let provider = vcs::GitProvider;
-        let result = expand_link(&link, repo_root, &provider).unwrap();
-
-        // Should return only the directory, not its contents
-        assert_eq!(result.len(), 1);
-        assert_eq!(result[0].source, PathBuf::from("testdir"));
-    }
-
-    #[test]
-    fn test_expand_link_with_glob_matching_directory() {
-        use tempfile::TempDir;
-        let temp_dir = TempDir::new().unwrap();
-        let repo_root = temp_dir.path();
-
-        // Create test directories with files inside
-        std::fs::create_dir_all(repo_root.join("dir1")).unwrap();
-        std::fs::write(repo_root.join("dir1/file.txt"), "content1").unwrap();
-        std::fs::create_dir_all(repo_root.join("dir2")).unwrap();
-        std::fs::write(repo_root.join("dir2/file.txt"), "content2").unwrap();
-        std::fs::create_dir_all(repo_root.join("other")).unwrap();
-        std::fs::write(repo_root.join("other/file.txt"), "content3").unwrap();
-
-        // Pattern matching directories starting with "dir"
-        let link = Link {
-            source: PathBuf::from("dir*"),
-            target: PathBuf::from("dir*"),
-            on_conflict: None,
-            description: None,
-            ignore_tracked: false,
-        };
-
-        let provider = vcs::GitProvider;
-        let result = expand_link(&link, repo_root, &provider).unwrap();
-
-        // Should return only the directories, not their contents
-        assert_eq!(result.len(), 2);
-
-        let mut sources: Vec<_> = result.iter().map(|l| l.source.clone()).collect();
-        sources.sort();
-        assert_eq!(sources[0], PathBuf::from("dir1"));
-        assert_eq!(sources[1], PathBuf::from("dir2"));
-    }
-}
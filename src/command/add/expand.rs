@@ -0,0 +1,866 @@
+//! Expansion of glob link/copy sources and include files.
+//!
+//! The patterns of all entries resolved against the same directory are matched in a
+//! single walk of it. The walk only goes into directories on the literal prefix of a
+//! pattern (`fixtures` for `fixtures/*.json`) or below it, and below the prefix it
+//! leaves out directories ignored by git, such as `node_modules` or `target`. A
+//! pattern reaches into those only by naming them before its first wildcard.
+
+use super::contains_glob_pattern;
+use crate::config::{self, Config, Copy, IncludeFile, Link, SourceContext};
+use crate::error::{Error, Result};
+use crate::vcs::VcsProvider;
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use globset::GlobMatcher;
+use ignore::Match;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+/// Link and copy entries with their glob sources expanded.
+#[derive(Debug, Default)]
+pub(crate) struct Expanded {
+    /// Expansion of each configured link entry, in config order
+    pub links: Vec<Vec<Link>>,
+    /// Expansion of each configured copy entry, in config order
+    pub copies: Vec<Vec<Copy>>,
+    /// Entries for the files listed in the include file
+    pub included_links: Vec<Link>,
+    pub included_copies: Vec<Copy>,
+}
+
+/// Expand the link and copy entries of a config and the files listed in its include file.
+///
+/// Entries without glob pattern are kept as they are. `when:` clauses are not
+/// looked at; they are checked when an entry's turn comes during setup, after the
+/// entries before it were applied. Each match is linked or copied to the same
+/// relative path. Include file paths that are also the target of a
+/// configured link or copy entry, including one matched by its glob, are left to it.
+pub(crate) fn expand_entries(
+    config: &Config,
+    sources: &SourceContext,
+    provider: &dyn VcsProvider,
+) -> Result<Expanded> {
+    let mut expanded = Expanded::default();
+    let mut walks: Vec<Walk> = Vec::new();
+
+    for (index, link) in config.link.iter().enumerate() {
+        expanded.links.push(Vec::new());
+        if !contains_glob_pattern(&link.source) {
+            expanded.links[index].push(link.clone());
+            continue;
+        }
+        let query = Query::glob(Owner::Link(index), &link.source, link.ignore_tracked)?;
+        walk_for(&mut walks, link.source_root.resolve(sources)?)
+            .queries
+            .push(query);
+    }
+    for (index, copy) in config.copy.iter().enumerate() {
+        expanded.copies.push(Vec::new());
+        if !contains_glob_pattern(&copy.source) {
+            expanded.copies[index].push(copy.clone());
+            continue;
+        }
+        let query = Query::glob(Owner::Copy(index), &copy.source, copy.ignore_tracked)?;
+        walk_for(&mut walks, copy.source_root.resolve(sources)?)
+            .queries
+            .push(query);
+    }
+    if let Some(include_file) = &config.include_file
+        && let Some(query) = Query::include_file(include_file, &sources.repo_root)?
+    {
+        walk_for(&mut walks, sources.repo_root.clone())
+            .queries
+            .push(query);
+    }
+
+    let mut included = Vec::new();
+    for mut walk in walks {
+        walk.run(provider)?;
+        for query in walk.queries {
+            match query.owner {
+                Owner::Link(index) => {
+                    let link = &config.link[index];
+                    expanded.links[index] = query
+                        .matches
+                        .into_iter()
+                        .map(|rel_path| Link {
+                            source: rel_path.clone(),
+                            target: rel_path,
+                            ignore_tracked: false, // Already filtered, no need to check again
+                            ..link.clone()
+                        })
+                        .collect();
+                }
+                Owner::Copy(index) => {
+                    let copy = &config.copy[index];
+                    expanded.copies[index] = query
+                        .matches
+                        .into_iter()
+                        .map(|rel_path| Copy {
+                            source: rel_path.clone(),
+                            target: rel_path,
+                            ignore_tracked: false,
+                            ..copy.clone()
+                        })
+                        .collect();
+                }
+                Owner::IncludeFile => included = query.matches,
+            }
+        }
+    }
+
+    if let Some(include_file) = &config.include_file {
+        // Targets as expanded, so paths matched by a glob entry count as configured
+        let configured: HashSet<&Path> = expanded
+            .links
+            .iter()
+            .flatten()
+            .map(|link| link.target.as_path())
+            .chain(
+                expanded
+                    .copies
+                    .iter()
+                    .flatten()
+                    .map(|copy| copy.target.as_path()),
+            )
+            .collect();
+        included.retain(|path| !configured.contains(path.as_path()));
+        (expanded.included_links, expanded.included_copies) = include_file.entries(included);
+    }
+
+    Ok(expanded)
+}
+
+/// The walk of `source_dir`, added if there is none yet.
+fn walk_for(walks: &mut Vec<Walk>, source_dir: PathBuf) -> &mut Walk {
+    let index = match walks.iter().position(|walk| walk.source_dir == source_dir) {
+        Some(index) => index,
+        None => {
+            walks.push(Walk {
+                source_dir,
+                queries: Vec::new(),
+            });
+            walks.len() - 1
+        }
+    };
+    &mut walks[index]
+}
+
+/// Queries resolved against the same directory, matched in one walk of it.
+struct Walk {
+    source_dir: PathBuf,
+    queries: Vec<Query>,
+}
+
+impl Walk {
+    /// Walk the source directory, collecting the matches of every query.
+    fn run(&mut self, provider: &dyn VcsProvider) -> Result<()> {
+        // Get VCS-tracked files if needed
+        let tracked_files: HashSet<PathBuf> = if self.queries.iter().any(|q| q.ignore_tracked) {
+            provider
+                .list_tracked_files(&self.source_dir)?
+                .into_iter()
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        let mut ignores = IgnoreStack::default();
+        ignores.enter(Path::new(""), &self.source_dir);
+
+        let mut entries = walkdir::WalkDir::new(&self.source_dir)
+            .follow_links(false)
+            .into_iter()
+            .filter_entry(|e| e.file_name() != ".git");
+        while let Some(entry) = entries.next() {
+            let Ok(entry) = entry else {
+                continue;
+            };
+            // The walk starts with the source directory itself
+            if entry.depth() == 0 {
+                continue;
+            }
+            let path = entry.path();
+            let Ok(rel_path) = path.strip_prefix(&self.source_dir) else {
+                continue;
+            };
+
+            // Symlinks to directories match like directories but are not walked into
+            let is_dir = if entry.path_is_symlink() {
+                path.is_dir()
+            } else {
+                entry.file_type().is_dir()
+            };
+            for query in &mut self.queries {
+                query.offer(rel_path, is_dir, &tracked_files);
+            }
+
+            if entry.file_type().is_dir() {
+                if self.descends(rel_path, entry.depth(), path, &mut ignores) {
+                    ignores.enter(rel_path, path);
+                } else {
+                    entries.skip_current_dir();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether any query can match below the directory at `rel_path`.
+    ///
+    /// Queries that cannot do not see the contents of the directory, even if the walk
+    /// goes into it for another query.
+    fn descends(
+        &mut self,
+        rel_path: &Path,
+        depth: usize,
+        path: &Path,
+        ignores: &mut IgnoreStack,
+    ) -> bool {
+        let reaches: Vec<Reach> = self
+            .queries
+            .iter()
+            .map(|query| query.reach(rel_path, depth))
+            .collect();
+        let ignored = reaches.contains(&Reach::Pattern) && ignores.is_ignored(rel_path, path);
+
+        let mut descends = false;
+        for (query, reach) in self.queries.iter_mut().zip(reaches) {
+            match reach {
+                Reach::Prefix => descends = true,
+                Reach::Pattern if !ignored => descends = true,
+                _ => {
+                    query
+                        .skipped_dir
+                        .get_or_insert_with(|| rel_path.to_path_buf());
+                }
+            }
+        }
+        descends
+    }
+}
+
+/// Which entry a query expands.
+#[derive(Debug, Clone, Copy)]
+enum Owner {
+    Link(usize),
+    Copy(usize),
+    IncludeFile,
+}
+
+/// How a query reaches into a directory.
+#[derive(Debug, PartialEq)]
+enum Reach {
+    /// The directory is on the literal prefix of a pattern
+    Prefix,
+    /// The directory is below the literal prefix of a pattern
+    Pattern,
+    /// Nothing below the directory can match
+    None,
+}
+
+enum Matcher {
+    Glob(GlobMatcher),
+    IncludeFile(Gitignore),
+}
+
+/// The patterns of one entry, matched during a [`Walk`].
+struct Query {
+    owner: Owner,
+    matcher: Matcher,
+    /// Literal prefixes of the patterns; nothing outside them can match
+    prefixes: Vec<PathBuf>,
+    /// Number of path components of the deepest possible match, None if unbounded
+    max_depth: Option<usize>,
+    ignore_tracked: bool,
+    /// Directory whose contents are not matched: it was matched itself, or nothing
+    /// below it can match
+    skipped_dir: Option<PathBuf>,
+    matches: Vec<PathBuf>,
+}
+
+impl Query {
+    /// Query for a link or copy entry with a glob source.
+    fn glob(owner: Owner, pattern: &Path, ignore_tracked: bool) -> Result<Query> {
+        let source_str = pattern.to_string_lossy();
+
+        // Build glob matcher
+        let glob = globset::GlobBuilder::new(&source_str)
+            .literal_separator(true)
+            .build()
+            .map_err(|e| Error::ConfigValidation {
+                message: format!("Invalid glob pattern '{}': {}", source_str, e),
+            })?;
+
+        // `**` spans any number of directories, and so may a `{a,b/c}` alternative
+        let max_depth = (!source_str.contains("**") && !source_str.contains('{'))
+            .then(|| pattern.components().count());
+
+        Ok(Query {
+            owner,
+            matcher: Matcher::Glob(glob.compile_matcher()),
+            prefixes: vec![literal_prefix(&source_str)],
+            max_depth,
+            ignore_tracked,
+            skipped_dir: None,
+            matches: Vec::new(),
+        })
+    }
+
+    /// Query for the files listed in the include file.
+    ///
+    /// The file uses gitignore syntax, including `!` to exclude files again. Tracked
+    /// files are left out: they are already checked out in every worktree. A missing
+    /// default include file matches nothing, so there is no query for it.
+    ///
+    /// All lines form one query: an ignored directory named by one line is searched
+    /// for the other lines too.
+    fn include_file(include_file: &IncludeFile, source_dir: &Path) -> Result<Option<Query>> {
+        let path = match &include_file.path {
+            Some(path) => {
+                let path = source_dir.join(path);
+                if !path.is_file() {
+                    return Err(Error::SourceNotFound {
+                        path: path.to_string_lossy().to_string(),
+                    });
+                }
+                path
+            }
+            None => match config::INCLUDE_FILES
+                .iter()
+                .map(|name| source_dir.join(name))
+                .find(|path| path.is_file())
+            {
+                Some(path) => path,
+                None => return Ok(None),
+            },
+        };
+
+        let content = fs::read_to_string(&path)?;
+        let mut builder = GitignoreBuilder::new(source_dir);
+        let mut prefixes = Vec::new();
+        for line in content.trim_start_matches('\u{feff}').lines() {
+            builder
+                .add_line(Some(path.clone()), line)
+                .map_err(|e| Error::ConfigValidation {
+                    message: format!("Invalid include file '{}': {}", path.display(), e),
+                })?;
+            prefixes.extend(include_prefix(line));
+        }
+        let patterns = builder.build().map_err(|e| Error::ConfigValidation {
+            message: format!("Invalid include file '{}': {}", path.display(), e),
+        })?;
+
+        Ok(Some(Query {
+            owner: Owner::IncludeFile,
+            matcher: Matcher::IncludeFile(patterns),
+            prefixes,
+            max_depth: None,
+            ignore_tracked: true,
+            skipped_dir: None,
+            matches: Vec::new(),
+        }))
+    }
+
+    /// Match the walk entry at `rel_path`.
+    fn offer(&mut self, rel_path: &Path, is_dir: bool, tracked_files: &HashSet<PathBuf>) {
+        // Entries come depth first, so once outside the skipped directory the walk
+        // never returns into it
+        if let Some(dir) = &self.skipped_dir {
+            if rel_path.starts_with(dir) {
+                return;
+            }
+            self.skipped_dir = None;
+        }
+
+        let is_match = match &self.matcher {
+            Matcher::Glob(glob) => glob.is_match(rel_path),
+            Matcher::IncludeFile(patterns) => patterns.matched(rel_path, is_dir).is_ignore(),
+        };
+        // Skip if it's tracked and ignore_tracked is true
+        if !is_match || (self.ignore_tracked && tracked_files.contains(rel_path)) {
+            return;
+        }
+
+        if is_dir {
+            self.skipped_dir = Some(rel_path.to_path_buf());
+        }
+        self.matches.push(rel_path.to_path_buf());
+    }
+
+    /// How far this query reaches into the directory at `rel_path`, `depth` components
+    /// below the source directory.
+    fn reach(&self, rel_path: &Path, depth: usize) -> Reach {
+        if self
+            .skipped_dir
+            .as_ref()
+            .is_some_and(|dir| rel_path.starts_with(dir))
+            || self.max_depth.is_some_and(|max_depth| depth >= max_depth)
+        {
+            return Reach::None;
+        }
+        let mut reach = Reach::None;
+        for prefix in &self.prefixes {
+            if prefix.starts_with(rel_path) {
+                return Reach::Prefix;
+            }
+            if rel_path.starts_with(prefix) {
+                reach = Reach::Pattern;
+            }
+        }
+        reach
+    }
+}
+
+/// Leading components of a glob pattern without glob syntax.
+///
+/// `fixtures/*.json` gives `fixtures`, `*.json` an empty path.
+fn literal_prefix(pattern: &str) -> PathBuf {
+    pattern
+        .split('/')
+        .take_while(|component| !component.contains(['*', '?', '[', '{', '\\']))
+        .collect()
+}
+
+/// Literal prefix of a line of an include file, None for lines that add no files
+/// (blank lines, comments and `!` exceptions).
+///
+/// As in gitignore, a pattern without a slash other than a trailing one matches at
+/// any depth, so its prefix is empty.
+fn include_prefix(line: &str) -> Option<PathBuf> {
+    let pattern = line.trim_end();
+    if pattern.is_empty() || pattern.starts_with('#') || pattern.starts_with('!') {
+        return None;
+    }
+    let pattern = pattern.trim_end_matches('/');
+    if !pattern.contains('/') {
+        return Some(PathBuf::new());
+    }
+    Some(literal_prefix(pattern.trim_start_matches('/')))
+}
+
+/// Gitignore rules of the directories the walk is in, innermost last.
+#[derive(Default)]
+struct IgnoreStack {
+    rules: Vec<(PathBuf, Gitignore)>,
+}
+
+impl IgnoreStack {
+    /// Read the rules of a directory the walk goes into.
+    ///
+    /// For the source directory itself this includes `.git/info/exclude`.
+    fn enter(&mut self, rel_path: &Path, path: &Path) {
+        let mut files = Vec::new();
+        if rel_path.as_os_str().is_empty() {
+            files.push(path.join(".git/info/exclude"));
+        }
+        files.push(path.join(".gitignore"));
+
+        let mut builder = GitignoreBuilder::new(path);
+        let mut found = false;
+        for file in files.iter().filter(|file| file.is_file()) {
+            // Invalid lines are skipped; the remaining rules still apply
+            let _ = builder.add(file);
+            found = true;
+        }
+        if found && let Ok(rules) = builder.build() {
+            self.leave(rel_path);
+            self.rules.push((rel_path.to_path_buf(), rules));
+        }
+    }
+
+    /// Whether the directory at `rel_path` is ignored by the rules of its parents.
+    fn is_ignored(&mut self, rel_path: &Path, path: &Path) -> bool {
+        self.leave(rel_path);
+        // Rules of deeper directories take precedence
+        for (_, rules) in self.rules.iter().rev() {
+            match rules.matched(path, true) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
+        }
+        false
+    }
+
+    /// Drop the rules of directories that are not parents of `rel_path`.
+    fn leave(&mut self, rel_path: &Path) {
+        while self
+            .rules
+            .last()
+            .is_some_and(|(dir, _)| dir == rel_path || !rel_path.starts_with(dir))
+        {
+            self.rules.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_literal_prefix() {
+        assert_eq!(literal_prefix("fixtures/*.json"), PathBuf::from("fixtures"));
+        assert_eq!(literal_prefix("a/b/file?.txt"), PathBuf::from("a/b"));
+        assert_eq!(literal_prefix("*.json"), PathBuf::new());
+        assert_eq!(literal_prefix("**/node_modules"), PathBuf::new());
+        assert_eq!(literal_prefix("{a,b}/c"), PathBuf::new());
+    }
+
+    #[test]
+    fn test_include_prefix() {
+        assert_eq!(include_prefix(".env*"), Some(PathBuf::new()));
+        assert_eq!(include_prefix("node_modules/"), Some(PathBuf::new()));
+        assert_eq!(
+            include_prefix("/config/*.local"),
+            Some(PathBuf::from("config"))
+        );
+        assert_eq!(
+            include_prefix("build/generated.h"),
+            Some(PathBuf::from("build/generated.h"))
+        );
+        assert_eq!(include_prefix("**/secrets.json"), Some(PathBuf::new()));
+        assert_eq!(include_prefix("!.env.example"), None);
+        assert_eq!(include_prefix("# comment"), None);
+        assert_eq!(include_prefix(""), None);
+    }
+
+    #[test]
+    fn test_reach() {
+        let query = Query::glob(Owner::Link(0), Path::new("a/b/*.txt"), false).unwrap();
+        assert_eq!(query.reach(Path::new("a"), 1), Reach::Prefix);
+        assert_eq!(query.reach(Path::new("a/b"), 2), Reach::Prefix);
+        assert_eq!(query.reach(Path::new("a/b/c"), 3), Reach::None);
+        assert_eq!(query.reach(Path::new("other"), 1), Reach::None);
+
+        let query = Query::glob(Owner::Link(0), Path::new("a/**/*.txt"), false).unwrap();
+        assert_eq!(query.reach(Path::new("a/b/c"), 3), Reach::Pattern);
+        assert_eq!(query.reach(Path::new("other"), 1), Reach::None);
+    }
+}
+
+#[cfg(all(test, feature = "impure-test"))]
+#[allow(clippy::unwrap_used)]
+mod impure_tests {
+    use super::*;
+    use crate::vcs;
+
+    fn sources(repo_root: &Path) -> SourceContext {
+        SourceContext {
+            repo_root: repo_root.to_path_buf(),
+            main_worktree_path: repo_root.to_path_buf(),
+            repository: "repo".to_string(),
+        }
+    }
+
+    fn link(source: &str) -> Link {
+        Link {
+            source: PathBuf::from(source),
+            source_root: Default::default(),
+            target: PathBuf::from(source),
+            on_conflict: None,
+            description: None,
+            ignore_tracked: false,
+            when: None,
+            global: false,
+        }
+    }
+
+    /// Sorted sources each link entry of `config` expands to.
+    fn expand_sources(config: &Config, repo_root: &Path) -> Vec<Vec<PathBuf>> {
+        let expanded = expand_entries(config, &sources(repo_root), &vcs::GitProvider).unwrap();
+        expanded
+            .links
+            .into_iter()
+            .map(|links| {
+                let mut sources: Vec<_> = links.into_iter().map(|l| l.source).collect();
+                sources.sort();
+                sources
+            })
+            .collect()
+    }
+
+    fn git(repo_root: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(args)
+            .current_dir(repo_root)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    fn create_file(repo_root: &Path, path: &str) {
+        let path = repo_root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "content").unwrap();
+    }
+
+    #[test]
+    fn test_expand_link_no_glob() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo_root = temp_dir.path();
+        create_file(repo_root, "test.txt");
+
+        let config = Config {
+            link: vec![link("test.txt")],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            expand_sources(&config, repo_root),
+            vec![vec![PathBuf::from("test.txt")]]
+        );
+    }
+
+    #[test]
+    fn test_expand_link_with_glob() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo_root = temp_dir.path();
+        create_file(repo_root, "fixtures/file1.txt");
+        create_file(repo_root, "fixtures/file2.txt");
+        create_file(repo_root, "fixtures/data.json");
+
+        let config = Config {
+            link: vec![link("fixtures/*.txt")],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            expand_sources(&config, repo_root),
+            vec![vec![
+                PathBuf::from("fixtures/file1.txt"),
+                PathBuf::from("fixtures/file2.txt")
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_expand_link_ignore_tracked() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo_root = temp_dir.path();
+        git(repo_root, &["init", "-q"]);
+        create_file(repo_root, "fixtures/tracked.txt");
+        create_file(repo_root, "fixtures/untracked.txt");
+        git(repo_root, &["add", "fixtures/tracked.txt"]);
+
+        let config = Config {
+            link: vec![Link {
+                ignore_tracked: true,
+                ..link("fixtures/*.txt")
+            }],
+            ..Default::default()
+        };
+
+        // Should only include untracked file
+        assert_eq!(
+            expand_sources(&config, repo_root),
+            vec![vec![PathBuf::from("fixtures/untracked.txt")]]
+        );
+    }
+
+    #[test]
+    fn test_expand_link_with_glob_matching_directory() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo_root = temp_dir.path();
+        create_file(repo_root, "dir1/file.txt");
+        create_file(repo_root, "dir2/file.txt");
+        create_file(repo_root, "other/file.txt");
+
+        let config = Config {
+            link: vec![link("dir*")],
+            ..Default::default()
+        };
+
+        // Should return only the directories, not their contents
+        assert_eq!(
+            expand_sources(&config, repo_root),
+            vec![vec![PathBuf::from("dir1"), PathBuf::from("dir2")]]
+        );
+    }
+
+    #[test]
+    fn test_expand_copy_with_glob() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo_root = temp_dir.path();
+        create_file(repo_root, "config/app.local.json");
+        create_file(repo_root, "config/db.local.json");
+        create_file(repo_root, "config/app.json");
+
+        let config = Config {
+            copy: vec![Copy {
+                source: PathBuf::from("config/*.local.json"),
+                source_root: Default::default(),
+                target: PathBuf::from("config/*.local.json"),
+                on_conflict: None,
+                description: None,
+                render: false,
+                mode: Default::default(),
+                ignore_tracked: false,
+                include: Vec::new(),
+                exclude: Vec::new(),
+                preserve: Vec::new(),
+                max_size: None,
+                when: None,
+                global: false,
+            }],
+            ..Default::default()
+        };
+        let expanded = expand_entries(&config, &sources(repo_root), &vcs::GitProvider).unwrap();
+
+        let mut targets: Vec<_> = expanded.copies[0]
+            .iter()
+            .map(|c| c.target.clone())
+            .collect();
+        targets.sort();
+        assert_eq!(
+            targets,
+            vec![
+                PathBuf::from("config/app.local.json"),
+                PathBuf::from("config/db.local.json")
+            ]
+        );
+        assert!(expanded.copies[0].iter().all(|c| c.source == c.target));
+    }
+
+    #[test]
+    fn test_expand_entries_share_walk() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo_root = temp_dir.path();
+        create_file(repo_root, "fixtures/a.txt");
+        create_file(repo_root, "fixtures/b.json");
+        create_file(repo_root, "fixtures/nested/c.txt");
+        create_file(repo_root, "other/d.txt");
+
+        let config = Config {
+            link: vec![link("fixtures/*.txt"), link("fixtures/*"), link("**/*.txt")],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            expand_sources(&config, repo_root),
+            vec![
+                vec![PathBuf::from("fixtures/a.txt")],
+                vec![
+                    PathBuf::from("fixtures/a.txt"),
+                    PathBuf::from("fixtures/b.json"),
+                    PathBuf::from("fixtures/nested"),
+                ],
+                vec![
+                    PathBuf::from("fixtures/a.txt"),
+                    PathBuf::from("fixtures/nested/c.txt"),
+                    PathBuf::from("other/d.txt"),
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_expand_entries_skip_ignored_directories() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo_root = temp_dir.path();
+        fs::write(repo_root.join(".gitignore"), "node_modules/\n").unwrap();
+        create_file(repo_root, "app/.env.local");
+        create_file(repo_root, "app/node_modules/pkg/.env.local");
+        create_file(repo_root, "node_modules/pkg/.env.local");
+
+        let config = Config {
+            link: vec![link("**/.env.local"), link("node_modules/pkg/.env*")],
+            ..Default::default()
+        };
+
+        // Ignored directories are only walked when the pattern names them
+        assert_eq!(
+            expand_sources(&config, repo_root),
+            vec![
+                vec![PathBuf::from("app/.env.local")],
+                vec![PathBuf::from("node_modules/pkg/.env.local")],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_expand_include_file_in_ignored_directory() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo_root = temp_dir.path();
+        git(repo_root, &["init", "-q"]);
+        fs::write(repo_root.join(".gitignore"), "build/\nnode_modules/\n").unwrap();
+        fs::write(
+            repo_root.join(".worktreeinclude"),
+            "*.local\nbuild/generated.h\n",
+        )
+        .unwrap();
+        create_file(repo_root, "app.local");
+        create_file(repo_root, "build/generated.h");
+        create_file(repo_root, "node_modules/cache.local");
+
+        let config = Config {
+            include_file: Some(IncludeFile {
+                path: None,
+                mode: config::IncludeMode::Link,
+            }),
+            ..Default::default()
+        };
+        let expanded = expand_entries(&config, &sources(repo_root), &vcs::GitProvider).unwrap();
+
+        let mut targets: Vec<_> = expanded
+            .included_links
+            .into_iter()
+            .map(|l| l.target)
+            .collect();
+        targets.sort();
+        assert_eq!(
+            targets,
+            vec![
+                PathBuf::from("app.local"),
+                PathBuf::from("build/generated.h")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_expand_include_file_leaves_glob_matches_to_entry() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo_root = temp_dir.path();
+        git(repo_root, &["init", "-q"]);
+        fs::write(repo_root.join(".worktreeinclude"), "*.local\n").unwrap();
+        create_file(repo_root, "app.local");
+        create_file(repo_root, "config/db.local");
+
+        let config = Config {
+            copy: vec![Copy {
+                source: PathBuf::from("config/*.local"),
+                source_root: Default::default(),
+                target: PathBuf::from("config/*.local"),
+                on_conflict: None,
+                description: None,
+                render: false,
+                mode: Default::default(),
+                ignore_tracked: false,
+                include: Vec::new(),
+                exclude: Vec::new(),
+                preserve: Vec::new(),
+                max_size: None,
+                when: None,
+                global: false,
+            }],
+            include_file: Some(IncludeFile {
+                path: None,
+                mode: config::IncludeMode::Link,
+            }),
+            ..Default::default()
+        };
+        let expanded = expand_entries(&config, &sources(repo_root), &vcs::GitProvider).unwrap();
+
+        let targets: Vec<_> = expanded
+            .included_links
+            .into_iter()
+            .map(|l| l.target)
+            .collect();
+        assert_eq!(targets, vec![PathBuf::from("app.local")]);
+        assert_eq!(expanded.copies[0].len(), 1);
+    }
+}
@@ -1,7 +1,11 @@
 use crate::cli::{ConfigCommand, ConfigFormatArg};
+use crate::command::add::contains_glob_pattern;
+use crate::command::setup::worktree_config;
 use crate::config;
 use crate::error::{Error, Result};
-use crate::vcs;
+use crate::state::{self, ManifestOp};
+use crate::trust;
+use crate::vcs::{self, VcsProvider};
 
 use std::fs;
 use std::path::Path;
@@ -52,9 +56,98 @@ fn validate() -> Result<()> {
     config::load(&repo_root)?;
 
     println!("Config is valid");
+
+    report_outdated_setups(provider.as_ref(), &repo_root)
+}
+
+/// Report worktrees whose setup manifest was recorded with a different configuration.
+///
+/// Lists what kabu created there that the current configuration no longer mentions.
+/// Worktrees are compared with the configuration of the profile they were set up with.
+fn report_outdated_setups(provider: &dyn VcsProvider, repo_root: &Path) -> Result<()> {
+    let cfg = config::load_merged(repo_root)?;
+    let main_worktree_path = provider.main_workspace_path_for(repo_root)?;
+
+    let mut outdated = Vec::new();
+    for ws in provider.list_workspaces()? {
+        if ws.is_main {
+            continue;
+        }
+        // Unreadable state only affects this report, not the config itself
+        if let Ok(Some(manifest)) = state::read_manifest(&main_worktree_path, &ws.path) {
+            let ws_cfg = worktree_config(&cfg, &main_worktree_path, &ws.path);
+            if manifest.config_hash != trust::compute_hash(&main_worktree_path, &ws_cfg)? {
+                outdated.push((manifest, ws_cfg));
+            }
+        }
+    }
+
+    if outdated.is_empty() {
+        return Ok(());
+    }
+
+    println!();
+    println!(
+        "Setup is out of date in {} {}(s). Run `kabu setup --all` to re-apply:",
+        outdated.len(),
+        provider.workspace_type()
+    );
+    for (manifest, ws_cfg) in &outdated {
+        println!("  {}", manifest.worktree_path.display());
+        for entry in unconfigured_entries(manifest, ws_cfg) {
+            println!(
+                "    no longer configured: {} ({})",
+                entry.target.display(),
+                manifest_op_name(entry.op)
+            );
+        }
+    }
+
     Ok(())
 }
 
+/// Manifest entries kabu owns whose target is not produced by the current config.
+///
+/// Link (copy) entries are not checked when glob link (copy) sources are configured,
+/// since their targets depend on the files present at setup time.
+fn unconfigured_entries<'a>(
+    manifest: &'a state::Manifest,
+    cfg: &config::Config,
+) -> Vec<&'a state::ManifestEntry> {
+    let has_glob_links = cfg
+        .link
+        .iter()
+        .any(|link| contains_glob_pattern(&link.source));
+    let has_glob_copies = cfg
+        .copy
+        .iter()
+        .any(|copy| contains_glob_pattern(&copy.source));
+
+    manifest
+        .entries
+        .iter()
+        .filter(|entry| entry.is_owned())
+        .filter(|entry| match entry.op {
+            ManifestOp::Mkdir => !cfg.mkdir.iter().any(|m| m.path == entry.target),
+            ManifestOp::Link => {
+                !has_glob_links && !cfg.link.iter().any(|l| l.target == entry.target)
+            }
+            ManifestOp::Copy | ManifestOp::Render => {
+                !has_glob_copies && !cfg.copy.iter().any(|c| c.target == entry.target)
+            }
+        })
+        .collect()
+}
+
+fn manifest_op_name(op: ManifestOp) -> &'static str {
+    match op {
+        ManifestOp::Mkdir => "mkdir",
+        ManifestOp::Link => "link",
+        ManifestOp::Copy => "copy",
+        ManifestOp::Render => "copy, rendered",
+    }
+}
+
 /// Get a configuration value by key.
 fn get_config_value(key: &str) -> Result<()> {
     let provider = vcs::get_provider()?;
@@ -307,10 +400,16 @@ fn repo_config_template_yaml() -> String {
 #   - source: config.template.json
 #     target: config.json
 
+# Link or copy the files listed in .worktreeinclude (or .kabu/include)
+# include_file:
+#   mode: copy
+
 # Hooks (requires trust via `kabu trust`)
 # hooks:
 #   pre_add:
 #     - command: echo "Creating {{{{worktree_name}}}}"
+#   post_create:
+#     - command: git lfs pull
 #   post_add:
 #     - command: npm install
 #       description: Install dependencies
@@ -318,6 +417,16 @@ fn repo_config_template_yaml() -> String {
 #     - command: echo "Removing {{{{worktree_name}}}}"
 #   post_remove:
 #     - command: ./scripts/cleanup.sh
+
+# Profiles: extra hooks/files selected with `kabu add --profile NAME`
+# default_profile: full
+# profiles:
+#   full:
+#     hooks:
+#       post_add:
+#         - command: npm install
+#   review:
+#     description: Read-only review checkout
 "#,
         schema_url()
     )
@@ -364,11 +473,18 @@ fn repo_config_template_toml() -> String {
 # source = "config.template.json"
 # target = "config.json"
 
+# Link or copy the files listed in .worktreeinclude (or .kabu/include)
+# [include_file]
+# mode = "copy"
+
 # Hooks (requires trust via `kabu trust`)
 # [hooks]
 # [[hooks.pre_add]]
 # command = "echo 'Creating {{worktree_name}}'"
 #
+# [[hooks.post_create]]
+# command = "git lfs pull"
+#
 # [[hooks.post_add]]
 # command = "npm install"
 # description = "Install dependencies"
@@ -378,6 +494,14 @@ fn repo_config_template_toml() -> String {
 #
 # [[hooks.post_remove]]
 # command = "./scripts/cleanup.sh"
+
+# Profiles: extra hooks/files selected with `kabu add --profile NAME`
+# (default_profile = "full" belongs at the top, before any table)
+# [[profiles.full.hooks.post_add]]
+# command = "npm install"
+#
+# [profiles.review]
+# description = "Read-only review checkout"
 "#
     .to_string()
 }
@@ -397,7 +521,7 @@ fn global_config_template_yaml() -> String {
 # Global kabu configuration
 # This file applies to all repositories and can be overridden by .kabu/config.yaml or .kabu/config.toml.
 
-# Allowed keys: on_conflict, auto_cd, worktree, ui, hooks.hook_shell
+# Allowed keys: everything except profiles and default_profile
 
 # on_conflict: backup  # abort, skip, overwrite, backup
 
@@ -437,8 +561,16 @@ fn global_config_template_yaml() -> String {
 #     warning: default
 #     error: default
 
+# Applied in every repository, before the repository's own entries and hooks.
+# Hooks here are trusted once with `kabu trust --global`.
+# link:
+#   - source: .claude
+#     on_conflict: skip
+
 # hooks:
 #   hook_shell: "pwsh"  # Windows-only: pwsh, powershell, bash, cmd, wsl
+#   post_add:
+#     - command: "code {{{{worktree_path}}}}"
  "#,
         schema_url()
     )
@@ -452,7 +584,7 @@ fn global_config_template_yaml() -> String {
 # Global kabu configuration
 # This file applies to all repositories and can be overridden by .kabu/config.yaml or .kabu/config.toml.
 
-# Allowed keys: on_conflict, auto_cd, worktree, ui
+# Allowed keys: everything except profiles and default_profile
 
 # on_conflict: backup  # abort, skip, overwrite, backup
 
@@ -491,6 +623,16 @@ fn global_config_template_yaml() -> String {
 #     selection_fg: default
 #     warning: default
 #     error: default
+
+# Applied in every repository, before the repository's own entries and hooks.
+# Hooks here are trusted once with `kabu trust --global`.
+# link:
+#   - source: .claude
+#     on_conflict: skip
+
+# hooks:
+#   post_add:
+#     - command: "code {{{{worktree_path}}}}"
  "#,
         schema_url()
     )
@@ -501,7 +643,7 @@ fn global_config_template_toml() -> String {
     r#"# Global kabu configuration
 # This file applies to all repositories and can be overridden by .kabu/config.yaml or .kabu/config.toml.
 
-# Allowed keys: on_conflict, auto_cd, worktree, ui, hooks.hook_shell
+# Allowed keys: everything except profiles and default_profile
 
 # on_conflict = "backup"  # abort, skip, overwrite, backup
 
@@ -542,8 +684,17 @@ fn global_config_template_toml() -> String {
 # warning = "default"
 # error = "default"
 
+# Applied in every repository, before the repository's own entries and hooks.
+# Hooks here are trusted once with `kabu trust --global`.
+# [[link]]
+# source = ".claude"
+# on_conflict = "skip"
+
 # [hooks]
 # hook_shell = "pwsh"  # Windows-only: pwsh, powershell, bash, cmd, wsl
+#
+# [[hooks.post_add]]
+# command = "code {{worktree_path}}"
  "#
     .to_string()
 }
@@ -553,7 +704,7 @@ fn global_config_template_toml() -> String {
     r#"# Global kabu configuration
 # This file applies to all repositories and can be overridden by .kabu/config.yaml or .kabu/config.toml.
 
-# Allowed keys: on_conflict, auto_cd, worktree, ui
+# Allowed keys: everything except profiles and default_profile
 
 # on_conflict = "backup"  # abort, skip, overwrite, backup
 
@@ -593,6 +744,15 @@ fn global_config_template_toml() -> String {
 # selection_fg = "default"
 # warning = "default"
 # error = "default"
+
+# Applied in every repository, before the repository's own entries and hooks.
+# Hooks here are trusted once with `kabu trust --global`.
+# [[link]]
+# source = ".claude"
+# on_conflict = "skip"
+
+# [[hooks.post_add]]
+# command = "code {{worktree_path}}"
  "#
     .to_string()
 }
@@ -0,0 +1,410 @@
+//! Doctor command implementation.
+//!
+//! Walks every worktree/workspace and the configured mkdir/link/copy entries looking
+//! for things that broke after the fact: dangling symlinks (e.g. after the main
+//! repository moved), deleted link sources, worktrees git/jj still lists whose
+//! directory is gone, and trust entries of repositories that no longer exist.
+//! With `--fix`, symlinks are re-created and dead metadata is pruned.
+
+use crate::cli::DoctorArgs;
+use crate::color::{self, ColorConfig};
+use crate::command::add::{contains_glob_pattern, expand_entries, is_linked_to, source_context};
+use crate::command::trust_check::{TrustHint, load_config_with_trust_check};
+use crate::config::{self, Config, Copy, Link, SourceContext, When, WhenContext};
+use crate::error::{Error, Result};
+use crate::operation;
+use crate::output::Output;
+use crate::state::{self, ManifestOp};
+use crate::trust;
+use crate::vcs::{self, VcsKind, VcsProvider, WorkspaceInfo};
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A problem found by `kabu doctor`.
+struct Problem {
+    path: PathBuf,
+    message: String,
+    hint: String,
+    fix: Option<Fix>,
+}
+
+/// Automatic repair for a [`Problem`].
+enum Fix {
+    /// Point the symlink at `source`, replacing whatever is at `target`
+    Relink { source: PathBuf, target: PathBuf },
+    /// Delete a kabu-created symlink whose source is gone
+    RemoveLink { target: PathBuf },
+    /// Drop VCS metadata and kabu state of a deleted worktree
+    PruneWorktree { workspace: WorkspaceInfo },
+    /// Delete trust entries of a repository that no longer exists
+    ForgetTrust { main_worktree_path: PathBuf },
+}
+
+pub(crate) fn run(args: DoctorArgs, color: ColorConfig) -> Result<()> {
+    let output = Output::new(args.quiet, color);
+
+    let provider = vcs::get_provider()?;
+
+    if !provider.is_inside_repo() {
+        return Err(Error::NotInAnyRepo);
+    }
+
+    let repo_root = provider.repository_root()?;
+
+    // Get main workspace path for trust operations
+    let main_worktree_path = provider.main_workspace_path_for(&repo_root)?;
+
+    // Doctor never runs hooks; trust is only required for sources outside the repository,
+    // which `--fix` may link to
+    let config =
+        load_config_with_trust_check(&repo_root, &main_worktree_path, false, TrustHint::None)?;
+    color::set_cli_theme(&config.ui.colors);
+
+    let worktrees = provider.list_workspaces()?;
+
+    // Sources come from the main worktree, also when run from a linked one; conditional
+    // sources are only expected where their `when:` clause holds
+    let repo_ctx = WhenContext {
+        vcs: provider.name(),
+        branch: worktrees
+            .iter()
+            .find(|ws| ws.path == main_worktree_path)
+            .and_then(|ws| ws.branch.as_deref()),
+        base_dir: &main_worktree_path,
+    };
+    let sources = source_context(&main_worktree_path, provider.as_ref())?;
+    let mut problems = check_sources(&config, &sources, &repo_ctx);
+    let (links, copies) = expand_sources(&config, &sources, provider.as_ref())?;
+    for ws in worktrees.iter().filter(|ws| !ws.is_main) {
+        if ws.path.exists() {
+            let ctx = WhenContext {
+                vcs: provider.name(),
+                branch: ws.branch.as_deref(),
+                base_dir: &ws.path,
+            };
+            problems.extend(check_worktree(
+                ws,
+                &config,
+                &links,
+                &copies,
+                &ctx,
+                &sources,
+                &main_worktree_path,
+            ));
+        } else {
+            problems.push(missing_worktree_problem(ws, provider.kind()));
+        }
+    }
+    problems.extend(check_trust_entries()?);
+
+    if problems.is_empty() {
+        output.results_success("No problems found");
+        return Ok(());
+    }
+
+    let mut remaining = 0;
+    for problem in &problems {
+        output.doctor_problem(&problem.path, &problem.message, &problem.hint);
+        match (&problem.fix, args.fix) {
+            (Some(fix), true) => match apply_fix(fix, &main_worktree_path, provider.as_ref()) {
+                Ok(message) => output.doctor_fixed(&problem.path, &message),
+                Err(e) => {
+                    output.safety_warning(&problem.path, &format!("fix failed: {e}"));
+                    remaining += 1;
+                }
+            },
+            _ => remaining += 1,
+        }
+    }
+
+    let fixable = problems.iter().filter(|p| p.fix.is_some()).count();
+    if !args.fix && fixable > 0 {
+        output.list("");
+        output.list(&format!(
+            "Run `kabu doctor --fix` to repair {fixable} of them automatically."
+        ));
+    }
+
+    if remaining == 0 {
+        output.results_success(&format!("Fixed {} problem(s)", problems.len()));
+        Ok(())
+    } else {
+        Err(Error::DoctorProblemsFound { count: remaining })
+    }
+}
+
+/// Whether an entry applies, i.e. it has no `when:` clause or the clause holds.
+fn applies(when: Option<&When>, ctx: &WhenContext) -> bool {
+    when.is_none_or(|when| when.matches(ctx))
+}
+
+/// Report configured link/copy sources that are missing.
+///
+/// Entries of the global config are left out: they apply to every repository, and
+/// `kabu add` skips them where their source is missing.
+fn check_sources(config: &Config, sources: &SourceContext, ctx: &WhenContext) -> Vec<Problem> {
+    let links = config
+        .link
+        .iter()
+        .filter(|link| !link.global && !contains_glob_pattern(&link.source))
+        .filter(|link| applies(link.when.as_ref(), ctx))
+        .map(|link| ("link", &link.source, link.source_path(sources)));
+    let copies = config
+        .copy
+        .iter()
+        .filter(|copy| !copy.global && !contains_glob_pattern(&copy.source))
+        .filter(|copy| applies(copy.when.as_ref(), ctx))
+        .map(|copy| ("copy", &copy.source, copy.source_path(sources)));
+
+    links
+        .chain(copies)
+        .filter_map(|(op, source, path)| Some((op, source, path.ok()?)))
+        .filter(|(_, _, path)| !path.exists())
+        .map(|(op, source, path)| Problem {
+            path,
+            message: format!("{op} source does not exist"),
+            hint: format!(
+                "restore the file or remove the `{op}:` entry for {} from the config",
+                source.display()
+            ),
+            fix: None,
+        })
+        .collect()
+}
+
+/// Expand the configured links and copies, including those of the include file.
+///
+/// Expansions whose source is missing are dropped; missing non-glob sources are
+/// already reported by [`check_sources`].
+fn expand_sources(
+    config: &Config,
+    sources: &SourceContext,
+    provider: &dyn VcsProvider,
+) -> Result<(Vec<Link>, Vec<Copy>)> {
+    let expanded = expand_entries(config, sources, provider)?;
+    let mut links = Vec::new();
+    for link in expanded.links.into_iter().flatten() {
+        if link.source_path(sources)?.exists() {
+            links.push(link);
+        }
+    }
+    links.extend(expanded.included_links);
+    let mut copies = Vec::new();
+    for copy in expanded.copies.into_iter().flatten() {
+        if copy.source_path(sources)?.exists() {
+            copies.push(copy);
+        }
+    }
+    copies.extend(expanded.included_copies);
+    Ok((links, copies))
+}
+
+fn missing_worktree_problem(ws: &WorkspaceInfo, kind: VcsKind) -> Problem {
+    let (hint, fix) = if ws.is_locked {
+        (
+            "the worktree is locked; run `kabu unlock` on it, then `kabu doctor --fix`".to_string(),
+            None,
+        )
+    } else {
+        let manual = match (kind, &ws.workspace_name) {
+            (VcsKind::Git, _) => "git worktree prune".to_string(),
+            (_, Some(name)) => format!("jj workspace forget {name}"),
+            (_, None) => "jj workspace forget".to_string(),
+        };
+        (
+            format!("run `kabu doctor --fix` or `{manual}` to forget it"),
+            Some(Fix::PruneWorktree {
+                workspace: ws.clone(),
+            }),
+        )
+    };
+
+    Problem {
+        path: ws.path.clone(),
+        message: format!("{} directory no longer exists", kind.workspace_type()),
+        hint,
+        fix,
+    }
+}
+
+/// Check the configured mkdir/link/copy entries and recorded symlinks of one worktree.
+///
+/// Entries whose `when:` clause does not hold in the worktree are not expected there.
+fn check_worktree(
+    ws: &WorkspaceInfo,
+    config: &Config,
+    links: &[Link],
+    copies: &[Copy],
+    ctx: &WhenContext,
+    sources: &SourceContext,
+    main_worktree_path: &Path,
+) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let setup_hint = format!("run `kabu setup {}` to re-create it", ws.path.display());
+
+    for mkdir in config
+        .mkdir
+        .iter()
+        .filter(|mkdir| applies(mkdir.when.as_ref(), ctx))
+    {
+        let target = ws.path.join(&mkdir.path);
+        if !target.exists() {
+            problems.push(Problem {
+                path: target,
+                message: "configured directory is missing".to_string(),
+                hint: setup_hint.clone(),
+                fix: None,
+            });
+        }
+    }
+
+    // Unreadable state only limits what can be checked, it is not a problem itself
+    let manifest = state::read_manifest(main_worktree_path, &ws.path)
+        .ok()
+        .flatten();
+
+    for link in links.iter().filter(|link| applies(link.when.as_ref(), ctx)) {
+        let Ok(source) = link.source_path(sources) else {
+            continue;
+        };
+        let target = ws.path.join(&link.target);
+        if is_linked_to(&target, &source) {
+            continue;
+        }
+
+        let Ok(points_to) = fs::read_link(&target) else {
+            // A regular file at the target is a conflict the user resolved by keeping it
+            if fs::symlink_metadata(&target).is_err() {
+                problems.push(Problem {
+                    path: target.clone(),
+                    message: "configured symlink is missing".to_string(),
+                    hint: "run `kabu doctor --fix` to re-create it".to_string(),
+                    fix: Some(Fix::Relink { source, target }),
+                });
+            }
+            continue;
+        };
+
+        let created_by_kabu = manifest
+            .as_ref()
+            .and_then(|m| m.entry(&link.target))
+            .is_some_and(|entry| entry.source.as_ref() == Some(&points_to));
+        let message = if !target.exists() {
+            format!("dangling symlink to {}", points_to.display())
+        } else if created_by_kabu {
+            format!("symlink points to {} instead", points_to.display())
+        } else {
+            // A symlink the user pointed somewhere else on purpose
+            continue;
+        };
+        problems.push(Problem {
+            path: target.clone(),
+            message,
+            hint: format!(
+                "run `kabu doctor --fix` to re-link it to {}",
+                source.display()
+            ),
+            fix: Some(Fix::Relink { source, target }),
+        });
+    }
+
+    for copy in copies
+        .iter()
+        .filter(|copy| applies(copy.when.as_ref(), ctx))
+    {
+        let target = ws.path.join(&copy.target);
+        if fs::symlink_metadata(&target).is_err() {
+            problems.push(Problem {
+                path: target,
+                message: "configured copy is missing".to_string(),
+                hint: setup_hint.clone(),
+                fix: None,
+            });
+        }
+    }
+
+    // Symlinks kabu created for entries no longer produced by the config, e.g. glob
+    // matches whose source file was deleted. Missing sources of configured links are
+    // reported by `check_sources` instead, so their symlinks are kept.
+    let configured: HashSet<&Path> = links
+        .iter()
+        .chain(&config.link)
+        .map(|link| link.target.as_path())
+        .collect();
+    for entry in manifest.iter().flat_map(|m| &m.entries) {
+        if entry.op != ManifestOp::Link
+            || !entry.is_owned()
+            || configured.contains(entry.target.as_path())
+        {
+            continue;
+        }
+        let target = ws.path.join(&entry.target);
+        let is_ours =
+            fs::read_link(&target).is_ok_and(|points_to| entry.source.as_ref() == Some(&points_to));
+        if is_ours && !target.exists() {
+            problems.push(Problem {
+                path: target.clone(),
+                message: "symlink source was deleted".to_string(),
+                hint: "run `kabu doctor --fix` to remove the dangling symlink".to_string(),
+                fix: Some(Fix::RemoveLink { target }),
+            });
+        }
+    }
+
+    problems
+}
+
+/// Report trust entries whose main worktree (or global config file) no longer exists.
+fn check_trust_entries() -> Result<Vec<Problem>> {
+    let mut seen = HashSet::new();
+    Ok(trust::list_trusted()?
+        .into_iter()
+        .map(|entry| entry.main_worktree_path)
+        .filter(|path| !path.exists() && seen.insert(path.clone()))
+        .map(|path| {
+            let message = if config::is_global_config_path(&path) {
+                "trusted global config no longer exists"
+            } else {
+                "trusted repository no longer exists"
+            };
+            Problem {
+                path: path.clone(),
+                message: message.to_string(),
+                hint: "run `kabu doctor --fix` to remove the stale trust entry".to_string(),
+                fix: Some(Fix::ForgetTrust {
+                    main_worktree_path: path,
+                }),
+            }
+        })
+        .collect())
+}
+
+/// Apply a fix, returning a description of what was done.
+fn apply_fix(fix: &Fix, main_worktree_path: &Path, provider: &dyn VcsProvider) -> Result<String> {
+    match fix {
+        Fix::Relink { source, target } => {
+            if fs::symlink_metadata(target).is_ok() {
+                fs::remove_file(target)?;
+            }
+            operation::create_symlink(source, target)?;
+            Ok(format!("linked to {}", source.display()))
+        }
+        Fix::RemoveLink { target } => {
+            fs::remove_file(target)?;
+            Ok("removed dangling symlink".to_string())
+        }
+        Fix::PruneWorktree { workspace } => {
+            provider.prune_workspace(workspace)?;
+            // Its state can no longer be used by anything
+            let _ = state::release_slot(main_worktree_path, &workspace.path);
+            let _ = state::remove_manifest(main_worktree_path, &workspace.path);
+            Ok(format!("forgot deleted {}", provider.workspace_type()))
+        }
+        Fix::ForgetTrust { main_worktree_path } => {
+            trust::forget_trusted(main_worktree_path)?;
+            Ok("removed stale trust entry".to_string())
+        }
+    }
+}
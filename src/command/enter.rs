@@ -0,0 +1,107 @@
+//! Enter command implementation.
+//!
+//! Runs the `on_enter` hooks for the current worktree/workspace. Shell integration
+//! calls this after `kabu cd` and `kabu add` change into a worktree/workspace.
+
+use crate::cli::EnterArgs;
+use crate::color::{ColorConfig, ColorScheme};
+use crate::command::remove::find_current_worktree;
+use crate::command::setup::{find_branch, worktree_config};
+use crate::command::trust_check::{TrustHint, is_global_trusted, load_config_with_trust_check};
+use crate::config;
+use crate::error::{Error, Result};
+use crate::hook::{self, HookEnv};
+use crate::output::Output;
+use crate::state;
+use crate::trust;
+use crate::vcs;
+
+pub(crate) fn run(args: EnterArgs, color: ColorConfig) -> Result<()> {
+    let output = Output::new(args.quiet, color);
+
+    let provider = vcs::get_provider()?;
+
+    if !provider.is_inside_repo() {
+        return Err(Error::NotInAnyRepo);
+    }
+
+    let repo_root = provider.repository_root()?;
+    let main_worktree_path = provider.main_workspace_path_for(&repo_root)?;
+
+    // Runs on every cd, so stay silent when there is nothing to do and never show
+    // the interactive trust review: a one-line warning is enough here
+    let global_config = config::load_global()?.unwrap_or_default();
+    let repo_config = config::load(&repo_root)?.unwrap_or_default();
+    let has_on_enter = !global_config.hooks.on_enter.is_empty()
+        || !repo_config.hooks.on_enter.is_empty()
+        || repo_config
+            .profiles
+            .values()
+            .any(|profile| !profile.hooks.on_enter.is_empty());
+    if !has_on_enter {
+        return Ok(());
+    }
+    if !trust::is_trusted(&main_worktree_path, &repo_config)? {
+        eprintln!(
+            "{} on_enter hooks skipped: hooks in config file are not trusted. Run 'kabu trust' to review them.",
+            ColorScheme::warning("kabu:")
+        );
+        return Ok(());
+    }
+    if !is_global_trusted(&global_config)? {
+        eprintln!(
+            "{} on_enter hooks skipped: hooks in global config file are not trusted. Run 'kabu trust --global' to review them.",
+            ColorScheme::warning("kabu:")
+        );
+        return Ok(());
+    }
+
+    let config =
+        load_config_with_trust_check(&repo_root, &main_worktree_path, true, TrustHint::None)?;
+
+    let worktrees = provider.list_workspaces()?;
+    let path = find_current_worktree(&worktrees)?;
+
+    // on_enter hooks may come from the profile this worktree was set up with
+    let config = worktree_config(&config, &main_worktree_path, &path);
+    if config.hooks.on_enter.is_empty() {
+        return Ok(());
+    }
+
+    let hook_shell = {
+        #[cfg(windows)]
+        {
+            config.hooks.hook_shell.clone()
+        }
+        #[cfg(not(windows))]
+        {
+            None
+        }
+    };
+
+    let allocation = if config.ports.is_empty() {
+        None
+    } else {
+        state::find_slot(&main_worktree_path, &path, &config.ports)?
+    };
+
+    let revision = provider.workspace_revision(&path).unwrap_or_default();
+    let hook_env = HookEnv {
+        worktree_path: path.to_string_lossy().to_string(),
+        worktree_name: path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("")
+            .to_string(),
+        branch: find_branch(&path, &worktrees),
+        repo_root: repo_root.to_string_lossy().to_string(),
+        vcs_type: provider.name().to_string(),
+        change_id: revision.change_id,
+        commit_id: revision.commit_id,
+        allocation,
+        log_dir: state::hook_log_dir(&main_worktree_path, &path).ok(),
+        hook_shell,
+    };
+
+    hook::run_on_enter(&config.hooks, &hook_env, &path, &output)
+}
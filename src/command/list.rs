@@ -1,24 +1,96 @@
 //! List worktrees/workspaces command implementation.
 //!
 //! Lists all git worktrees or jj workspaces with detailed information including
-//! branch/bookmark, commit hash, and status indicators.
+//! branch/bookmark, commit hash, and status indicators. Also supports JSON and
+//! TSV output with a stable schema shared by git and jj.
 
-use crate::cli::ListArgs;
+use crate::cli::{ListArgs, ListFormatArg};
 use crate::color::{self, ColorConfig, ColorScheme};
 use crate::config;
 use crate::error::{Error, Result};
 use crate::output::Output;
 use crate::vcs::{self, UnpushedInfo, WorkspaceInfo, WorkspaceStatus};
 
+use serde::Serialize;
+
+/// Column order for TSV output. Must match the field order of [`ListRecord`].
+const TSV_COLUMNS: &[&str] = &[
+    "path",
+    "branch",
+    "head",
+    "is_main",
+    "is_locked",
+    "workspace_name",
+    "has_uncommitted_changes",
+    "modified",
+    "deleted",
+    "untracked",
+    "has_unpushed",
+    "unpushed",
+    "upstream",
+    "lock_reason",
+    "is_prunable",
+];
+
 /// Enriched workspace info for display purposes.
 struct DisplayWorkspace {
     path: String,
-    branch: String,
+    /// Bare branch (git) or bookmark (jj) name. None if detached.
+    branch: Option<String>,
     head: String,
     status: WorkspaceStatus,
     unpushed: UnpushedInfo,
     upstream: Option<String>,
+    is_main: bool,
+    is_locked: bool,
+    lock_reason: Option<String>,
+    is_prunable: bool,
+    workspace_name: Option<String>,
+}
+
+/// Machine-readable record for `--format json|tsv`.
+///
+/// The schema is flat and identical for git and jj so that scripts do not
+/// need to special-case the VCS.
+#[derive(Debug, Serialize)]
+struct ListRecord<'a> {
+    path: &'a str,
+    branch: Option<&'a str>,
+    head: &'a str,
+    is_main: bool,
     is_locked: bool,
+    workspace_name: Option<&'a str>,
+    has_uncommitted_changes: bool,
+    modified: usize,
+    deleted: usize,
+    untracked: usize,
+    has_unpushed: bool,
+    unpushed: usize,
+    upstream: Option<&'a str>,
+    lock_reason: Option<&'a str>,
+    is_prunable: bool,
+}
+
+impl<'a> From<&'a DisplayWorkspace> for ListRecord<'a> {
+    fn from(ws: &'a DisplayWorkspace) -> Self {
+        Self {
+            path: &ws.path,
+            branch: ws.branch.as_deref(),
+            head: &ws.head,
+            is_main: ws.is_main,
+            is_locked: ws.is_locked,
+            workspace_name: ws.workspace_name.as_deref(),
+            has_uncommitted_changes: ws.status.has_uncommitted_changes,
+            modified: ws.status.modified_count,
+            deleted: ws.status.deleted_count,
+            untracked: ws.status.untracked_count,
+            has_unpushed: ws.unpushed.has_unpushed,
+            unpushed: ws.unpushed.count,
+            upstream: ws.upstream.as_deref(),
+            lock_reason: ws.lock_reason.as_deref(),
+            is_prunable: ws.is_prunable,
+        }
+    }
 }
 
 pub(crate) fn run(args: ListArgs, color: ColorConfig) -> Result<()> {
@@ -45,9 +117,21 @@ pub(crate) fn run(args: ListArgs, color: ColorConfig) -> Result<()> {
     // Pre-fetch all data before printing
     let display_data = enrich_workspaces(&workspaces, provider.as_ref())?;
 
+    match args.format {
+        ListFormatArg::Json => return print_json(&display_data, &output),
+        ListFormatArg::Tsv => {
+            print_tsv(&display_data, &output, args.header);
+            return Ok(());
+        }
+        ListFormatArg::Text => {}
+    }
+
     // Calculate max path length and max branch length for alignment
     let (max_path, max_branch) = display_data.iter().fold((0, 0), |(max_p, max_b), ws| {
-        (max_p.max(ws.path.len()), max_b.max(ws.branch.len()))
+        (
+            max_p.max(ws.path.len()),
+            max_b.max(branch_display(&ws.branch).len()),
+        )
     });
 
     if args.header {
@@ -83,17 +167,68 @@ fn enrich_workspaces(
 
         display_data.push(DisplayWorkspace {
             path: ws.path.display().to_string(),
-            branch: branch_display(&ws.branch),
+            branch: branch_name(&ws.branch),
             head: ws.head.clone(),
             status,
             unpushed,
             upstream,
+            is_main: ws.is_main,
             is_locked: ws.is_locked,
+            lock_reason: ws.lock_reason.clone(),
+            is_prunable: ws.is_prunable,
+            workspace_name: ws.workspace_name.clone(),
         });
     }
     Ok(display_data)
 }
 
+fn print_json(display_data: &[DisplayWorkspace], output: &Output) -> Result<()> {
+    let records: Vec<ListRecord> = display_data.iter().map(ListRecord::from).collect();
+    let json = serde_json::to_string_pretty(&records)
+        .map_err(|e| Error::Internal(format!("Failed to serialize worktree list: {}", e)))?;
+    output.list(&json);
+    Ok(())
+}
+
+fn print_tsv(display_data: &[DisplayWorkspace], output: &Output, header: bool) {
+    if header {
+        output.list(&TSV_COLUMNS.join("\t"));
+    }
+    for ws in display_data {
+        output.list(&tsv_line(&ListRecord::from(ws)));
+    }
+}
+
+fn tsv_line(record: &ListRecord) -> String {
+    let opt = |value: Option<&str>| value.map(tsv_escape).unwrap_or_default();
+    [
+        tsv_escape(record.path),
+        opt(record.branch),
+        tsv_escape(record.head),
+        record.is_main.to_string(),
+        record.is_locked.to_string(),
+        opt(record.workspace_name),
+        record.has_uncommitted_changes.to_string(),
+        record.modified.to_string(),
+        record.deleted.to_string(),
+        record.untracked.to_string(),
+        record.has_unpushed.to_string(),
+        record.unpushed.to_string(),
+        opt(record.upstream),
+        opt(record.lock_reason),
+        record.is_prunable.to_string(),
+    ]
+    .join("\t")
+}
+
+/// Escape characters that would break TSV row/column boundaries.
+fn tsv_escape(value: &str) -> String {
+    value
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
 fn print_header(output: &Output, max_path: usize, max_branch: usize, color: ColorConfig) {
     let path = "PATH";
     let branch = "BRANCH";
@@ -121,14 +256,15 @@ fn display_workspace(
     color: ColorConfig,
 ) {
     let short_hash = ws.head.chars().take(7).collect::<String>();
-    let status_str = format_status(&ws.status, &ws.unpushed, &ws.upstream, ws.is_locked);
+    let status_str = format_status(&ws.status, &ws.unpushed, &ws.upstream, &ws.lock());
+    let branch = branch_display(&ws.branch);
 
     let line = if color.is_enabled() {
         format!(
             "{path:<p_width$} {branch} {hash} {status}",
             path = ws.path,
             p_width = max_path,
-            branch = ColorScheme::branch(&format!("{:<width$}", ws.branch, width = max_branch)),
+            branch = ColorScheme::branch(&format!("{:<width$}", branch, width = max_branch)),
             hash = ColorScheme::hash(&format!("{:<width$}", short_hash, width = 7)),
             status = ColorScheme::dimmed(&status_str),
         )
@@ -137,7 +273,6 @@ fn display_workspace(
             "{path:<p_width$} {branch:<b_width$} {hash:<c_width$} {status}",
             path = ws.path,
             p_width = max_path,
-            branch = ws.branch,
             b_width = max_branch,
             hash = short_hash,
             c_width = 7,
@@ -148,19 +283,42 @@ fn display_workspace(
     output.list(&line);
 }
 
-fn branch_display(branch: &Option<String>) -> String {
+/// Normalize a branch ref to its bare name.
+///
+/// Git reports `refs/heads/<name>` while jj reports the bookmark name as-is.
+fn branch_name(branch: &Option<String>) -> Option<String> {
     branch
-        .as_ref()
-        .and_then(|b| b.strip_prefix("refs/heads/"))
-        .unwrap_or("(detached)")
-        .to_string()
+        .as_deref()
+        .map(|b| b.strip_prefix("refs/heads/").unwrap_or(b).to_string())
+}
+
+fn branch_display(branch: &Option<String>) -> String {
+    branch_name(branch).unwrap_or_else(|| "(detached)".to_string())
+}
+
+/// Lock and prune state shown at the end of the status column.
+#[derive(Default)]
+struct LockState<'a> {
+    is_locked: bool,
+    reason: Option<&'a str>,
+    is_prunable: bool,
+}
+
+impl DisplayWorkspace {
+    fn lock(&self) -> LockState<'_> {
+        LockState {
+            is_locked: self.is_locked,
+            reason: self.lock_reason.as_deref(),
+            is_prunable: self.is_prunable,
+        }
+    }
 }
 
 fn format_status(
     status: &WorkspaceStatus,
     unpushed: &UnpushedInfo,
     upstream: &Option<String>,
-    is_locked: bool,
+    lock: &LockState,
 ) -> String {
     let mut parts = Vec::new();
     if status.has_uncommitted_changes {
@@ -188,8 +346,14 @@ fn format_status(
         parts.push(unpushed_str);
     }
 
-    if is_locked {
-        parts.push("locked".to_string());
+    match (lock.is_locked, lock.reason) {
+        (true, Some(reason)) => parts.push(format!("locked: {reason}")),
+        (true, None) => parts.push("locked".to_string()),
+        (false, _) => {}
+    }
+
+    if lock.is_prunable {
+        parts.push("prunable".to_string());
     }
 
     if parts.is_empty() {
@@ -215,6 +379,72 @@ mod tests {
         assert_eq!(branch_display(&None), "(detached)");
     }
 
+    #[test]
+    fn test_branch_display_jj_bookmark() {
+        let branch = Some("feature".to_string());
+        assert_eq!(branch_display(&branch), "feature");
+    }
+
+    fn sample_workspace() -> DisplayWorkspace {
+        DisplayWorkspace {
+            path: "/repo/feature".to_string(),
+            branch: Some("feature".to_string()),
+            head: "abc1234def".to_string(),
+            status: WorkspaceStatus {
+                has_uncommitted_changes: true,
+                modified_count: 1,
+                deleted_count: 0,
+                untracked_count: 2,
+                untracked_paths: Vec::new(),
+            },
+            unpushed: UnpushedInfo {
+                has_unpushed: true,
+                count: 3,
+            },
+            upstream: None,
+            is_main: false,
+            is_locked: true,
+            lock_reason: Some("on a USB drive".to_string()),
+            is_prunable: false,
+            workspace_name: Some("feature".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_list_record_json_schema() {
+        let ws = sample_workspace();
+        let value = serde_json::to_value(ListRecord::from(&ws)).unwrap();
+        let mut keys: Vec<&str> = value
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        let mut expected = TSV_COLUMNS.to_vec();
+        keys.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(keys, expected);
+        assert_eq!(value["branch"], "feature");
+        assert_eq!(value["untracked"], 2);
+        assert!(value["upstream"].is_null());
+    }
+
+    #[test]
+    fn test_tsv_line() {
+        let ws = sample_workspace();
+        let line = tsv_line(&ListRecord::from(&ws));
+        assert_eq!(
+            line,
+            "/repo/feature\tfeature\tabc1234def\tfalse\ttrue\tfeature\ttrue\t1\t0\t2\ttrue\t3\t\ton a USB drive\tfalse"
+        );
+        assert_eq!(line.split('\t').count(), TSV_COLUMNS.len());
+    }
+
+    #[test]
+    fn test_tsv_escape() {
+        assert_eq!(tsv_escape("a\tb\nc"), "a\\tb\\nc");
+    }
+
     #[test]
     fn test_format_status_all() {
         use crate::vcs::WorkspaceStatus;
@@ -224,16 +454,22 @@ mod tests {
             modified_count: 2,
             deleted_count: 1,
             untracked_count: 3,
+            untracked_paths: Vec::new(),
         };
         let unpushed = UnpushedInfo {
             has_unpushed: true,
             count: 5,
         };
         let upstream = Some("origin/main".to_string());
-        let result = format_status(&status, &unpushed, &upstream, true);
+        let lock = LockState {
+            is_locked: true,
+            reason: Some("on a USB drive"),
+            is_prunable: true,
+        };
+        let result = format_status(&status, &unpushed, &upstream, &lock);
         assert_eq!(
             result,
-            "modified, deleted, untracked | unpushed: 5 (vs origin/main) | locked"
+            "modified, deleted, untracked | unpushed: 5 (vs origin/main) | locked: on a USB drive | prunable"
         );
     }
 
@@ -246,12 +482,13 @@ mod tests {
             modified_count: 0,
             deleted_count: 0,
             untracked_count: 0,
+            untracked_paths: Vec::new(),
         };
         let unpushed = UnpushedInfo {
             has_unpushed: false,
             count: 0,
         };
-        let result = format_status(&status, &unpushed, &None, false);
+        let result = format_status(&status, &unpushed, &None, &LockState::default());
         assert_eq!(result, "up to date");
     }
 }
@@ -0,0 +1,42 @@
+//! Lock command implementation.
+//!
+//! Locks a worktree/workspace so that kabu (and, for git, git itself) does not remove,
+//! move or prune it. git worktrees use `git worktree lock`; jj workspace locks are
+//! recorded in kabu's state.
+
+use crate::cli::LockArgs;
+use crate::color::ColorConfig;
+use crate::command::remove::{find_current_worktree, resolve_worktree_paths};
+use crate::error::{Error, Result};
+use crate::output::Output;
+use crate::vcs;
+
+pub(crate) fn run(args: LockArgs, color: ColorConfig) -> Result<()> {
+    let output = Output::new(args.quiet, color);
+
+    let provider = vcs::get_provider()?;
+
+    if !provider.is_inside_repo() {
+        return Err(Error::NotInAnyRepo);
+    }
+
+    let worktrees = provider.list_workspaces()?;
+    let path = match &args.path {
+        Some(path) => resolve_worktree_paths(std::slice::from_ref(path), &worktrees)?.remove(0),
+        None => find_current_worktree(&worktrees)?,
+    };
+
+    let Some(info) = worktrees.iter().find(|wt| wt.path == path) else {
+        return Err(Error::WorktreeNotFound { path });
+    };
+    if info.is_main {
+        return Err(Error::CannotLockMainWorktree { path });
+    }
+    if info.is_locked {
+        return Err(Error::WorktreeAlreadyLocked { path });
+    }
+
+    provider.workspace_lock(&path, args.reason.as_deref())?;
+    output.lock(&path, args.reason.as_deref());
+    Ok(())
+}
@@ -0,0 +1,51 @@
+//! Logs command implementation.
+//!
+//! Shows the output of the last hook run of a worktree/workspace, as recorded in the
+//! hook logs kabu keeps in its data directory.
+
+use crate::cli::LogsArgs;
+use crate::command::remove::find_current_worktree;
+use crate::error::{Error, Result};
+use crate::state;
+use crate::vcs;
+
+use std::fs;
+
+pub(crate) fn run(args: LogsArgs) -> Result<()> {
+    let provider = vcs::get_provider()?;
+
+    if !provider.is_inside_repo() {
+        return Err(Error::NotInAnyRepo);
+    }
+
+    let repo_root = provider.repository_root()?;
+    let main_worktree_path = provider.main_workspace_path_for(&repo_root)?;
+
+    // A path does not have to be a current worktree, so logs of removed ones stay reachable
+    let worktree_path = match args.path {
+        Some(path) if path.is_absolute() => path,
+        Some(path) => std::env::current_dir()?.join(path),
+        None => find_current_worktree(&provider.list_workspaces()?)?,
+    };
+
+    let log_dir = state::hook_log_dir(&main_worktree_path, &worktree_path)?;
+    let mut logs = state::hook_logs(&log_dir)?;
+    // on_enter runs after every cd; `--event` reaches the run a failure was reported for
+    if let Some(event) = &args.event {
+        logs.retain(|log| state::log_hook_type(log) == Some(event.as_str()));
+    }
+    let Some(latest) = logs.last() else {
+        return Err(Error::NoHookLogs {
+            path: worktree_path,
+        });
+    };
+
+    if args.list {
+        for log in &logs {
+            println!("{}", log.display());
+        }
+    } else {
+        print!("{}", fs::read_to_string(latest)?);
+    }
+    Ok(())
+}
@@ -0,0 +1,301 @@
+//! Move worktree/workspace command implementation.
+//!
+//! Moves a git worktree or jj workspace to a new path and carries over everything
+//! kabu keeps about it: slot allocation, setup manifest, hook logs and the symlinks
+//! kabu created that point into it.
+
+use crate::cli::MoveArgs;
+use crate::color::{self, ColorConfig};
+use crate::command::remove::{is_main_worktree, resolve_worktree_paths};
+use crate::command::setup::{find_branch, worktree_config};
+use crate::command::trust_check::{TrustHint, load_config_with_trust_check};
+use crate::error::{Error, Result};
+use crate::hook::{self, HookEnv};
+use crate::operation;
+use crate::output::Output;
+use crate::state::{self, Allocation, ManifestEntry, ManifestOp};
+use crate::vcs::{self, Revision};
+
+use std::fs;
+use std::path::Path;
+
+pub(crate) fn run(args: MoveArgs, color: ColorConfig) -> Result<()> {
+    let output = Output::new(args.quiet, color);
+
+    let provider = vcs::get_provider()?;
+
+    if !provider.is_inside_repo() {
+        return Err(Error::NotInAnyRepo);
+    }
+
+    let repo_root = provider.repository_root()?;
+
+    // Get main workspace path for trust operations
+    let main_worktree_path = provider.main_workspace_path_for(&repo_root)?;
+
+    let config =
+        load_config_with_trust_check(&repo_root, &main_worktree_path, true, TrustHint::None)?;
+    color::set_cli_theme(&config.ui.colors);
+
+    let worktrees = provider.list_workspaces()?;
+
+    let old_path =
+        resolve_worktree_paths(std::slice::from_ref(&args.old_path), &worktrees)?.remove(0);
+    if is_main_worktree(&old_path, &worktrees) {
+        return Err(Error::CannotMoveMainWorktree { path: old_path });
+    }
+    if worktrees
+        .iter()
+        .any(|wt| wt.path == old_path && wt.is_locked)
+    {
+        return Err(Error::CannotMoveLockedWorktree { path: old_path });
+    }
+
+    // Hooks of the profile the worktree was set up with apply to its move too
+    let config = worktree_config(&config, &main_worktree_path, &old_path);
+
+    let new_path = if args.new_path.is_absolute() {
+        args.new_path.clone()
+    } else {
+        std::env::current_dir()?.join(&args.new_path)
+    };
+    if fs::symlink_metadata(&new_path).is_ok() {
+        return Err(Error::MoveDestinationExists { path: new_path });
+    }
+
+    let hook_shell = {
+        #[cfg(windows)]
+        {
+            args.hook_shell
+                .clone()
+                .or_else(|| config.hooks.hook_shell.clone())
+        }
+        #[cfg(not(windows))]
+        {
+            None
+        }
+    };
+
+    // Resolved before the move; the checked out revision does not change
+    let context = MoveContext {
+        branch: find_branch(&old_path, &worktrees),
+        revision: provider.workspace_revision(&old_path).unwrap_or_default(),
+        allocation: state::find_slot(&main_worktree_path, &old_path, &config.ports)
+            .unwrap_or_default(),
+        repo_root: &repo_root,
+        main_worktree_path: &main_worktree_path,
+        vcs_type: provider.name(),
+        hook_shell,
+    };
+
+    if args.dry_run {
+        if !args.quiet && !config.hooks.pre_move.is_empty() {
+            hook::dry_run_hooks("pre_move", &config.hooks.pre_move, &output);
+        }
+        output.dry_run(&format!(
+            "Would move: {} -> {}",
+            old_path.display(),
+            new_path.display()
+        ));
+        if !args.quiet && !config.hooks.post_move.is_empty() {
+            hook::dry_run_hooks("post_move", &config.hooks.post_move, &output);
+        }
+        return Ok(());
+    }
+
+    if !config.hooks.pre_move.is_empty() {
+        hook::run_pre_move(
+            &config.hooks,
+            &context.hook_env(&old_path),
+            &old_path,
+            &output,
+        )?;
+    }
+
+    if let Some(parent) = new_path.parent()
+        && !parent.exists()
+    {
+        fs::create_dir_all(parent)?;
+    }
+    let new_path = state::normalize_worktree_path(&new_path);
+
+    let main = main_worktree_path.as_path();
+    let steps: [MoveStep<'_>; 5] = [
+        &|from, to| provider.workspace_move(from, to),
+        &|from, to| state::move_slot(main, from, to),
+        &|from, to| state::move_manifest(main, from, to),
+        &|from, to| state::move_hook_logs(main, from, to),
+        &|from, to| relink_sources(main, from, to, &output),
+    ];
+    run_move_steps(&steps, &old_path, &new_path)?;
+    output.moved(&old_path, &new_path);
+
+    if !config.hooks.post_move.is_empty()
+        && let Err(e) = hook::run_post_move(
+            &config.hooks,
+            &context.hook_env(&new_path),
+            &new_path,
+            &output,
+        )
+    {
+        let exit_code = match &e {
+            Error::HookFailed { exit_code, .. } => *exit_code,
+            _ => None,
+        };
+        output.hook_warning("post_move", &e.to_string(), exit_code);
+        output.hook_note("Worktree was moved but post-move hooks may be incomplete.");
+    }
+
+    Ok(())
+}
+
+/// One step of a move from the first path to the second; undone by swapping the paths.
+type MoveStep<'a> = &'a dyn Fn(&Path, &Path) -> Result<()>;
+
+/// Apply `steps` in order to move from `old_path` to `new_path`.
+///
+/// If a step fails, it and the steps before it are undone in reverse order, so that the
+/// worktree and kabu's state about it stay together at `old_path`. Errors while undoing
+/// are ignored in favor of the error of the failed step.
+fn run_move_steps(steps: &[MoveStep<'_>], old_path: &Path, new_path: &Path) -> Result<()> {
+    for (index, step) in steps.iter().enumerate() {
+        if let Err(e) = step(old_path, new_path) {
+            // The failed step may have got partway
+            for undo in steps[..=index].iter().rev() {
+                let _ = undo(new_path, old_path);
+            }
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+/// Hook context that stays the same on both sides of the move.
+struct MoveContext<'a> {
+    branch: Option<String>,
+    revision: Revision,
+    allocation: Option<Allocation>,
+    repo_root: &'a Path,
+    main_worktree_path: &'a Path,
+    vcs_type: &'static str,
+    hook_shell: Option<String>,
+}
+
+impl MoveContext<'_> {
+    fn hook_env(&self, path: &Path) -> HookEnv {
+        HookEnv {
+            worktree_path: path.to_string_lossy().to_string(),
+            worktree_name: path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("")
+                .to_string(),
+            branch: self.branch.clone(),
+            repo_root: self.repo_root.to_string_lossy().to_string(),
+            vcs_type: self.vcs_type.to_string(),
+            change_id: self.revision.change_id.clone(),
+            commit_id: self.revision.commit_id.clone(),
+            allocation: self.allocation.clone(),
+            log_dir: state::hook_log_dir(self.main_worktree_path, path).ok(),
+            hook_shell: self.hook_shell.clone(),
+        }
+    }
+}
+
+/// Re-create kabu-made symlinks whose source was inside the moved worktree.
+///
+/// Covers links in every worktree of the repository, including the moved one. Links
+/// that were changed since kabu created them are left alone.
+fn relink_sources(
+    main_worktree_path: &Path,
+    old_path: &Path,
+    new_path: &Path,
+    output: &Output,
+) -> Result<()> {
+    for manifest in state::list_manifests(main_worktree_path)? {
+        let mut relinked: Vec<ManifestEntry> = Vec::new();
+        let result: Result<()> = manifest.entries.iter().try_for_each(|entry| {
+            if entry.op != ManifestOp::Link || !entry.is_pristine(&manifest.worktree_path) {
+                return Ok(());
+            }
+            let Some(relative) = entry
+                .source
+                .as_ref()
+                .and_then(|source| source.strip_prefix(old_path).ok())
+            else {
+                return Ok(());
+            };
+            let source = new_path.join(relative);
+            let target = manifest.worktree_path.join(&entry.target);
+            fs::remove_file(&target)?;
+            operation::create_symlink(&source, &target)?;
+            output.link(&source, &target, None);
+            relinked.push(ManifestEntry {
+                source: Some(source),
+                ..entry.clone()
+            });
+            Ok(())
+        });
+        // Links re-created before a failure are recorded too, so they can be undone
+        if !relinked.is_empty() {
+            state::record_manifest(
+                main_worktree_path,
+                &manifest.worktree_path,
+                &manifest.config_hash,
+                manifest.profile.as_deref(),
+                relinked,
+            )?;
+        }
+        result?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_run_move_steps_undoes_steps_in_reverse_on_failure() {
+        let calls = RefCell::new(Vec::new());
+        let record = |name: &str, from: &Path, to: &Path| {
+            calls
+                .borrow_mut()
+                .push(format!("{name} {} -> {}", from.display(), to.display()));
+        };
+        let fails_forward = |from: &Path, to: &Path| {
+            record("manifest", from, to);
+            if from == Path::new("/old") {
+                return Err(Error::Aborted);
+            }
+            Ok(())
+        };
+        let steps: [MoveStep<'_>; 3] = [
+            &|from, to| {
+                record("move", from, to);
+                Ok(())
+            },
+            &fails_forward,
+            &|from, to| {
+                record("logs", from, to);
+                Ok(())
+            },
+        ];
+
+        let result = run_move_steps(&steps, Path::new("/old"), Path::new("/new"));
+
+        assert!(matches!(result, Err(Error::Aborted)));
+        assert_eq!(
+            calls.into_inner(),
+            [
+                "move /old -> /new",
+                "manifest /old -> /new",
+                "manifest /new -> /old",
+                "move /new -> /old",
+            ]
+        );
+    }
+}
@@ -0,0 +1,169 @@
+//! Prune worktrees/workspaces command implementation.
+//!
+//! Finds non-main worktrees whose branch (git) or bookmark (jj) is merged into a
+//! base, or whose upstream branch no longer exists, and removes the selected ones
+//! with the same safety checks and hooks as `kabu remove`.
+
+use crate::cli::PruneArgs;
+use crate::color::{self, ColorConfig};
+use crate::command::remove::{RemoveOptions, remove_worktrees};
+use crate::command::trust_check::{TrustHint, load_config_with_trust_check};
+use crate::error::{Error, Result};
+use crate::interactive::{PruneCandidate, run_prune_selection};
+use crate::output::Output;
+use crate::prompt;
+use crate::vcs::{self, VcsProvider, WorkspaceInfo};
+
+use std::path::PathBuf;
+
+pub(crate) fn run(args: PruneArgs, color: ColorConfig) -> Result<()> {
+    let output = Output::new(args.quiet, color);
+
+    let provider = vcs::get_provider()?;
+
+    if !provider.is_inside_repo() {
+        return Err(Error::NotInAnyRepo);
+    }
+
+    let repo_root = provider.repository_root()?;
+
+    // Get main workspace path for trust operations
+    let main_worktree_path = provider.main_workspace_path_for(&repo_root)?;
+
+    let config =
+        load_config_with_trust_check(&repo_root, &main_worktree_path, true, TrustHint::None)?;
+    color::set_cli_theme(&config.ui.colors);
+
+    let worktrees = provider.list_workspaces()?;
+
+    let base = args
+        .base
+        .clone()
+        .or_else(|| config.prune.base.clone())
+        .unwrap_or_else(|| provider.default_prune_base(&worktrees));
+
+    let candidates = find_prune_candidates(&worktrees, &base, provider.as_ref())?;
+    if candidates.is_empty() {
+        output.list(&format!(
+            "No {}s to prune (base: {})",
+            provider.workspace_type(),
+            base
+        ));
+        return Ok(());
+    }
+
+    let targets: Vec<PathBuf> = if args.yes || args.dry_run {
+        for candidate in &candidates {
+            output.list(&format!(
+                "{} [{}]",
+                candidate.workspace.path.display(),
+                candidate.reason
+            ));
+        }
+        candidates
+            .iter()
+            .map(|candidate| candidate.workspace.path.clone())
+            .collect()
+    } else {
+        if !prompt::is_interactive() {
+            return Err(Error::PruneRequiresConfirmation);
+        }
+        // Clear screen before entering interactive mode
+        prompt::clear_screen_interactive()?;
+        run_prune_selection(&candidates)?
+    };
+
+    if targets.is_empty() {
+        return Err(Error::Aborted);
+    }
+
+    let hook_shell = {
+        #[cfg(windows)]
+        {
+            args.hook_shell
+                .clone()
+                .or_else(|| config.hooks.hook_shell.clone())
+        }
+        #[cfg(not(windows))]
+        {
+            None
+        }
+    };
+
+    let options = RemoveOptions {
+        force: args.force,
+        dry_run: args.dry_run,
+        quiet: args.quiet,
+        hook_shell,
+    };
+    remove_worktrees(
+        &targets,
+        &worktrees,
+        &options,
+        &config,
+        &repo_root,
+        provider.as_ref(),
+        &output,
+    )
+}
+
+/// Collect non-main worktrees that are merged into `base` or whose upstream is gone.
+///
+/// Detached and locked worktrees are never proposed, nor is a worktree that has
+/// `base` itself checked out.
+fn find_prune_candidates(
+    worktrees: &[WorkspaceInfo],
+    base: &str,
+    provider: &dyn VcsProvider,
+) -> Result<Vec<PruneCandidate>> {
+    let mut candidates = Vec::new();
+
+    for ws in worktrees {
+        if ws.is_main || ws.is_locked {
+            continue;
+        }
+        let Some(branch) = ws.branch.as_deref() else {
+            continue;
+        };
+        if branch.strip_prefix("refs/heads/").unwrap_or(branch) == base {
+            continue;
+        }
+
+        let merged = provider.is_merged(ws, base)?;
+        let gone = provider.is_upstream_gone(ws)?;
+
+        if let Some(reason) = prune_reason(merged, gone) {
+            candidates.push(PruneCandidate {
+                workspace: ws.clone(),
+                reason,
+            });
+        }
+    }
+
+    Ok(candidates)
+}
+
+fn prune_reason(merged: bool, gone: bool) -> Option<String> {
+    match (merged, gone) {
+        (true, true) => Some("merged, upstream gone".to_string()),
+        (true, false) => Some("merged".to_string()),
+        (false, true) => Some("upstream gone".to_string()),
+        (false, false) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prune_reason() {
+        assert_eq!(prune_reason(true, false), Some("merged".to_string()));
+        assert_eq!(prune_reason(false, true), Some("upstream gone".to_string()));
+        assert_eq!(
+            prune_reason(true, true),
+            Some("merged, upstream gone".to_string())
+        );
+        assert_eq!(prune_reason(false, false), None);
+    }
+}
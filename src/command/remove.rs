@@ -5,15 +5,18 @@
 
 use crate::cli::RemoveArgs;
 use crate::color::{self, ColorConfig};
+use crate::command::setup::worktree_config;
 use crate::command::trust_check::{TrustHint, load_config_with_trust_check};
+use crate::config::Config;
 use crate::error::{Error, Result};
 use crate::hook::{self, HookEnv};
 use crate::interactive::{SafetyWarning, run_remove_confirmation, run_remove_selection};
 use crate::output::Output;
 use crate::prompt;
-use crate::vcs::{self, WorkspaceInfo};
+use crate::state;
+use crate::vcs::{self, VcsProvider, WorkspaceInfo, WorkspaceStatus};
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub(crate) fn run(args: RemoveArgs, color: ColorConfig) -> Result<()> {
     let output = Output::new(args.quiet, color);
@@ -57,16 +60,80 @@ pub(crate) fn run(args: RemoveArgs, color: ColorConfig) -> Result<()> {
         }
     }
 
-    let warnings = if !args.force {
-        collect_safety_warnings(&targets, provider.as_ref())?
+    let hook_shell = {
+        #[cfg(windows)]
+        {
+            args.hook_shell
+                .clone()
+                .or_else(|| config.hooks.hook_shell.clone())
+        }
+        #[cfg(not(windows))]
+        {
+            None
+        }
+    };
+
+    let options = RemoveOptions {
+        force: args.force,
+        dry_run: args.dry_run,
+        quiet: args.quiet,
+        hook_shell,
+    };
+    remove_worktrees(
+        &targets,
+        &worktrees,
+        &options,
+        &config,
+        &repo_root,
+        provider.as_ref(),
+        &output,
+    )
+}
+
+/// Options for [`remove_worktrees`].
+pub(super) struct RemoveOptions {
+    pub force: bool,
+    pub dry_run: bool,
+    pub quiet: bool,
+    pub hook_shell: Option<String>,
+}
+
+/// Remove worktrees with safety checks and pre_remove/post_remove hooks.
+///
+/// Shared by `kabu remove` and `kabu prune`. Targets must already be resolved from
+/// `worktrees` and must not include the main worktree.
+pub(super) fn remove_worktrees(
+    targets: &[PathBuf],
+    worktrees: &[WorkspaceInfo],
+    options: &RemoveOptions,
+    config: &Config,
+    repo_root: &Path,
+    provider: &dyn VcsProvider,
+    output: &Output,
+) -> Result<()> {
+    let main_worktree_path = provider.main_workspace_path_for(repo_root)?;
+
+    if !options.force
+        && let Some(locked) = worktrees
+            .iter()
+            .find(|wt| wt.is_locked && targets.contains(&wt.path))
+    {
+        return Err(Error::WorktreeLocked {
+            path: locked.path.clone(),
+            reason: locked.lock_reason.clone(),
+        });
+    }
+
+    let warnings = if !options.force {
+        collect_safety_warnings(targets, &main_worktree_path, provider)?
     } else {
         vec![]
     };
 
     if !warnings.is_empty() {
-        if args.dry_run {
+        if options.dry_run {
             for warning in &warnings {
-                display_warning(&output, warning);
+                display_warning(output, warning);
             }
         } else if prompt::is_interactive() {
             if !run_remove_confirmation(&warnings)? {
@@ -86,7 +153,10 @@ pub(crate) fn run(args: RemoveArgs, color: ColorConfig) -> Result<()> {
         }
     }
 
-    for path in &targets {
+    for path in targets {
+        // Hooks of the profile the worktree was set up with apply to its removal too
+        let config = &worktree_config(config, &main_worktree_path, path);
+
         // Create hook environment
         let worktree_name = path
             .file_name()
@@ -94,57 +164,76 @@ pub(crate) fn run(args: RemoveArgs, color: ColorConfig) -> Result<()> {
             .unwrap_or("")
             .to_string();
 
-        let hook_shell = {
-            #[cfg(windows)]
-            {
-                args.hook_shell
-                    .clone()
-                    .or_else(|| config.hooks.hook_shell.clone())
-            }
-            #[cfg(not(windows))]
-            {
-                None
-            }
-        };
+        let info = worktrees.iter().find(|wt| &wt.path == path);
+        let branch = info
+            .and_then(|wt| wt.branch.as_ref())
+            .map(|b| b.strip_prefix("refs/heads/").unwrap_or(b).to_string());
+        // Resolved before removal, so post_remove hooks see the same values
+        let revision = provider.workspace_revision(path).unwrap_or_default();
+        let commit_id = revision.commit_id.or_else(|| {
+            info.map(|wt| wt.head.chars().take(12).collect::<String>())
+                .filter(|head| !head.is_empty())
+        });
 
         let hook_env = HookEnv {
             worktree_path: path.to_string_lossy().to_string(),
             worktree_name,
-            branch: None, // Branch info not available for remove
+            branch,
             repo_root: repo_root.to_string_lossy().to_string(),
             vcs_type: provider.name().to_string(),
-            change_id: None,
-            commit_id: None,
-            hook_shell,
+            change_id: revision.change_id,
+            commit_id,
+            // State problems must not block removal; hooks just see no ports
+            allocation: state::find_slot(&main_worktree_path, path, &config.ports)
+                .unwrap_or_default(),
+            log_dir: state::hook_log_dir(&main_worktree_path, path).ok(),
+            hook_shell: options.hook_shell.clone(),
         };
 
         // Run pre_remove hooks
         if !config.hooks.pre_remove.is_empty() {
-            if args.dry_run {
-                if !args.quiet {
-                    hook::dry_run_hooks("pre_remove", &config.hooks.pre_remove, &output);
+            if options.dry_run {
+                if !options.quiet {
+                    hook::dry_run_hooks("pre_remove", &config.hooks.pre_remove, output);
                 }
             } else {
-                hook::run_pre_remove(&config.hooks, &hook_env, path, &output)?;
+                hook::run_pre_remove(&config.hooks, &hook_env, path, output)?;
             }
         }
 
-        if args.dry_run {
+        if options.dry_run {
             output.dry_run(&format!("Would remove: {}", path.display()));
         } else {
-            let use_force = args.force || !warnings.is_empty();
-            provider.workspace_remove_checked(path, use_force)?;
+            let use_force = options.force || !warnings.is_empty();
+            let locked = info.filter(|wt| wt.is_locked);
+            if locked.is_some() {
+                // Only reachable with --force; git refuses to remove locked worktrees
+                provider.workspace_unlock(path)?;
+            }
+            if !use_force {
+                // Files kabu created would otherwise make the removal fail as untracked
+                remove_owned_files(&main_worktree_path, path, provider)?;
+            }
+            if let Err(e) = provider.workspace_remove_checked(path, use_force) {
+                // The worktree is still there, so it keeps its lock
+                if let Some(wt) = locked {
+                    let _ = provider.workspace_lock(path, wt.lock_reason.as_deref());
+                }
+                return Err(e);
+            }
             output.remove(path);
+            let _ = state::release_slot(&main_worktree_path, path);
+            let _ = state::remove_manifest(&main_worktree_path, path);
         }
 
         // Run post_remove hooks
         if !config.hooks.post_remove.is_empty() {
-            if args.dry_run {
-                if !args.quiet {
-                    hook::dry_run_hooks("post_remove", &config.hooks.post_remove, &output);
+            if options.dry_run {
+                if !options.quiet {
+                    hook::dry_run_hooks("post_remove", &config.hooks.post_remove, output);
                 }
             } else if let Err(e) =
-                hook::run_post_remove(&config.hooks, &hook_env, &repo_root, &output)
+                hook::run_post_remove(&config.hooks, &hook_env, repo_root, output)
             {
                 // Extract exit code from error if available
                 let exit_code = match &e {
@@ -168,22 +257,25 @@ fn select_worktrees_interactively(worktrees: &[WorkspaceInfo]) -> Result<Vec<Pat
     Ok(paths)
 }
 
-fn find_current_worktree(worktrees: &[WorkspaceInfo]) -> Result<PathBuf> {
+pub(super) fn find_current_worktree(worktrees: &[WorkspaceInfo]) -> Result<PathBuf> {
     let current_dir = std::env::current_dir()?;
     let current_dir = current_dir
         .canonicalize()
         .unwrap_or_else(|_| current_dir.clone());
 
-    for wt in worktrees {
-        if current_dir.starts_with(&wt.path) {
-            return Ok(wt.path.clone());
-        }
-    }
-
-    Err(Error::NotInWorktree)
+    // Worktrees may be nested in the main worktree, so the deepest match wins
+    worktrees
+        .iter()
+        .filter(|wt| current_dir.starts_with(&wt.path))
+        .max_by_key(|wt| wt.path.components().count())
+        .map(|wt| wt.path.clone())
+        .ok_or(Error::NotInWorktree)
 }
 
-fn resolve_worktree_paths(paths: &[PathBuf], worktrees: &[WorkspaceInfo]) -> Result<Vec<PathBuf>> {
+pub(super) fn resolve_worktree_paths(
+    paths: &[PathBuf],
+    worktrees: &[WorkspaceInfo],
+) -> Result<Vec<PathBuf>> {
     let mut resolved = Vec::new();
 
     for path in paths {
@@ -207,7 +299,7 @@ fn resolve_worktree_paths(paths: &[PathBuf], worktrees: &[WorkspaceInfo]) -> Res
     Ok(resolved)
 }
 
-fn is_main_worktree(path: &PathBuf, worktrees: &[WorkspaceInfo]) -> bool {
+pub(super) fn is_main_worktree(path: &PathBuf, worktrees: &[WorkspaceInfo]) -> bool {
     worktrees
         .iter()
         .find(|wt| &wt.path == path)
@@ -217,12 +309,17 @@ fn is_main_worktree(path: &PathBuf, worktrees: &[WorkspaceInfo]) -> bool {
 
 fn collect_safety_warnings(
     targets: &[PathBuf],
+    main_worktree_path: &Path,
     provider: &dyn vcs::VcsProvider,
 ) -> Result<Vec<SafetyWarning>> {
     let mut warnings = Vec::new();
 
     for path in targets {
-        let status = provider.workspace_status(path)?;
+        let mut status = provider.workspace_status(path)?;
+        let owned = owned_untracked_paths(&status, main_worktree_path, path);
+        status.untracked_count = status.untracked_count.saturating_sub(owned.len());
+        status.has_uncommitted_changes =
+            status.modified_count > 0 || status.deleted_count > 0 || status.untracked_count > 0;
         let unpushed = provider.workspace_unpushed(path)?;
 
         if status.has_uncommitted_changes || unpushed.has_unpushed {
@@ -241,6 +338,47 @@ fn collect_safety_warnings(
     Ok(warnings)
 }
 
+/// Untracked paths that kabu created during setup and the user has not changed.
+///
+/// These are recreated by `kabu setup`, so they don't count as uncommitted work.
+fn owned_untracked_paths(
+    status: &WorkspaceStatus,
+    main_worktree_path: &Path,
+    worktree_path: &Path,
+) -> Vec<PathBuf> {
+    let Ok(Some(manifest)) = state::read_manifest(main_worktree_path, worktree_path) else {
+        return Vec::new();
+    };
+    status
+        .untracked_paths
+        .iter()
+        .filter(|path| {
+            manifest
+                .entry(path)
+                .is_some_and(|entry| entry.is_pristine(worktree_path))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Delete untracked files kabu created so a non-forced removal can succeed.
+fn remove_owned_files(
+    main_worktree_path: &Path,
+    worktree_path: &Path,
+    provider: &dyn VcsProvider,
+) -> Result<()> {
+    let status = provider.workspace_status(worktree_path)?;
+    for relative in owned_untracked_paths(&status, main_worktree_path, worktree_path) {
+        let target = worktree_path.join(relative);
+        if target.is_dir() && !target.is_symlink() {
+            std::fs::remove_dir_all(&target)?;
+        } else {
+            std::fs::remove_file(&target)?;
+        }
+    }
+    Ok(())
+}
+
 fn display_warning(output: &Output, warning: &SafetyWarning) {
     if warning.modified_count > 0 {
         output.safety_warning(
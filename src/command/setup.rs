@@ -0,0 +1,210 @@
+//! Setup command implementation.
+//!
+//! Re-applies the mkdir/link/copy setup from `.kabu/config.yaml` to worktrees or
+//! workspaces that already exist, optionally re-running `post_add` hooks.
+
+use crate::cli::SetupArgs;
+use crate::color::{self, ColorConfig};
+use crate::command::add::{
+    SetupOptions, conflict_override, run_setup, source_context, validate_sources,
+};
+use crate::command::remove::{find_current_worktree, is_main_worktree, resolve_worktree_paths};
+use crate::command::trust_check::{TrustHint, load_config_with_trust_check};
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::hook::{self, HookEnv};
+use crate::output::Output;
+use crate::state;
+use crate::vcs::{self, WorkspaceInfo};
+
+use std::path::{Path, PathBuf};
+
+pub(crate) fn run(args: SetupArgs, color: ColorConfig) -> Result<()> {
+    let output = Output::new(args.quiet, color);
+
+    let provider = vcs::get_provider()?;
+
+    if !provider.is_inside_repo() {
+        return Err(Error::NotInAnyRepo);
+    }
+
+    let repo_root = provider.repository_root()?;
+
+    // Get main workspace path for trust operations
+    let main_worktree_path = provider.main_workspace_path_for(&repo_root)?;
+
+    // Hooks only run with --run-hooks; without it, trust is only required for sources
+    // outside the repository
+    let config = load_config_with_trust_check(
+        &repo_root,
+        &main_worktree_path,
+        args.run_hooks,
+        TrustHint::None,
+    )?;
+    color::set_cli_theme(&config.ui.colors);
+
+    let worktrees = provider.list_workspaces()?;
+
+    let targets = if args.all {
+        worktrees
+            .iter()
+            .filter(|wt| !wt.is_main)
+            .map(|wt| wt.path.clone())
+            .collect()
+    } else if let Some(path) = &args.path {
+        resolve_worktree_paths(std::slice::from_ref(path), &worktrees)?
+    } else {
+        vec![find_current_worktree(&worktrees)?]
+    };
+
+    if targets.is_empty() {
+        return Err(Error::NoWorktreesFound);
+    }
+
+    for path in &targets {
+        if is_main_worktree(path, &worktrees) {
+            return Err(Error::CannotSetupMainWorktree { path: path.clone() });
+        }
+    }
+
+    // Sources come from the main worktree, also when run from a linked one
+    let sources = source_context(&main_worktree_path, provider.as_ref())?;
+    // Each worktree keeps the profile it was set up with unless --profile is given
+    let mut target_configs = Vec::with_capacity(targets.len());
+    for path in &targets {
+        let target_config = match &args.profile {
+            Some(name) => config.with_profile(Some(name))?,
+            None => worktree_config(&config, &main_worktree_path, path),
+        };
+        validate_sources(&target_config, &sources)?;
+        target_configs.push(target_config);
+    }
+
+    let on_conflict = conflict_override(args.on_conflict);
+    let mut hook_failures: Vec<PathBuf> = Vec::new();
+
+    for (path, config) in targets.iter().zip(&target_configs) {
+        if targets.len() > 1 {
+            output.setup_target(path);
+        }
+
+        let branch = find_branch(path, &worktrees);
+        let setup_options = SetupOptions {
+            on_conflict,
+            dry_run: args.dry_run,
+            branch: branch.clone(),
+        };
+        run_setup(
+            config,
+            &main_worktree_path,
+            path,
+            &setup_options,
+            &output,
+            provider.as_ref(),
+        )?;
+
+        if !args.run_hooks || config.hooks.post_add.is_empty() {
+            continue;
+        }
+
+        if args.dry_run {
+            if !args.quiet {
+                hook::dry_run_hooks("post_add", &config.hooks.post_add, &output);
+            }
+            continue;
+        }
+
+        let hook_shell = {
+            #[cfg(windows)]
+            {
+                args.hook_shell
+                    .clone()
+                    .or_else(|| config.hooks.hook_shell.clone())
+            }
+            #[cfg(not(windows))]
+            {
+                None
+            }
+        };
+
+        // Worktrees created before `ports:` was configured get their slot here
+        let allocation = if config.ports.is_empty() {
+            None
+        } else {
+            Some(state::allocate_slot(
+                &main_worktree_path,
+                path,
+                &config.ports,
+            )?)
+        };
+
+        let revision = provider.workspace_revision(path).unwrap_or_default();
+        let hook_env = HookEnv {
+            worktree_path: path.to_string_lossy().to_string(),
+            worktree_name: path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("")
+                .to_string(),
+            branch,
+            repo_root: repo_root.to_string_lossy().to_string(),
+            vcs_type: provider.name().to_string(),
+            change_id: revision.change_id,
+            commit_id: revision.commit_id,
+            allocation,
+            log_dir: state::hook_log_dir(&main_worktree_path, path).ok(),
+            hook_shell,
+        };
+
+        if let Err(e) = hook::run_post_add(&config.hooks, &hook_env, path, &output) {
+            let exit_code = match &e {
+                Error::HookFailed { exit_code, .. } => *exit_code,
+                _ => None,
+            };
+            output.hook_warning("post_add", &e.to_string(), exit_code);
+            hook_failures.push(path.clone());
+        }
+    }
+
+    if !args.dry_run && !args.quiet {
+        if hook_failures.is_empty() {
+            output.results_success("Setup completed successfully");
+        } else {
+            output.results_header();
+            output.results_item_success("Setup operations completed");
+            for path in &hook_failures {
+                output.results_item_failed(&format!("post_add hooks: {}", path.display()));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The config with the profile a worktree was set up with applied.
+///
+/// Falls back to the default profile when no profile was recorded or the recorded
+/// profile is no longer configured. State problems never block the caller.
+pub(super) fn worktree_config(
+    config: &Config,
+    main_worktree_path: &Path,
+    worktree_path: &Path,
+) -> Config {
+    let recorded = state::read_manifest(main_worktree_path, worktree_path)
+        .ok()
+        .flatten()
+        .and_then(|manifest| manifest.profile)
+        .filter(|name| config.profiles.contains_key(name));
+    config
+        .with_profile(recorded.as_deref())
+        .unwrap_or_else(|_| config.clone())
+}
+
+/// Look up the branch (git) or bookmark (jj) name of a worktree.
+pub(super) fn find_branch(path: &Path, worktrees: &[WorkspaceInfo]) -> Option<String> {
+    worktrees
+        .iter()
+        .find(|wt| wt.path == path)
+        .and_then(|wt| wt.branch.as_ref())
+        .map(|b| b.strip_prefix("refs/heads/").unwrap_or(b).to_string())
+}
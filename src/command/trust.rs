@@ -1,9 +1,15 @@
 use crate::cli::TrustArgs;
 use crate::color::{self, ColorConfig, ColorScheme};
-use crate::config::ConfigSnapshot;
+use crate::config::{Config, ConfigSnapshot, HookEntry};
 use crate::{config, error::Error, error::Result, prompt, trust, vcs};
 
+use std::path::{Path, PathBuf};
+
 pub(crate) fn run(args: TrustArgs, color_config: ColorConfig) -> Result<()> {
+    if args.global {
+        return run_global(args, color_config);
+    }
+
     let provider = vcs::get_provider()?;
 
     if args.check {
@@ -22,7 +28,7 @@ pub(crate) fn run(args: TrustArgs, color_config: ColorConfig) -> Result<()> {
             None => return Ok(()),
         };
 
-        if !config.hooks.has_hooks() {
+        if !config.requires_trust() {
             return Ok(());
         }
 
@@ -35,7 +41,7 @@ pub(crate) fn run(args: TrustArgs, color_config: ColorConfig) -> Result<()> {
         return Err(Error::TrustCheckFailed);
     }
 
-    let repo_root = match args.path {
+    let repo_root = match &args.path {
         Some(p) => p.canonicalize()?,
         None => provider.repository_root()?,
     };
@@ -48,53 +54,94 @@ pub(crate) fn run(args: TrustArgs, color_config: ColorConfig) -> Result<()> {
 
     color::set_cli_theme(&config.ui.colors);
 
-    if !config.hooks.has_hooks() {
+    if !config.requires_trust() {
         return Err(Error::NoHooksDefined);
     }
 
+    review_and_trust(
+        &args,
+        color_config,
+        &Target {
+            label: "Repository",
+            path: &repo_root,
+            trust_path: &main_worktree_path,
+        },
+        &config,
+    )
+}
+
+/// Review and trust the hooks of the global config, keyed to the global config file.
+fn run_global(args: TrustArgs, color_config: ColorConfig) -> Result<()> {
+    let config = config::load_global()?;
+    let path = config::global_config_file();
+
+    if args.check {
+        let (Some(config), Some(path)) = (config, path) else {
+            return Ok(());
+        };
+        if !config.requires_trust() || trust::is_trusted(&path, &config)? {
+            return Ok(());
+        }
+        return Err(Error::TrustCheckFailed);
+    }
+
+    let (Some(config), Some(path)) = (config, path) else {
+        return Err(Error::GlobalConfigNotFound);
+    };
+
+    color::set_cli_theme(&config.ui.colors);
+
+    if !config.requires_trust() {
+        return Err(Error::NoGlobalHooksDefined);
+    }
+
+    review_and_trust(
+        &args,
+        color_config,
+        &Target {
+            label: "Global config",
+            path: &path,
+            trust_path: &path,
+        },
+        &config,
+    )
+}
+
+/// What a trust review is for.
+struct Target<'a> {
+    /// Heading shown before the hooks under review
+    label: &'static str,
+    /// Path shown to the user
+    path: &'a Path,
+    /// Path the trust entry is keyed to
+    trust_path: &'a Path,
+}
+
+/// Show the hooks of a config and trust them once confirmed, or only show them with `--show`.
+fn review_and_trust(
+    args: &TrustArgs,
+    color_config: ColorConfig,
+    target: &Target,
+    config: &Config,
+) -> Result<()> {
     if args.show {
         let use_color = color_config.is_enabled();
 
-        println!("Hooks in {}:", repo_root.display());
-        if !config.hooks.pre_add.is_empty() {
-            println!();
-            if use_color {
-                println!("{}", ColorScheme::hook_type("pre_add:"));
-            } else {
-                println!("pre_add:");
-            }
-            for entry in &config.hooks.pre_add {
-                println!("  {}", entry.command);
-                if let Some(desc) = &entry.description {
-                    if use_color {
-                        println!(
-                            "  {} {}",
-                            ColorScheme::hook_arrow("->"),
-                            ColorScheme::hook_description(desc)
-                        );
-                    } else {
-                        println!("  -> {}", desc);
-                    }
-                } else if use_color {
-                    println!(
-                        "  {} {}",
-                        ColorScheme::hook_arrow("->"),
-                        ColorScheme::dimmed("no description")
-                    );
-                } else {
-                    println!("  -> no description");
-                }
+        println!("Hooks in {}:", target.path.display());
+        print_extends(&config.extends);
+        print_external_sources(config);
+        for (hook_type, entries) in config.hook_events() {
+            if entries.is_empty() {
+                continue;
             }
-        }
-        if !config.hooks.post_add.is_empty() {
             println!();
             if use_color {
-                println!("{}", ColorScheme::hook_type("post_add:"));
+                println!("{}", ColorScheme::hook_type(&format!("{hook_type}:")));
             } else {
-                println!("post_add:");
+                println!("{hook_type}:");
             }
-            for entry in &config.hooks.post_add {
-                println!("  {}", entry.command);
+            for entry in entries {
+                println!("  {}", entry.headline());
                 if let Some(desc) = &entry.description {
                     if use_color {
                         println!(
@@ -114,70 +161,13 @@ pub(crate) fn run(args: TrustArgs, color_config: ColorConfig) -> Result<()> {
                 } else {
                     println!("  -> no description");
                 }
-            }
-        }
-        if !config.hooks.pre_remove.is_empty() {
-            println!();
-            if use_color {
-                println!("{}", ColorScheme::hook_type("pre_remove:"));
-            } else {
-                println!("pre_remove:");
-            }
-            for entry in &config.hooks.pre_remove {
-                println!("  {}", entry.command);
-                if let Some(desc) = &entry.description {
-                    if use_color {
-                        println!(
-                            "  {} {}",
-                            ColorScheme::hook_arrow("->"),
-                            ColorScheme::hook_description(desc)
-                        );
-                    } else {
-                        println!("  -> {}", desc);
-                    }
-                } else if use_color {
-                    println!(
-                        "  {} {}",
-                        ColorScheme::hook_arrow("->"),
-                        ColorScheme::dimmed("no description")
-                    );
-                } else {
-                    println!("  -> no description");
-                }
-            }
-        }
-        if !config.hooks.post_remove.is_empty() {
-            println!();
-            if use_color {
-                println!("{}", ColorScheme::hook_type("post_remove:"));
-            } else {
-                println!("post_remove:");
-            }
-            for entry in &config.hooks.post_remove {
-                println!("  {}", entry.command);
-                if let Some(desc) = &entry.description {
-                    if use_color {
-                        println!(
-                            "  {} {}",
-                            ColorScheme::hook_arrow("->"),
-                            ColorScheme::hook_description(desc)
-                        );
-                    } else {
-                        println!("  -> {}", desc);
-                    }
-                } else if use_color {
-                    println!(
-                        "  {} {}",
-                        ColorScheme::hook_arrow("->"),
-                        ColorScheme::dimmed("no description")
-                    );
-                } else {
-                    println!("  -> no description");
+                for line in entry.option_lines() {
+                    println!("     {}", line);
                 }
             }
         }
 
-        let is_trusted = trust::is_trusted(&main_worktree_path, &config)?;
+        let is_trusted = trust::is_trusted(target.trust_path, config)?;
         println!(
             "\nTrust status: {}",
             if is_trusted { "trusted" } else { "not trusted" }
@@ -197,46 +187,21 @@ pub(crate) fn run(args: TrustArgs, color_config: ColorConfig) -> Result<()> {
         println!("WARNING: Review these commands before trusting");
     }
     println!();
-    println!("Repository: {}", repo_root.display());
+    println!("{}: {}", target.label, target.path.display());
+    print_extends(&config.extends);
+    print_external_sources(config);
 
-    if !config.hooks.pre_add.is_empty() {
-        if use_color {
-            println!("{}", ColorScheme::hook_type("pre_add:"));
-        } else {
-            println!("pre_add:");
+    for (hook_type, entries) in config.hook_events() {
+        if entries.is_empty() {
+            continue;
         }
-        for entry in &config.hooks.pre_add {
-            println!("  {}", entry.command);
-            if let Some(desc) = &entry.description {
-                if use_color {
-                    println!(
-                        "  {} {}",
-                        ColorScheme::hook_arrow("->"),
-                        ColorScheme::hook_description(desc)
-                    );
-                } else {
-                    println!("  -> {}", desc);
-                }
-            } else if use_color {
-                println!(
-                    "  {} {}",
-                    ColorScheme::hook_arrow("->"),
-                    ColorScheme::dimmed("no description")
-                );
-            } else {
-                println!("  -> no description");
-            }
-        }
-        println!();
-    }
-    if !config.hooks.post_add.is_empty() {
         if use_color {
-            println!("{}", ColorScheme::hook_type("post_add:"));
+            println!("{}", ColorScheme::hook_type(&format!("{hook_type}:")));
         } else {
-            println!("post_add:");
+            println!("{hook_type}:");
         }
-        for entry in &config.hooks.post_add {
-            println!("  {}", entry.command);
+        for entry in entries {
+            println!("  {}", entry.headline());
             if let Some(desc) = &entry.description {
                 if use_color {
                     println!(
@@ -256,65 +221,8 @@ pub(crate) fn run(args: TrustArgs, color_config: ColorConfig) -> Result<()> {
             } else {
                 println!("  -> no description");
             }
-        }
-        println!();
-    }
-    if !config.hooks.pre_remove.is_empty() {
-        if use_color {
-            println!("{}", ColorScheme::hook_type("pre_remove:"));
-        } else {
-            println!("pre_remove:");
-        }
-        for entry in &config.hooks.pre_remove {
-            println!("  {}", entry.command);
-            if let Some(desc) = &entry.description {
-                if use_color {
-                    println!(
-                        "  {} {}",
-                        ColorScheme::hook_arrow("->"),
-                        ColorScheme::hook_description(desc)
-                    );
-                } else {
-                    println!("  -> {}", desc);
-                }
-            } else if use_color {
-                println!(
-                    "  {} {}",
-                    ColorScheme::hook_arrow("->"),
-                    ColorScheme::dimmed("no description")
-                );
-            } else {
-                println!("  -> no description");
-            }
-        }
-        println!();
-    }
-    if !config.hooks.post_remove.is_empty() {
-        if use_color {
-            println!("{}", ColorScheme::hook_type("post_remove:"));
-        } else {
-            println!("post_remove:");
-        }
-        for entry in &config.hooks.post_remove {
-            println!("  {}", entry.command);
-            if let Some(desc) = &entry.description {
-                if use_color {
-                    println!(
-                        "  {} {}",
-                        ColorScheme::hook_arrow("->"),
-                        ColorScheme::hook_description(desc)
-                    );
-                } else {
-                    println!("  -> {}", desc);
-                }
-            } else if use_color {
-                println!(
-                    "  {} {}",
-                    ColorScheme::hook_arrow("->"),
-                    ColorScheme::dimmed("no description")
-                );
-            } else {
-                println!("  -> no description");
+            for line in entry.option_lines() {
+                println!("     {}", line);
             }
         }
         println!();
@@ -322,19 +230,17 @@ pub(crate) fn run(args: TrustArgs, color_config: ColorConfig) -> Result<()> {
 
     // Check if configuration has changed and display diff if so
     let use_color = color_config.is_enabled();
-    if let Ok(Some(trust_entry)) = trust::read_trust_entry(&main_worktree_path) {
+    if let Ok(Some(trust_entry)) = trust::read_trust_entry(target.trust_path) {
         let old_snapshot = &trust_entry.config_snapshot;
-        let new_config = &config;
-
         // Compare snapshots to detect changes
-        let new_snapshot = ConfigSnapshot::from_config(new_config);
+        let new_snapshot = ConfigSnapshot::from_config(config);
         if old_snapshot == &new_snapshot {
-            let is_trusted = trust::is_trusted(&main_worktree_path, &config)?;
+            let is_trusted = trust::is_trusted(target.trust_path, config)?;
             if is_trusted {
                 // Configuration hasn't changed, already trusted
                 println!(
                     "Configuration is already trusted for: {}",
-                    repo_root.display()
+                    target.path.display()
                 );
                 return Ok(());
             }
@@ -342,21 +248,21 @@ pub(crate) fn run(args: TrustArgs, color_config: ColorConfig) -> Result<()> {
         }
 
         // Configuration has changed, show diff
-        display_config_diff(old_snapshot, new_config, use_color);
+        display_config_diff(old_snapshot, config, use_color);
     }
 
     if args.yes {
-        trust::trust(&main_worktree_path, &config)?;
-        println!("\n✓ Configuration trusted for: {}", repo_root.display());
+        trust::trust(target.trust_path, config)?;
+        println!("\n✓ Configuration trusted for: {}", target.path.display());
         println!("These hooks will now run automatically on kabu add/remove commands.");
         return Ok(());
     }
 
     // Prompt for confirmation
     if prompt::is_interactive() {
-        if prompt::prompt_trust_hooks(&repo_root)? {
-            trust::trust(&main_worktree_path, &config)?;
-            println!("\n✓ Configuration trusted for: {}", repo_root.display());
+        if prompt::prompt_trust_hooks(target.path)? {
+            trust::trust(target.trust_path, config)?;
+            println!("\n✓ Configuration trusted for: {}", target.path.display());
             println!("These hooks will now run automatically on kabu add/remove commands.");
         } else {
             println!("\nConfiguration was not trusted.");
@@ -376,6 +282,15 @@ pub(crate) fn run(args: TrustArgs, color_config: ColorConfig) -> Result<()> {
     Ok(())
 }
 
+/// First line of a hook entry in a diff: `command: ...`, or `parallel:` for a group.
+fn hook_diff_headline(entry: &HookEntry) -> String {
+    if entry.is_parallel() {
+        entry.headline().to_string()
+    } else {
+        format!("command: {}", entry.command)
+    }
+}
+
 fn diff_prefix(use_color: bool, added: bool) -> String {
     match (use_color, added) {
         (true, true) => ColorScheme::diff_added("+"),
@@ -411,6 +326,29 @@ fn diff_list<T: Clone + PartialEq>(old: &[T], new: &[T]) -> (Vec<T>, Vec<T>, boo
     (removed, added, order_changed)
 }
 
+/// List the files the config extends; their hooks are reviewed along with the config.
+fn print_extends(extends: &[PathBuf]) {
+    if extends.is_empty() {
+        return;
+    }
+    println!("Extends:");
+    for path in extends {
+        println!("  {}", path.display());
+    }
+}
+
+/// List link/copy entries that reach outside the repository; they are trusted with the hooks.
+fn print_external_sources(config: &Config) {
+    let external = config.external_sources();
+    if external.is_empty() {
+        return;
+    }
+    println!("Sources outside the repository:");
+    for line in external {
+        println!("  {line}");
+    }
+}
+
 fn format_on_conflict(conflict: &config::OnConflict) -> String {
     format!("{:?}", conflict).to_lowercase()
 }
@@ -418,7 +356,7 @@ fn format_on_conflict(conflict: &config::OnConflict) -> String {
 /// Display configuration diff between old snapshot and new config.
 ///
 /// Shows which items were removed and which were added in each section
-/// (mkdir, link, copy, hooks) to help users understand what changed.
+/// (extends, profiles, mkdir, link, copy, hooks) to help users understand what changed.
 fn display_config_diff(old: &ConfigSnapshot, new: &config::Config, use_color: bool) {
     let new_snapshot = ConfigSnapshot::from_config(new);
 
@@ -432,6 +370,60 @@ fn display_config_diff(old: &ConfigSnapshot, new: &config::Config, use_color: bo
     );
     println!("────────────────────────────────────────────────────────");
 
+    // Compare extended files
+    if old.extends != new_snapshot.extends {
+        println!();
+        if use_color {
+            println!("{}", ColorScheme::operation("extends:"));
+        } else {
+            println!("extends:");
+        }
+
+        let (removed, added, order_changed) = diff_list(&old.extends, &new_snapshot.extends);
+        for item in removed {
+            println!("    {} {}", diff_prefix(use_color, false), item);
+        }
+        for item in added {
+            println!("    {} {}", diff_prefix(use_color, true), item);
+        }
+        if order_changed {
+            println!("    {}", order_changed_marker(use_color));
+        }
+    }
+
+    // Compare profiles; their hooks are listed in full above the diff
+    if old.profiles != new_snapshot.profiles || old.default_profile != new_snapshot.default_profile
+    {
+        println!();
+        if use_color {
+            println!("{}", ColorScheme::operation("profiles:"));
+        } else {
+            println!("profiles:");
+        }
+
+        for name in old.profiles.keys() {
+            if !new_snapshot.profiles.contains_key(name) {
+                println!("    {} {}", diff_prefix(use_color, false), name);
+            }
+        }
+        for (name, profile) in &new_snapshot.profiles {
+            match old.profiles.get(name) {
+                None => println!("    {} {}", diff_prefix(use_color, true), name),
+                Some(old_profile) if old_profile != profile => {
+                    println!("    ~ {} (changed)", name)
+                }
+                Some(_) => {}
+            }
+        }
+        if old.default_profile != new_snapshot.default_profile {
+            println!(
+                "    default_profile: {} -> {}",
+                old.default_profile.as_deref().unwrap_or("(none)"),
+                new_snapshot.default_profile.as_deref().unwrap_or("(none)")
+            );
+        }
+    }
+
     // Compare mkdir operations
     if old.mkdir != new_snapshot.mkdir {
         println!();
@@ -450,6 +442,9 @@ fn display_config_diff(old: &ConfigSnapshot, new: &config::Config, use_color: bo
             if let Some(desc) = &item.description {
                 println!("    {} description: {}", removed_prefix, desc);
             }
+            if let Some(when) = &item.when {
+                println!("    {} when: {}", removed_prefix, when);
+            }
         }
 
         for item in added {
@@ -457,6 +452,9 @@ fn display_config_diff(old: &ConfigSnapshot, new: &config::Config, use_color: bo
             if let Some(desc) = &item.description {
                 println!("    {} description: {}", added_prefix, desc);
             }
+            if let Some(when) = &item.when {
+                println!("    {} when: {}", added_prefix, when);
+            }
         }
 
         if order_changed {
@@ -479,6 +477,9 @@ fn display_config_diff(old: &ConfigSnapshot, new: &config::Config, use_color: bo
 
         for item in removed {
             println!("    {} source: \"{}\"", removed_prefix, item.source);
+            if let Some(root) = &item.source_root {
+                println!("    {} source_root: \"{}\"", removed_prefix, root);
+            }
             println!("    {} target: \"{}\"", removed_prefix, item.target);
             if let Some(conflict) = &item.on_conflict {
                 println!(
@@ -494,10 +495,16 @@ fn display_config_diff(old: &ConfigSnapshot, new: &config::Config, use_color: bo
                 "    {} ignore_tracked: {}",
                 removed_prefix, item.ignore_tracked
             );
+            if let Some(when) = &item.when {
+                println!("    {} when: {}", removed_prefix, when);
+            }
         }
 
         for item in added {
             println!("    {} source: \"{}\"", added_prefix, item.source);
+            if let Some(root) = &item.source_root {
+                println!("    {} source_root: \"{}\"", added_prefix, root);
+            }
             println!("    {} target: \"{}\"", added_prefix, item.target);
             if let Some(conflict) = &item.on_conflict {
                 println!(
@@ -513,6 +520,9 @@ fn display_config_diff(old: &ConfigSnapshot, new: &config::Config, use_color: bo
                 "    {} ignore_tracked: {}",
                 added_prefix, item.ignore_tracked
             );
+            if let Some(when) = &item.when {
+                println!("    {} when: {}", added_prefix, when);
+            }
         }
 
         if order_changed {
@@ -535,6 +545,9 @@ fn display_config_diff(old: &ConfigSnapshot, new: &config::Config, use_color: bo
 
         for item in removed {
             println!("    {} source: \"{}\"", removed_prefix, item.source);
+            if let Some(root) = &item.source_root {
+                println!("    {} source_root: \"{}\"", removed_prefix, root);
+            }
             println!("    {} target: \"{}\"", removed_prefix, item.target);
             if let Some(conflict) = &item.on_conflict {
                 println!(
@@ -546,10 +559,46 @@ fn display_config_diff(old: &ConfigSnapshot, new: &config::Config, use_color: bo
             if let Some(desc) = &item.description {
                 println!("    {} description: {}", removed_prefix, desc);
             }
+            if item.render {
+                println!("    {} render: true", removed_prefix);
+            }
+            if let Some(mode) = item.mode {
+                println!("    {} mode: {}", removed_prefix, mode.as_str());
+            }
+            if item.ignore_tracked {
+                println!("    {} ignore_tracked: true", removed_prefix);
+            }
+            if !item.include.is_empty() {
+                println!(
+                    "    {} include: {}",
+                    removed_prefix,
+                    item.include.join(", ")
+                );
+            }
+            if !item.exclude.is_empty() {
+                println!(
+                    "    {} exclude: {}",
+                    removed_prefix,
+                    item.exclude.join(", ")
+                );
+            }
+            if !item.preserve.is_empty() {
+                let preserve: Vec<_> = item.preserve.iter().map(|p| p.as_str()).collect();
+                println!("    {} preserve: {}", removed_prefix, preserve.join(", "));
+            }
+            if let Some(max_size) = item.max_size {
+                println!("    {} max_size: {}", removed_prefix, max_size);
+            }
+            if let Some(when) = &item.when {
+                println!("    {} when: {}", removed_prefix, when);
+            }
         }
 
         for item in added {
             println!("    {} source: \"{}\"", added_prefix, item.source);
+            if let Some(root) = &item.source_root {
+                println!("    {} source_root: \"{}\"", added_prefix, root);
+            }
             println!("    {} target: \"{}\"", added_prefix, item.target);
             if let Some(conflict) = &item.on_conflict {
                 println!(
@@ -561,6 +610,31 @@ fn display_config_diff(old: &ConfigSnapshot, new: &config::Config, use_color: bo
             if let Some(desc) = &item.description {
                 println!("    {} description: {}", added_prefix, desc);
             }
+            if item.render {
+                println!("    {} render: true", added_prefix);
+            }
+            if let Some(mode) = item.mode {
+                println!("    {} mode: {}", added_prefix, mode.as_str());
+            }
+            if item.ignore_tracked {
+                println!("    {} ignore_tracked: true", added_prefix);
+            }
+            if !item.include.is_empty() {
+                println!("    {} include: {}", added_prefix, item.include.join(", "));
+            }
+            if !item.exclude.is_empty() {
+                println!("    {} exclude: {}", added_prefix, item.exclude.join(", "));
+            }
+            if !item.preserve.is_empty() {
+                let preserve: Vec<_> = item.preserve.iter().map(|p| p.as_str()).collect();
+                println!("    {} preserve: {}", added_prefix, preserve.join(", "));
+            }
+            if let Some(max_size) = item.max_size {
+                println!("    {} max_size: {}", added_prefix, max_size);
+            }
+            if let Some(when) = &item.when {
+                println!("    {} when: {}", added_prefix, when);
+            }
         }
 
         if order_changed {
@@ -568,6 +642,39 @@ fn display_config_diff(old: &ConfigSnapshot, new: &config::Config, use_color: bo
         }
     }
 
+    // Compare include file
+    if old.include_file != new_snapshot.include_file {
+        println!();
+        if use_color {
+            println!("{}", ColorScheme::operation("include_file:"));
+        } else {
+            println!("include_file:");
+        }
+
+        let describe = |include_file: &config::IncludeFile| {
+            let path = include_file
+                .path
+                .as_ref()
+                .map(|path| path.display().to_string())
+                .unwrap_or_else(|| config::INCLUDE_FILES.join(" or "));
+            format!("{} ({})", path, include_file.mode.as_str())
+        };
+        if let Some(include_file) = &old.include_file {
+            println!(
+                "    {} {}",
+                diff_prefix(use_color, false),
+                describe(include_file)
+            );
+        }
+        if let Some(include_file) = &new_snapshot.include_file {
+            println!(
+                "    {} {}",
+                diff_prefix(use_color, true),
+                describe(include_file)
+            );
+        }
+    }
+
     // Compare hooks
     if old.hooks != new_snapshot.hooks {
         println!();
@@ -577,126 +684,42 @@ fn display_config_diff(old: &ConfigSnapshot, new: &config::Config, use_color: bo
             println!("hooks:");
         }
 
-        // Pre-add hooks
-        if old.hooks.pre_add != new_snapshot.hooks.pre_add {
-            if use_color {
-                println!("  {}", ColorScheme::hook_type("pre_add:"));
-            } else {
-                println!("  pre_add:");
+        let events = old
+            .hooks
+            .events()
+            .into_iter()
+            .zip(new_snapshot.hooks.events());
+        for ((hook_type, old_entries), (_, new_entries)) in events {
+            if old_entries == new_entries {
+                continue;
             }
-
-            let (removed, added, order_changed) =
-                diff_list(&old.hooks.pre_add, &new_snapshot.hooks.pre_add);
-            let removed_prefix = diff_prefix(use_color, false);
-            let added_prefix = diff_prefix(use_color, true);
-
-            for item in removed {
-                println!("    {} command: {}", removed_prefix, item.command);
-                if let Some(desc) = &item.description {
-                    println!("    {} description: {}", removed_prefix, desc);
-                }
-            }
-
-            for item in added {
-                println!("    {} command: {}", added_prefix, item.command);
-                if let Some(desc) = &item.description {
-                    println!("    {} description: {}", added_prefix, desc);
-                }
-            }
-
-            if order_changed {
-                println!("    {}", order_changed_marker(use_color));
-            }
-        }
-
-        // Post-add hooks
-        if old.hooks.post_add != new_snapshot.hooks.post_add {
             if use_color {
-                println!("  {}", ColorScheme::hook_type("post_add:"));
+                println!("  {}", ColorScheme::hook_type(&format!("{hook_type}:")));
             } else {
-                println!("  post_add:");
+                println!("  {hook_type}:");
             }
 
-            let (removed, added, order_changed) =
-                diff_list(&old.hooks.post_add, &new_snapshot.hooks.post_add);
+            let (removed, added, order_changed) = diff_list(old_entries, new_entries);
             let removed_prefix = diff_prefix(use_color, false);
             let added_prefix = diff_prefix(use_color, true);
 
             for item in removed {
-                println!("    {} command: {}", removed_prefix, item.command);
+                println!("    {} {}", removed_prefix, hook_diff_headline(&item));
                 if let Some(desc) = &item.description {
                     println!("    {} description: {}", removed_prefix, desc);
                 }
-            }
-
-            for item in added {
-                println!("    {} command: {}", added_prefix, item.command);
-                if let Some(desc) = &item.description {
-                    println!("    {} description: {}", added_prefix, desc);
-                }
-            }
-
-            if order_changed {
-                println!("    {}", order_changed_marker(use_color));
-            }
-        }
-
-        // Pre-remove hooks
-        if old.hooks.pre_remove != new_snapshot.hooks.pre_remove {
-            if use_color {
-                println!("  {}", ColorScheme::hook_type("pre_remove:"));
-            } else {
-                println!("  pre_remove:");
-            }
-
-            let (removed, added, order_changed) =
-                diff_list(&old.hooks.pre_remove, &new_snapshot.hooks.pre_remove);
-            let removed_prefix = diff_prefix(use_color, false);
-            let added_prefix = diff_prefix(use_color, true);
-
-            for item in removed {
-                println!("    {} command: {}", removed_prefix, item.command);
-                if let Some(desc) = &item.description {
-                    println!("    {} description: {}", removed_prefix, desc);
+                for line in item.option_lines() {
+                    println!("    {} {}", removed_prefix, line);
                 }
             }
 
             for item in added {
-                println!("    {} command: {}", added_prefix, item.command);
+                println!("    {} {}", added_prefix, hook_diff_headline(&item));
                 if let Some(desc) = &item.description {
                     println!("    {} description: {}", added_prefix, desc);
                 }
-            }
-
-            if order_changed {
-                println!("    {}", order_changed_marker(use_color));
-            }
-        }
-
-        // Post-remove hooks
-        if old.hooks.post_remove != new_snapshot.hooks.post_remove {
-            if use_color {
-                println!("  {}", ColorScheme::hook_type("post_remove:"));
-            } else {
-                println!("  post_remove:");
-            }
-
-            let (removed, added, order_changed) =
-                diff_list(&old.hooks.post_remove, &new_snapshot.hooks.post_remove);
-            let removed_prefix = diff_prefix(use_color, false);
-            let added_prefix = diff_prefix(use_color, true);
-
-            for item in removed {
-                println!("    {} command: {}", removed_prefix, item.command);
-                if let Some(desc) = &item.description {
-                    println!("    {} description: {}", removed_prefix, desc);
-                }
-            }
-
-            for item in added {
-                println!("    {} command: {}", added_prefix, item.command);
-                if let Some(desc) = &item.description {
-                    println!("    {} description: {}", added_prefix, desc);
+                for line in item.option_lines() {
+                    println!("    {} {}", added_prefix, line);
                 }
             }
 
@@ -12,6 +12,8 @@ pub(crate) enum TrustHint {
     SkipHooks { command: &'static str },
 }
 
+/// Load the merged config, requiring trust if hooks will run (`enforce_hooks`) or the
+/// config links or copies sources from outside the repository.
 pub(crate) fn load_config_with_trust_check(
     repo_root: &Path,
     main_worktree_path: &Path,
@@ -24,15 +26,18 @@ pub(crate) fn load_config_with_trust_check(
         config::merge_with_global(initial_repo_config.clone(), global_config.as_ref());
     color::set_cli_theme(&initial_config.ui.colors);
 
-    if enforce_hooks
-        && initial_repo_config.hooks.has_hooks()
+    if enforces_trust(enforce_hooks, &initial_repo_config)
+        && initial_repo_config.requires_trust()
         && !trust::is_trusted(main_worktree_path, &initial_repo_config)?
     {
-        hook::display_hooks_for_review(&initial_repo_config.hooks);
+        hook::display_hooks_for_review(&initial_repo_config);
 
         eprintln!();
         eprintln!("{}", ColorScheme::error("Configuration is not trusted."));
-        eprintln!("The config file contains hooks that can execute arbitrary commands.");
+        eprintln!(
+            "The config file {}.",
+            untrusted_content(&initial_repo_config)
+        );
         eprintln!("For security, you must explicitly review and trust the configuration.");
         eprintln!();
         eprintln!("To trust this configuration, run:");
@@ -47,14 +52,50 @@ pub(crate) fn load_config_with_trust_check(
         return Err(Error::HooksNotTrusted);
     }
 
+    if let Some(global_config) = &global_config
+        && enforces_trust(enforce_hooks, global_config)
+        && !is_global_trusted(global_config)?
+    {
+        hook::display_hooks_for_review(global_config);
+
+        eprintln!();
+        eprintln!(
+            "{}",
+            ColorScheme::error("Global configuration is not trusted.")
+        );
+        eprintln!(
+            "The global config file {}.",
+            untrusted_content(global_config)
+        );
+        eprintln!("For security, you must explicitly review and trust the configuration.");
+        eprintln!();
+        eprintln!("To trust the global configuration, run:");
+        eprintln!("  kabu trust --global");
+
+        if let TrustHint::SkipHooks { command } = hint {
+            eprintln!();
+            eprintln!("Or skip hooks:");
+            eprintln!("  {command}");
+        }
+
+        return Err(Error::HooksNotTrusted);
+    }
+
     // TOCTOU protection: reload config immediately before use
+    let global_config = config::load_global()?;
     let repo_config = config::load(repo_root)?.unwrap_or_default();
     let config = config::merge_with_global(repo_config.clone(), global_config.as_ref());
     color::set_cli_theme(&config.ui.colors);
-    if enforce_hooks
-        && repo_config.hooks.has_hooks()
-        && !trust::is_trusted(main_worktree_path, &repo_config)?
-    {
+    let modified = (enforces_trust(enforce_hooks, &repo_config)
+        && repo_config.requires_trust()
+        && !trust::is_trusted(main_worktree_path, &repo_config)?)
+        || match &global_config {
+            Some(global_config) => {
+                enforces_trust(enforce_hooks, global_config) && !is_global_trusted(global_config)?
+            }
+            None => false,
+        };
+    if modified {
         eprintln!(
             "{}",
             ColorScheme::error("Config file was modified after trust check.")
@@ -66,3 +107,31 @@ pub(crate) fn load_config_with_trust_check(
 
     Ok(config)
 }
+
+/// Whether trust is checked for a config: sources outside the repository are linked or
+/// copied by every setup, also when no hooks run.
+fn enforces_trust(enforce_hooks: bool, config: &Config) -> bool {
+    enforce_hooks || !config.external_sources().is_empty()
+}
+
+/// What makes a config need trust, for the error message.
+fn untrusted_content(config: &Config) -> &'static str {
+    if config.has_hooks() {
+        "contains hooks that can execute arbitrary commands"
+    } else {
+        "links or copies files from outside the repository"
+    }
+}
+
+/// Whether the hooks of the global config are trusted.
+///
+/// The global config is trusted for its own file, independent of any repository.
+pub(crate) fn is_global_trusted(global_config: &Config) -> Result<bool> {
+    if !global_config.requires_trust() {
+        return Ok(true);
+    }
+    match config::global_config_file() {
+        Some(path) => trust::is_trusted(&path, global_config),
+        None => Ok(false),
+    }
+}
@@ -0,0 +1,34 @@
+//! Unlock command implementation.
+//!
+//! Unlocks a worktree/workspace locked with `kabu lock` (or `git worktree lock`).
+
+use crate::cli::UnlockArgs;
+use crate::color::ColorConfig;
+use crate::command::remove::{find_current_worktree, resolve_worktree_paths};
+use crate::error::{Error, Result};
+use crate::output::Output;
+use crate::vcs;
+
+pub(crate) fn run(args: UnlockArgs, color: ColorConfig) -> Result<()> {
+    let output = Output::new(args.quiet, color);
+
+    let provider = vcs::get_provider()?;
+
+    if !provider.is_inside_repo() {
+        return Err(Error::NotInAnyRepo);
+    }
+
+    let worktrees = provider.list_workspaces()?;
+    let path = match &args.path {
+        Some(path) => resolve_worktree_paths(std::slice::from_ref(path), &worktrees)?.remove(0),
+        None => find_current_worktree(&worktrees)?,
+    };
+
+    if !worktrees.iter().any(|wt| wt.path == path && wt.is_locked) {
+        return Err(Error::WorktreeNotLocked { path });
+    }
+
+    provider.workspace_unlock(&path)?;
+    output.unlock(&path);
+    Ok(())
+}
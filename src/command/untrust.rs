@@ -16,6 +16,17 @@ pub(crate) fn run(args: UntrustArgs) -> Result<()> {
         return Ok(());
     }
 
+    if args.global {
+        let path = config::global_config_file().ok_or(Error::GlobalConfigNotFound)?;
+        let config = config::load_global()?.ok_or(Error::GlobalConfigNotFound)?;
+        if trust::untrust(&path, &config)? {
+            println!("Untrusted global configuration: {}", path.display());
+        } else {
+            println!("Global configuration was not trusted: {}", path.display());
+        }
+        return Ok(());
+    }
+
     let provider = vcs::get_provider()?;
     let repo_root = match args.path {
         Some(p) => p.canonicalize()?,
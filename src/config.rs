@@ -1,6 +1,6 @@
 use crate::error::{Error, Result};
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -19,6 +19,8 @@ const GLOBAL_CONFIG_DIR_NAME: &str = "kabu";
 pub const GLOBAL_CONFIG_FILE_NAME_YAML: &str = "config.yaml";
 /// Global config file name for TOML format
 pub const GLOBAL_CONFIG_FILE_NAME_TOML: &str = "config.toml";
+/// Prefix of `extends:` entries that are looked up in the global config dir
+const GLOBAL_EXTENDS_PREFIX: &str = "global:";
 
 #[derive(Debug, Clone, Copy)]
 enum ConfigFormat {
@@ -69,14 +71,7 @@ pub(crate) fn load(repo_root: &Path) -> Result<Option<Config>> {
             None => return Ok(None),
         };
 
-    let content = fs::read_to_string(&config_path)?;
-
-    // Parse into RawConfig (permissive, all fields optional)
-    let raw: RawConfig =
-        parse_raw_config(&content, format).map_err(|message| Error::ConfigParse { message })?;
-
-    // Convert to Config (validates and transforms)
-    Config::try_from(raw).map(Some)
+    load_composed(&config_path, format, ConfigScope::Repo(repo_root)).map(Some)
 }
 
 /// Load global config. Returns None if config file doesn't exist or config dir is unknown.
@@ -95,19 +90,26 @@ pub(crate) fn load_global() -> Result<Option<Config>> {
         None => return Ok(None),
     };
 
-    let content = fs::read_to_string(&config_path)?;
-
-    let raw: RawConfig = parse_raw_config(&content, format)
-        .map_err(|message| Error::GlobalConfigParse { message })?;
-
-    validate_global_config(&raw)?;
+    load_composed(&config_path, format, ConfigScope::Global(&config_dir)).map(Some)
+}
 
-    let config = Config::try_from(raw).map_err(|err| match err {
-        Error::ConfigValidation { message } => Error::GlobalConfigValidation { message },
-        other => other,
-    })?;
+/// Path of the global config file, if there is one.
+///
+/// Hooks of the global config are trusted for this path rather than for a repository.
+pub(crate) fn global_config_file() -> Option<PathBuf> {
+    let config_dir = global_config_dir()?;
+    find_config_file(
+        &config_dir,
+        GLOBAL_CONFIG_FILE_NAME_YAML,
+        GLOBAL_CONFIG_FILE_NAME_TOML,
+    )
+    .map(|(path, _)| path)
+}
 
-    Ok(Some(config))
+/// Whether `path` is a global config file path, as used by trust entries of the global
+/// config.
+pub(crate) fn is_global_config_path(path: &Path) -> bool {
+    global_config_dir().is_some_and(|dir| path.parent() == Some(dir.as_path()))
 }
 
 /// Load config merged with global config. Repo config overrides global settings.
@@ -120,17 +122,8 @@ pub(crate) fn load_merged(repo_root: &Path) -> Result<Config> {
 fn validate_global_config(raw: &RawConfig) -> Result<()> {
     let mut errors = Vec::new();
 
-    if raw.hooks.has_hooks() {
-        errors.push("  - hooks are not allowed in global config".to_string());
-    }
-    if !raw.mkdir.is_empty() {
-        errors.push("  - mkdir entries are not allowed in global config".to_string());
-    }
-    if !raw.link.is_empty() {
-        errors.push("  - link entries are not allowed in global config".to_string());
-    }
-    if !raw.copy.is_empty() {
-        errors.push("  - copy entries are not allowed in global config".to_string());
+    if !raw.profiles.is_empty() || raw.default_profile.is_some() {
+        errors.push("  - profiles are not allowed in global config".to_string());
     }
 
     if errors.is_empty() {
@@ -142,6 +135,204 @@ fn validate_global_config(raw: &RawConfig) -> Result<()> {
     }
 }
 
+/// Where a config file and the files it extends belong.
+#[derive(Debug, Clone, Copy)]
+enum ConfigScope<'a> {
+    /// Repository config; relative `extends:` paths resolve against the repository root.
+    Repo(&'a Path),
+    /// Global config; relative `extends:` paths resolve against the global config dir.
+    Global(&'a Path),
+}
+
+impl ConfigScope<'_> {
+    fn base_dir(&self) -> &Path {
+        match self {
+            ConfigScope::Repo(dir) | ConfigScope::Global(dir) => dir,
+        }
+    }
+
+    /// Path as shown in errors and recorded for trust: relative to the repository
+    /// root when inside it, so every worktree records the same path.
+    fn display_path(&self, path: &Path) -> PathBuf {
+        match self {
+            ConfigScope::Repo(root) => path.strip_prefix(root).unwrap_or(path).to_path_buf(),
+            ConfigScope::Global(_) => path.to_path_buf(),
+        }
+    }
+
+    fn parse_error(&self, message: String) -> Error {
+        match self {
+            ConfigScope::Repo(_) => Error::ConfigParse { message },
+            ConfigScope::Global(_) => Error::GlobalConfigParse { message },
+        }
+    }
+
+    fn validation_error(&self, message: String) -> Error {
+        match self {
+            ConfigScope::Repo(_) => Error::ConfigValidation { message },
+            ConfigScope::Global(_) => Error::GlobalConfigValidation { message },
+        }
+    }
+}
+
+/// A parsed config file of an `extends:` chain.
+struct ConfigFile {
+    path: PathBuf,
+    canonical: PathBuf,
+    raw: RawConfig,
+}
+
+/// Load a config file together with the files it extends.
+///
+/// Every file is validated on its own, so errors name the file they came from when
+/// more than one file is involved. The files are then merged with [`extend_config`],
+/// extended files first.
+fn load_composed(config_path: &Path, format: ConfigFormat, scope: ConfigScope) -> Result<Config> {
+    let mut files = Vec::new();
+    collect_config_files(config_path, format, scope, &mut Vec::new(), &mut files)?;
+
+    let composed = files.len() > 1;
+    let mut errors = Vec::new();
+    let mut configs = Vec::with_capacity(files.len());
+    for file in files {
+        let result = match scope {
+            ConfigScope::Global(_) => validate_global_config(&file.raw),
+            ConfigScope::Repo(_) => Ok(()),
+        }
+        .and_then(|()| Config::try_from(file.raw));
+
+        match result {
+            Ok(config) => configs.push((file.path, config)),
+            Err(
+                Error::ConfigValidation { message } | Error::GlobalConfigValidation { message },
+            ) => {
+                if composed {
+                    errors.push(format!(
+                        "{}:\n{message}",
+                        scope.display_path(&file.path).display()
+                    ));
+                } else {
+                    errors.push(message);
+                }
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(scope.validation_error(errors.join("\n")));
+    }
+
+    // The config file itself comes last, after everything it extends
+    let extends = configs[..configs.len().saturating_sub(1)]
+        .iter()
+        .map(|(path, _)| scope.display_path(path))
+        .collect();
+    let mut config = configs
+        .into_iter()
+        .map(|(_, config)| config)
+        .reduce(extend_config)
+        .unwrap_or_default();
+    config.extends = extends;
+
+    // Profiles may come from a different file than the default_profile naming them
+    if let Some(name) = &config.default_profile
+        && !config.profiles.contains_key(name)
+    {
+        return Err(
+            scope.validation_error(format!("  - default_profile: no profile named '{name}'"))
+        );
+    }
+    Ok(config)
+}
+
+/// Parse a config file and, depth first, the files it extends.
+///
+/// Files end up in `files` in merge order. A file extended more than once is only
+/// loaded the first time; a file that extends itself through the chain is an error.
+fn collect_config_files(
+    path: &Path,
+    format: ConfigFormat,
+    scope: ConfigScope,
+    chain: &mut Vec<PathBuf>,
+    files: &mut Vec<ConfigFile>,
+) -> Result<()> {
+    let content = fs::read_to_string(path)?;
+    let raw = parse_raw_config(&content, format).map_err(|message| {
+        if chain.is_empty() {
+            scope.parse_error(message)
+        } else {
+            scope.parse_error(format!("{}: {message}", scope.display_path(path).display()))
+        }
+    })?;
+
+    let canonical = path.canonicalize()?;
+    chain.push(canonical.clone());
+
+    for (i, entry) in raw.extends.iter().enumerate() {
+        let invalid = |reason: String| {
+            scope.validation_error(format!(
+                "{}:\n  - extends[{i}]: {reason}",
+                scope.display_path(path).display()
+            ))
+        };
+
+        let extended = resolve_extends(entry, scope)
+            .ok_or_else(|| invalid("global config directory is unknown".to_string()))?;
+        let Ok(extended_canonical) = extended.canonicalize() else {
+            return Err(invalid(format!("file not found: {}", extended.display())));
+        };
+        if chain.contains(&extended_canonical) {
+            return Err(invalid(format!(
+                "extends itself through {}",
+                extended.display()
+            )));
+        }
+        if files
+            .iter()
+            .any(|file| file.canonical == extended_canonical)
+        {
+            continue;
+        }
+
+        let extended_format = match extended.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => ConfigFormat::Toml,
+            _ => ConfigFormat::Yaml,
+        };
+        collect_config_files(&extended, extended_format, scope, chain, files)?;
+    }
+
+    chain.pop();
+    files.push(ConfigFile {
+        path: path.to_path_buf(),
+        canonical,
+        raw,
+    });
+    Ok(())
+}
+
+/// Resolve an `extends:` entry.
+///
+/// Entries starting with `global:` are looked up in the global config dir, absolute
+/// and `~/` paths are used as is, and any other relative path, a bare file name
+/// included, is resolved against the base dir of the scope. Returns None if the
+/// global config dir is unknown.
+fn resolve_extends(entry: &Path, scope: ConfigScope) -> Option<PathBuf> {
+    if let Some(name) = entry
+        .to_str()
+        .and_then(|entry| entry.strip_prefix(GLOBAL_EXTENDS_PREFIX))
+    {
+        return global_config_dir().map(|dir| dir.join(name));
+    }
+    if entry.is_absolute() {
+        return Some(entry.to_path_buf());
+    }
+    if let Ok(rest) = entry.strip_prefix("~") {
+        return dirs::home_dir().map(|home| home.join(rest));
+    }
+    Some(scope.base_dir().join(entry))
+}
+
 // Raw types for permissive YAML parsing. Missing fields get default values
 // instead of parse errors, allowing validation to collect all errors at once.
 
@@ -152,12 +343,21 @@ fn validate_global_config(raw: &RawConfig) -> Result<()> {
     description = "Configuration file for kabu"
 )]
 pub(crate) struct RawConfig {
+    #[schemars(
+        description = "Config files this one builds on. Entries starting with global: are looked up in the global config directory, other relative paths (bare file names included) in the repository root. Settings here override theirs; hooks are appended and mkdir/link/copy entries replace theirs with the same target."
+    )]
+    #[serde(default)]
+    extends: Vec<PathBuf>,
     on_conflict: Option<OnConflict>,
     #[serde(default)]
     auto_cd: RawAutoCd,
     #[serde(default)]
     worktree: RawWorktree,
     #[serde(default)]
+    prune: RawPrune,
+    #[serde(default)]
+    ports: BTreeMap<String, RawPort>,
+    #[serde(default)]
     ui: RawUi,
     #[serde(default)]
     hooks: RawHooks,
@@ -167,6 +367,33 @@ pub(crate) struct RawConfig {
     link: Vec<RawLink>,
     #[serde(default)]
     copy: Vec<RawCopy>,
+    include_file: Option<RawIncludeFile>,
+    #[schemars(
+        description = "Named sets of extra hooks and mkdir/link/copy entries, selected with kabu add --profile"
+    )]
+    #[serde(default)]
+    profiles: BTreeMap<String, RawProfile>,
+    #[schemars(description = "Profile applied when kabu add is run without --profile")]
+    default_profile: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(
+    rename = "Profile",
+    title = "Profile",
+    description = "Hooks and mkdir/link/copy entries applied on top of the top-level ones"
+)]
+struct RawProfile {
+    description: Option<String>,
+    #[serde(default)]
+    hooks: RawHooks,
+    #[serde(default)]
+    mkdir: Vec<RawMkdir>,
+    #[serde(default)]
+    link: Vec<RawLink>,
+    #[serde(default)]
+    copy: Vec<RawCopy>,
 }
 
 #[derive(Debug, Deserialize, Default, JsonSchema)]
@@ -193,6 +420,61 @@ struct RawWorktree {
     branch_template: Option<String>,
 }
 
+#[derive(Debug, Deserialize, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(
+    rename = "Prune",
+    title = "Prune",
+    description = "Configuration for kabu prune"
+)]
+struct RawPrune {
+    #[schemars(
+        description = "Branch (git) or revset (jj) to check for merged worktrees (default: main worktree branch for git, trunk() for jj)"
+    )]
+    base: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(
+    rename = "IncludeFile",
+    title = "Include File",
+    description = "Link or copy the files listed in a gitignore-style include file (.worktreeinclude or .kabu/include)"
+)]
+struct RawIncludeFile {
+    #[schemars(
+        description = "Include file relative to the repository root (default: .worktreeinclude, then .kabu/include)"
+    )]
+    path: Option<PathBuf>,
+    #[schemars(description = "Whether matching files are linked or copied")]
+    mode: Option<IncludeMode>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(untagged)]
+#[schemars(
+    rename = "PortEntry",
+    title = "Port Entry",
+    description = "Base port (e.g. 3000), or a base port with a per-worktree block size"
+)]
+enum RawPort {
+    Base(u16),
+    Range(RawPortRange),
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(
+    rename = "PortRange",
+    title = "Port Range",
+    description = "Block of ports handed to each worktree, starting at base + slot * size"
+)]
+struct RawPortRange {
+    base: u16,
+    #[schemars(description = "Number of ports per worktree (default: 1)")]
+    size: Option<u16>,
+}
+
 #[derive(Debug, Deserialize, Default, JsonSchema)]
 #[serde(deny_unknown_fields)]
 #[schemars(
@@ -276,16 +558,380 @@ pub(crate) enum AddDefaultMode {
 }
 
 /// Hook entry with command and optional description.
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
+///
+/// An entry with `parallel:` instead of `command:` is a group whose commands run at
+/// the same time. The execution options are omitted from serialization when unset,
+/// so trust hashes of configs that don't use them stay unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 #[schemars(
     title = "Hook Entry",
-    description = "A hook command with optional description"
+    description = "A hook command with optional description, or a group of parallel commands"
 )]
 pub(crate) struct HookEntry {
+    /// Shell command to run (required unless `parallel` is set)
+    #[serde(default)]
     pub command: String,
     #[serde(default)]
     pub description: Option<String>,
+    /// Commands to run at the same time instead of `command`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parallel: Vec<HookEntry>,
+    /// How the output of a `parallel` group is shown (default: prefix)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<ParallelOutput>,
+    /// Only run the hook when these conditions hold
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<When>,
+    /// Kill the hook after this many seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// Extra environment variables (values support template variables)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Working directory, relative to the directory the hook normally runs in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<PathBuf>,
+    /// Number of times to re-run the hook after a failure
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub retries: u32,
+    /// Report a failure as a warning and continue with the next hook
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub continue_on_error: bool,
+}
+
+impl HookEntry {
+    /// Whether this entry is a `parallel:` group.
+    pub(crate) fn is_parallel(&self) -> bool {
+        !self.parallel.is_empty()
+    }
+
+    /// Command line shown for review: the command, or `parallel:` for a group.
+    pub(crate) fn headline(&self) -> &str {
+        if self.is_parallel() {
+            "parallel:"
+        } else {
+            &self.command
+        }
+    }
+
+    /// The commands this entry runs: itself, or the members of a `parallel:` group.
+    pub(crate) fn commands(&self) -> impl Iterator<Item = &HookEntry> {
+        let group = self.is_parallel();
+        std::iter::once(self)
+            .filter(move |_| !group)
+            .chain(self.parallel.iter())
+    }
+
+    /// Execution options set on this hook, formatted for review (e.g. `timeout: 300s`).
+    ///
+    /// For a `parallel:` group, the member commands are listed with their own options.
+    pub(crate) fn option_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(when) = &self.when {
+            lines.push(format!("when: {when}"));
+        }
+        if let Some(output) = self.output {
+            lines.push(format!("output: {}", output.as_str()));
+        }
+        for entry in &self.parallel {
+            lines.push(format!("- {}", entry.command));
+            if let Some(desc) = &entry.description {
+                lines.push(format!("  description: {desc}"));
+            }
+            lines.extend(entry.option_lines().iter().map(|line| format!("  {line}")));
+        }
+        if let Some(timeout) = self.timeout {
+            lines.push(format!("timeout: {timeout}s"));
+        }
+        if let Some(cwd) = &self.cwd {
+            lines.push(format!("cwd: {}", cwd.display()));
+        }
+        for (key, value) in &self.env {
+            lines.push(format!("env: {key}={value}"));
+        }
+        if self.retries > 0 {
+            lines.push(format!("retries: {}", self.retries));
+        }
+        if self.continue_on_error {
+            lines.push("continue_on_error: true".to_string());
+        }
+        lines
+    }
+}
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+/// How the output of commands in a `parallel:` hook group is shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ParallelOutput {
+    /// Stream each line as it arrives, prefixed with the command
+    #[default]
+    Prefix,
+    /// Collect each command's output and print it in one block when it finishes
+    Buffer,
+}
+
+impl ParallelOutput {
+    fn as_str(self) -> &'static str {
+        match self {
+            ParallelOutput::Prefix => "prefix",
+            ParallelOutput::Buffer => "buffer",
+        }
+    }
+}
+
+/// Operating systems accepted by `when.os`. `unix` matches every Unix-like system.
+const WHEN_OS_VALUES: &[&str] = &["linux", "macos", "windows", "freebsd", "unix"];
+
+/// Condition under which a hook or mkdir/link/copy entry applies.
+///
+/// Every condition that is set must hold. Unset conditions are omitted from
+/// serialization, so they don't affect trust hashes.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(
+    title = "When",
+    description = "Conditions that must all hold for the entry to apply"
+)]
+pub(crate) struct When {
+    /// Path that must exist, relative to the worktree (hooks: to the directory they run in)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_exists: Option<PathBuf>,
+    /// Version control system of the repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vcs: Option<WhenVcs>,
+    /// Glob the branch (git) or bookmark (jj) name must match, e.g. `release/*`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Operating system: linux, macos, windows, freebsd or unix
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os: Option<String>,
+    /// Environment variable that must be set and non-empty (`NAME`), or equal a value (`NAME=value`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<String>,
+}
+
+/// VCS matched by `when.vcs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum WhenVcs {
+    Git,
+    Jj,
+}
+
+impl WhenVcs {
+    fn as_str(self) -> &'static str {
+        match self {
+            WhenVcs::Git => "git",
+            WhenVcs::Jj => "jj",
+        }
+    }
+}
+
+/// What `when:` conditions are evaluated against.
+pub(crate) struct WhenContext<'a> {
+    /// VCS name: "git" or "jj"
+    pub vcs: &'a str,
+    /// Branch (git) or bookmark (jj) name, if any
+    pub branch: Option<&'a str>,
+    /// Directory `file_exists` paths are resolved against
+    pub base_dir: &'a Path,
+}
+
+impl When {
+    /// Check every condition against the context.
+    pub(crate) fn matches(&self, ctx: &WhenContext) -> bool {
+        if let Some(path) = &self.file_exists
+            && !ctx.base_dir.join(path).exists()
+        {
+            return false;
+        }
+        if let Some(vcs) = self.vcs
+            && vcs.as_str() != ctx.vcs
+        {
+            return false;
+        }
+        if let Some(pattern) = &self.branch {
+            let branch = ctx
+                .branch
+                .map(|b| b.strip_prefix("refs/heads/").unwrap_or(b));
+            let matched = match (branch, globset::Glob::new(pattern)) {
+                (Some(branch), Ok(glob)) => glob.compile_matcher().is_match(branch),
+                _ => false,
+            };
+            if !matched {
+                return false;
+            }
+        }
+        if let Some(os) = &self.os {
+            let matched = match os.as_str() {
+                "unix" => cfg!(unix),
+                os => os == env::consts::OS,
+            };
+            if !matched {
+                return false;
+            }
+        }
+        if let Some(var) = &self.env {
+            let matched = match var.split_once('=') {
+                Some((name, expected)) => env::var(name).is_ok_and(|value| value == expected),
+                None => env::var_os(var).is_some_and(|value| !value.is_empty()),
+            };
+            if !matched {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Validation errors for this clause, prefixed with `prefix` (e.g. `link[0].when`).
+    fn validate(&self, prefix: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+        if let Some(path) = &self.file_exists
+            && let Some(err) = validate_path(path)
+        {
+            errors.push(format!("  - {prefix}.file_exists: {err}"));
+        }
+        if let Some(pattern) = &self.branch
+            && let Err(e) = globset::Glob::new(pattern)
+        {
+            errors.push(format!("  - {prefix}.branch: invalid glob pattern: {e}"));
+        }
+        if let Some(os) = &self.os
+            && !WHEN_OS_VALUES.contains(&os.as_str())
+        {
+            errors.push(format!(
+                "  - {prefix}.os: unknown operating system: {os} (expected one of: {})",
+                WHEN_OS_VALUES.join(", ")
+            ));
+        }
+        if let Some(var) = &self.env {
+            let name = var.split_once('=').map_or(var.as_str(), |(name, _)| name);
+            if name.is_empty() || name.contains('\0') {
+                errors.push(format!("  - {prefix}.env: invalid variable name: {var:?}"));
+            }
+        }
+        errors
+    }
+}
+
+impl std::fmt::Display for When {
+    /// Formats the set conditions for display, e.g. `os: linux, branch: release/*`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut conditions = Vec::new();
+        if let Some(path) = &self.file_exists {
+            conditions.push(format!("file_exists: {}", path.display()));
+        }
+        if let Some(vcs) = self.vcs {
+            conditions.push(format!("vcs: {}", vcs.as_str()));
+        }
+        if let Some(branch) = &self.branch {
+            conditions.push(format!("branch: {branch}"));
+        }
+        if let Some(os) = &self.os {
+            conditions.push(format!("os: {os}"));
+        }
+        if let Some(var) = &self.env {
+            conditions.push(format!("env: {var}"));
+        }
+        if conditions.is_empty() {
+            write!(f, "always")
+        } else {
+            write!(f, "{}", conditions.join(", "))
+        }
+    }
+}
+
+/// Directory the source of a link/copy entry is relative to (`source_root:`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) enum SourceRoot {
+    /// Root of the worktree/workspace kabu runs in
+    #[default]
+    Repo,
+    /// Main worktree (git) or default workspace (jj)
+    MainWorktree,
+    /// The user's home directory
+    Home,
+    /// Absolute or `~/` path; `{{repository}}` is expanded
+    Path(String),
+}
+
+/// What `source_root:` values are resolved against.
+pub(crate) struct SourceContext {
+    pub repo_root: PathBuf,
+    pub main_worktree_path: PathBuf,
+    /// Repository name for `{{repository}}`
+    pub repository: String,
+}
+
+impl SourceRoot {
+    fn parse(value: &str) -> std::result::Result<Self, String> {
+        match value {
+            "repo" => Ok(SourceRoot::Repo),
+            "main_worktree" => Ok(SourceRoot::MainWorktree),
+            "home" => Ok(SourceRoot::Home),
+            _ if value == "~"
+                || value.starts_with("~/")
+                || value.starts_with('/')
+                || Path::new(value).is_absolute() =>
+            {
+                Ok(SourceRoot::Path(value.to_string()))
+            }
+            _ => Err(format!(
+                "must be repo, main_worktree, home, or an absolute or ~/ path: {value}"
+            )),
+        }
+    }
+
+    /// Whether sources resolved against this root can lie outside the repository.
+    pub(crate) fn is_outside_repo(&self) -> bool {
+        matches!(self, SourceRoot::Home | SourceRoot::Path(_))
+    }
+
+    /// Resolve the directory sources are relative to.
+    pub(crate) fn resolve(&self, ctx: &SourceContext) -> Result<PathBuf> {
+        match self {
+            SourceRoot::Repo => Ok(ctx.repo_root.clone()),
+            SourceRoot::MainWorktree => Ok(ctx.main_worktree_path.clone()),
+            SourceRoot::Home => dirs::home_dir().ok_or(Error::HomeDirNotFound),
+            SourceRoot::Path(template) => {
+                let path = template.replace("{{repository}}", &ctx.repository);
+                match path.strip_prefix('~') {
+                    Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+                        let home = dirs::home_dir().ok_or(Error::HomeDirNotFound)?;
+                        Ok(home.join(rest.trim_start_matches('/')))
+                    }
+                    _ => Ok(PathBuf::from(path)),
+                }
+            }
+        }
+    }
+
+    /// How the root is shown in front of a source path, e.g. `~/secrets/{{repository}}`.
+    fn display_dir(&self) -> &str {
+        match self {
+            SourceRoot::Repo => ".",
+            SourceRoot::MainWorktree => "main_worktree",
+            SourceRoot::Home => "~",
+            SourceRoot::Path(template) => template.trim_end_matches('/'),
+        }
+    }
+}
+
+impl std::fmt::Display for SourceRoot {
+    /// Formats the root as it is written in the config.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SourceRoot::Repo => write!(f, "repo"),
+            SourceRoot::MainWorktree => write!(f, "main_worktree"),
+            SourceRoot::Home => write!(f, "home"),
+            SourceRoot::Path(template) => write!(f, "{template}"),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Default, JsonSchema)]
@@ -299,21 +945,24 @@ struct RawHooks {
     hook_shell: Option<String>,
     #[serde(default)]
     pre_add: Vec<HookEntry>,
+    /// Run after the worktree is created, before mkdir/link/copy
+    #[serde(default)]
+    post_create: Vec<HookEntry>,
     #[serde(default)]
     post_add: Vec<HookEntry>,
     #[serde(default)]
     pre_remove: Vec<HookEntry>,
     #[serde(default)]
     post_remove: Vec<HookEntry>,
-}
-
-impl RawHooks {
-    fn has_hooks(&self) -> bool {
-        !self.pre_add.is_empty()
-            || !self.post_add.is_empty()
-            || !self.pre_remove.is_empty()
-            || !self.post_remove.is_empty()
-    }
+    /// Run in the worktree before `kabu move` moves it
+    #[serde(default)]
+    pre_move: Vec<HookEntry>,
+    /// Run in the worktree after `kabu move` moved it
+    #[serde(default)]
+    post_move: Vec<HookEntry>,
+    /// Run by shell integration when `kabu cd` or auto_cd enters a worktree
+    #[serde(default)]
+    on_enter: Vec<HookEntry>,
 }
 
 #[derive(Debug, Deserialize, Default, JsonSchema)]
@@ -327,6 +976,7 @@ struct RawMkdir {
     #[serde(default)]
     path: PathBuf,
     description: Option<String>,
+    when: Option<When>,
 }
 
 #[derive(Debug, Deserialize, Default, JsonSchema)]
@@ -339,11 +989,15 @@ struct RawMkdir {
 struct RawLink {
     #[serde(default)]
     source: PathBuf,
+    /// Directory the source is relative to: repo (default), main_worktree, home, or an
+    /// absolute or ~/ path that may contain {{repository}}
+    source_root: Option<String>,
     target: Option<PathBuf>,
     on_conflict: Option<OnConflict>,
     description: Option<String>,
     #[serde(default)]
     ignore_tracked: bool,
+    when: Option<When>,
 }
 
 #[derive(Debug, Deserialize, Default, JsonSchema)]
@@ -356,9 +1010,37 @@ struct RawLink {
 struct RawCopy {
     #[serde(default)]
     source: PathBuf,
+    /// Directory the source is relative to: repo (default), main_worktree, home, or an
+    /// absolute or ~/ path that may contain {{repository}}
+    source_root: Option<String>,
     target: Option<PathBuf>,
     on_conflict: Option<OnConflict>,
     description: Option<String>,
+    #[serde(default)]
+    render: bool,
+    mode: Option<CopyMode>,
+    #[serde(default)]
+    ignore_tracked: bool,
+    /// Only copy files of a directory source matching one of these globs
+    #[serde(default)]
+    include: Vec<String>,
+    /// Skip files and directories of a directory source matching one of these globs
+    #[serde(default)]
+    exclude: Vec<String>,
+    /// Keep symlinks as symlinks, and carry over file permissions and modification times
+    #[serde(default)]
+    preserve: Vec<Preserve>,
+    /// Refuse to copy more than this many bytes (a number, or e.g. 500MB)
+    max_size: Option<RawSize>,
+    when: Option<When>,
+}
+
+/// Size in bytes, or as text with a unit.
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum RawSize {
+    Bytes(u64),
+    Text(String),
 }
 
 // Validated types used by the application. Guaranteed valid after TryFrom conversion.
@@ -369,16 +1051,188 @@ pub(crate) struct Config {
     pub on_conflict: Option<OnConflict>,
     pub auto_cd: AutoCd,
     pub worktree: Worktree,
+    pub prune: Prune,
+    pub ports: BTreeMap<String, Port>,
     pub ui: Ui,
     pub hooks: Hooks,
     pub mkdir: Vec<Mkdir>,
     pub link: Vec<Link>,
     pub copy: Vec<Copy>,
+    pub include_file: Option<IncludeFile>,
+    /// Files this config was composed from through `extends:`, in merge order.
+    pub extends: Vec<PathBuf>,
+    pub profiles: BTreeMap<String, Profile>,
+    pub default_profile: Option<String>,
+    /// Profile applied by [`Config::with_profile`]
+    pub profile: Option<String>,
 }
 
-pub(crate) fn merge_with_global(mut repo: Config, global: Option<&Config>) -> Config {
-    let Some(global) = global else {
-        return repo;
+impl Config {
+    /// Check if any hooks are defined, including the hooks of profiles.
+    pub(crate) fn has_hooks(&self) -> bool {
+        self.hooks.has_hooks() || self.profiles.values().any(|p| p.hooks.has_hooks())
+    }
+
+    /// Whether the config has to be trusted: it has hooks, or it links or copies files
+    /// from outside the repository.
+    pub(crate) fn requires_trust(&self) -> bool {
+        self.has_hooks() || !self.external_sources().is_empty()
+    }
+
+    /// Link/copy entries whose source can lie outside the repository, including those of
+    /// profiles, formatted for review (e.g. `link: ~/secrets/{{repository}}/.env -> .env`).
+    pub(crate) fn external_sources(&self) -> Vec<String> {
+        let mut sources = Vec::new();
+        let setups = std::iter::once((String::new(), &self.link, &self.copy)).chain(
+            self.profiles
+                .iter()
+                .map(|(name, p)| (format!("profiles.{name}."), &p.link, &p.copy)),
+        );
+        for (prefix, link, copy) in setups {
+            let entries = link
+                .iter()
+                .map(|l| ("link", &l.source_root, &l.source, &l.target))
+                .chain(
+                    copy.iter()
+                        .map(|c| ("copy", &c.source_root, &c.source, &c.target)),
+                );
+            for (op, root, source, target) in entries {
+                if root.is_outside_repo() {
+                    sources.push(format!(
+                        "{prefix}{op}: {}/{} -> {}",
+                        root.display_dir(),
+                        source.display(),
+                        target.display()
+                    ));
+                }
+            }
+        }
+        sources
+    }
+
+    /// All hook events with their entries, followed by the events of each profile
+    /// (labeled `profiles.<name>.<event>`).
+    pub(crate) fn hook_events(&self) -> Vec<(String, &[HookEntry])> {
+        let mut events: Vec<(String, &[HookEntry])> = self
+            .hooks
+            .events()
+            .into_iter()
+            .map(|(event, entries)| (event.to_string(), entries))
+            .collect();
+        for (name, profile) in &self.profiles {
+            events.extend(
+                profile
+                    .hooks
+                    .events()
+                    .into_iter()
+                    .map(|(event, entries)| (format!("profiles.{name}.{event}"), entries)),
+            );
+        }
+        events
+    }
+
+    /// Apply a profile on top of the config: the given one, or `default_profile`.
+    ///
+    /// Profile entries are merged like a config file that extends this one.
+    pub(crate) fn with_profile(&self, name: Option<&str>) -> Result<Config> {
+        let Some(name) = name.or(self.default_profile.as_deref()) else {
+            return Ok(self.clone());
+        };
+        let profile = self
+            .profiles
+            .get(name)
+            .ok_or_else(|| Error::UnknownProfile {
+                name: name.to_string(),
+                available: self.profiles.keys().cloned().collect::<Vec<_>>().join(", "),
+            })?;
+
+        let overlay = Config {
+            hooks: profile.hooks.clone(),
+            mkdir: profile.mkdir.clone(),
+            link: profile.link.clone(),
+            copy: profile.copy.clone(),
+            ..Default::default()
+        };
+        let mut config = extend_config(self.clone(), overlay);
+        config.profile = Some(name.to_string());
+        Ok(config)
+    }
+}
+
+/// Merge a config over a config it extends.
+///
+/// Settings of `overlay` win and ports and profiles are merged by name. Hooks are appended after
+/// the hooks of `base`. mkdir/link/copy entries are appended too, replacing entries
+/// of `base` that have the same target.
+fn extend_config(mut base: Config, overlay: Config) -> Config {
+    base.on_conflict = overlay.on_conflict.or(base.on_conflict);
+    base.auto_cd.after_add = overlay.auto_cd.after_add.or(base.auto_cd.after_add);
+    base.auto_cd.after_remove = overlay.auto_cd.after_remove.or(base.auto_cd.after_remove);
+    base.worktree.path_template = overlay
+        .worktree
+        .path_template
+        .or(base.worktree.path_template);
+    base.worktree.branch_template = overlay
+        .worktree
+        .branch_template
+        .or(base.worktree.branch_template);
+    base.prune.base = overlay.prune.base.or(base.prune.base);
+    base.include_file = overlay.include_file.or(base.include_file);
+    base.ports.extend(overlay.ports);
+    base.profiles.extend(overlay.profiles);
+    base.default_profile = overlay.default_profile.or(base.default_profile);
+
+    base.ui.colors = overlay.ui.colors.merge_with_fallback(&base.ui.colors);
+    base.ui.show_key_hints = overlay.ui.show_key_hints.or(base.ui.show_key_hints);
+    base.ui.add_default_mode = overlay.ui.add_default_mode.or(base.ui.add_default_mode);
+
+    append_setup(
+        &mut base,
+        overlay.hooks,
+        overlay.mkdir,
+        overlay.link,
+        overlay.copy,
+    );
+    base
+}
+
+/// Append hooks and mkdir/link/copy entries to a config.
+///
+/// The `hook_shell` given wins. Entries replace entries of `base` that have the same target.
+fn append_setup(
+    base: &mut Config,
+    hooks: Hooks,
+    mkdir: Vec<Mkdir>,
+    link: Vec<Link>,
+    copy: Vec<Copy>,
+) {
+    base.hooks.hook_shell = hooks.hook_shell.or(base.hooks.hook_shell.take());
+    base.hooks.pre_add.extend(hooks.pre_add);
+    base.hooks.post_create.extend(hooks.post_create);
+    base.hooks.post_add.extend(hooks.post_add);
+    base.hooks.pre_remove.extend(hooks.pre_remove);
+    base.hooks.post_remove.extend(hooks.post_remove);
+    base.hooks.pre_move.extend(hooks.pre_move);
+    base.hooks.post_move.extend(hooks.post_move);
+    base.hooks.on_enter.extend(hooks.on_enter);
+
+    let targets: HashSet<&Path> = mkdir
+        .iter()
+        .map(|m| m.path.as_path())
+        .chain(link.iter().map(|l| l.target.as_path()))
+        .chain(copy.iter().map(|c| c.target.as_path()))
+        .collect();
+    base.mkdir.retain(|m| !targets.contains(m.path.as_path()));
+    base.link.retain(|l| !targets.contains(l.target.as_path()));
+    base.copy.retain(|c| !targets.contains(c.target.as_path()));
+    base.mkdir.extend(mkdir);
+    base.link.extend(link);
+    base.copy.extend(copy);
+}
+
+pub(crate) fn merge_with_global(mut repo: Config, global: Option<&Config>) -> Config {
+    let Some(global) = global else {
+        return repo;
     };
 
     if repo.on_conflict.is_none() {
@@ -401,6 +1255,18 @@ pub(crate) fn merge_with_global(mut repo: Config, global: Option<&Config>) -> Co
         repo.worktree.branch_template = global.worktree.branch_template.clone();
     }
 
+    if repo.prune.base.is_none() {
+        repo.prune.base = global.prune.base.clone();
+    }
+
+    if repo.include_file.is_none() {
+        repo.include_file = global.include_file.clone();
+    }
+
+    if repo.ports.is_empty() {
+        repo.ports = global.ports.clone();
+    }
+
     repo.ui.colors = repo.ui.colors.merge_with_fallback(&global.ui.colors);
     if repo.ui.show_key_hints.is_none() {
         repo.ui.show_key_hints = global.ui.show_key_hints;
@@ -409,9 +1275,21 @@ pub(crate) fn merge_with_global(mut repo: Config, global: Option<&Config>) -> Co
         repo.ui.add_default_mode = global.ui.add_default_mode;
     }
 
-    if repo.hooks.hook_shell.is_none() {
-        repo.hooks.hook_shell = global.hooks.hook_shell.clone();
-    }
+    // Global hooks run before the repository's; repository entries replace global
+    // entries with the same target
+    let hooks = std::mem::replace(&mut repo.hooks, global.hooks.clone());
+    let mkdir = std::mem::replace(&mut repo.mkdir, global.mkdir.clone());
+    let global_link = global.link.iter().map(|l| Link {
+        global: true,
+        ..l.clone()
+    });
+    let global_copy = global.copy.iter().map(|c| Copy {
+        global: true,
+        ..c.clone()
+    });
+    let link = std::mem::replace(&mut repo.link, global_link.collect());
+    let copy = std::mem::replace(&mut repo.copy, global_copy.collect());
+    append_setup(&mut repo, hooks, mkdir, link, copy);
 
     repo
 }
@@ -447,9 +1325,14 @@ impl TryFrom<RawConfig> for Config {
                 ));
             }
 
+            if let Some(when) = &raw_mkdir.when {
+                errors.extend(when.validate(&format!("{prefix}.when")));
+            }
+
             mkdir.push(Mkdir {
                 path: raw_mkdir.path,
                 description: raw_mkdir.description,
+                when: raw_mkdir.when,
             });
         }
 
@@ -467,6 +1350,14 @@ impl TryFrom<RawConfig> for Config {
                 errors.push(format!("  - {prefix}.source: {err}"));
             }
 
+            let source_root =
+                parse_source_root(raw_link.source_root.as_deref(), &prefix, &mut errors);
+            if raw_link.ignore_tracked && source_root.is_outside_repo() {
+                errors.push(format!(
+                    "  - {prefix}.ignore_tracked: only applies to sources in the repository"
+                ));
+            }
+
             let target = raw_link
                 .target
                 .clone()
@@ -483,12 +1374,19 @@ impl TryFrom<RawConfig> for Config {
                 ));
             }
 
+            if let Some(when) = &raw_link.when {
+                errors.extend(when.validate(&format!("{prefix}.when")));
+            }
+
             link.push(Link {
                 source: raw_link.source,
+                source_root,
                 target,
                 on_conflict: raw_link.on_conflict,
                 description: raw_link.description,
                 ignore_tracked: raw_link.ignore_tracked,
+                when: raw_link.when,
+                global: false,
             });
         }
 
@@ -506,6 +1404,38 @@ impl TryFrom<RawConfig> for Config {
                 errors.push(format!("  - {prefix}.source: {err}"));
             }
 
+            let source_root =
+                parse_source_root(raw_copy.source_root.as_deref(), &prefix, &mut errors);
+            if raw_copy.ignore_tracked && source_root.is_outside_repo() {
+                errors.push(format!(
+                    "  - {prefix}.ignore_tracked: only applies to sources in the repository"
+                ));
+            }
+
+            let mode = raw_copy.mode.unwrap_or_default();
+            if raw_copy.render && mode != CopyMode::Copy {
+                errors.push(format!(
+                    "  - {prefix}.mode: cannot be combined with render: true"
+                ));
+            }
+
+            validate_globs(&raw_copy.include, &format!("{prefix}.include"), &mut errors);
+            validate_globs(&raw_copy.exclude, &format!("{prefix}.exclude"), &mut errors);
+
+            let max_size = raw_copy.max_size.as_ref().and_then(|value| {
+                let size = parse_size(value);
+                if size.is_none() {
+                    errors.push(format!(
+                        "  - {prefix}.max_size: must be a number of bytes or a size like 500MB"
+                    ));
+                }
+                size
+            });
+
+            let mut preserve = raw_copy.preserve;
+            preserve.sort();
+            preserve.dedup();
+
             let target = raw_copy
                 .target
                 .clone()
@@ -522,11 +1452,25 @@ impl TryFrom<RawConfig> for Config {
                 ));
             }
 
+            if let Some(when) = &raw_copy.when {
+                errors.extend(when.validate(&format!("{prefix}.when")));
+            }
+
             copy.push(Copy {
                 source: raw_copy.source,
+                source_root,
                 target,
                 on_conflict: raw_copy.on_conflict,
                 description: raw_copy.description,
+                render: raw_copy.render,
+                mode,
+                ignore_tracked: raw_copy.ignore_tracked,
+                include: raw_copy.include,
+                exclude: raw_copy.exclude,
+                preserve,
+                max_size,
+                when: raw_copy.when,
+                global: false,
             });
         }
 
@@ -567,6 +1511,118 @@ impl TryFrom<RawConfig> for Config {
         ui_colors.warning = parse_ui_color("warning", raw.ui.colors.warning);
         ui_colors.error = parse_ui_color("error", raw.ui.colors.error);
 
+        // Validate port entries
+        let mut ports = BTreeMap::new();
+        for (name, raw_port) in raw.ports {
+            let prefix = format!("ports.{name}");
+
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                errors.push(format!(
+                    "  - {prefix}: name may only contain letters, digits, '_' and '-'"
+                ));
+            }
+
+            let port = match raw_port {
+                RawPort::Base(base) => Port { base, size: 1 },
+                RawPort::Range(range) => Port {
+                    base: range.base,
+                    size: range.size.unwrap_or(1),
+                },
+            };
+
+            if port.base == 0 {
+                errors.push(format!("  - {prefix}: base must be greater than 0"));
+            }
+            if port.size == 0 {
+                errors.push(format!("  - {prefix}.size: must be greater than 0"));
+            }
+
+            ports.insert(name, port);
+        }
+
+        // Validate hook execution options
+        let hook_lists = [
+            ("pre_add", &raw.hooks.pre_add),
+            ("post_create", &raw.hooks.post_create),
+            ("post_add", &raw.hooks.post_add),
+            ("pre_remove", &raw.hooks.pre_remove),
+            ("post_remove", &raw.hooks.post_remove),
+            ("pre_move", &raw.hooks.pre_move),
+            ("post_move", &raw.hooks.post_move),
+            ("on_enter", &raw.hooks.on_enter),
+        ];
+        for (hook_type, entries) in hook_lists {
+            for (i, entry) in entries.iter().enumerate() {
+                errors.extend(validate_hook_entry(
+                    entry,
+                    &format!("hooks.{hook_type}[{i}]"),
+                ));
+            }
+        }
+
+        // Validate and convert profiles; their entries are checked like top-level ones
+        let mut profiles = BTreeMap::new();
+        for (name, raw_profile) in raw.profiles {
+            let prefix = format!("profiles.{name}");
+
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                errors.push(format!(
+                    "  - {prefix}: name may only contain letters, digits, '_' and '-'"
+                ));
+            }
+
+            let entries = RawConfig {
+                hooks: raw_profile.hooks,
+                mkdir: raw_profile.mkdir,
+                link: raw_profile.link,
+                copy: raw_profile.copy,
+                ..Default::default()
+            };
+            match Config::try_from(entries) {
+                Ok(config) => {
+                    profiles.insert(
+                        name,
+                        Profile {
+                            description: raw_profile.description,
+                            hooks: config.hooks,
+                            mkdir: config.mkdir,
+                            link: config.link,
+                            copy: config.copy,
+                        },
+                    );
+                }
+                Err(Error::ConfigValidation { message }) => {
+                    errors.extend(
+                        message
+                            .lines()
+                            .map(|line| line.replacen("  - ", &format!("  - {prefix}."), 1)),
+                    );
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        let include_file = raw.include_file.and_then(|raw_include| {
+            if let Some(err) = raw_include.path.as_deref().and_then(validate_path) {
+                errors.push(format!("  - include_file.path: {err}"));
+            }
+            if raw_include.mode.is_none() {
+                errors.push("  - include_file.mode: is required (link or copy)".to_string());
+            }
+            Some(IncludeFile {
+                path: raw_include.path,
+                mode: raw_include.mode?,
+            })
+        });
+
         // Validate branch_template if present
         if let Some(ref branch_template) = raw.worktree.branch_template {
             let template_errors = validate_branch_template(branch_template);
@@ -591,6 +1647,11 @@ impl TryFrom<RawConfig> for Config {
                 path_template: raw.worktree.path_template,
                 branch_template: raw.worktree.branch_template,
             },
+            prune: Prune {
+                base: raw.prune.base,
+            },
+            include_file,
+            ports,
             ui: Ui {
                 colors: ui_colors,
                 show_key_hints: raw.ui.show_key_hints,
@@ -599,17 +1660,138 @@ impl TryFrom<RawConfig> for Config {
             hooks: Hooks {
                 hook_shell: raw.hooks.hook_shell,
                 pre_add: raw.hooks.pre_add,
+                post_create: raw.hooks.post_create,
                 post_add: raw.hooks.post_add,
                 pre_remove: raw.hooks.pre_remove,
                 post_remove: raw.hooks.post_remove,
+                pre_move: raw.hooks.pre_move,
+                post_move: raw.hooks.post_move,
+                on_enter: raw.hooks.on_enter,
             },
             mkdir,
             link,
             copy,
+            extends: Vec::new(),
+            profiles,
+            default_profile: raw.default_profile,
+            profile: None,
         })
     }
 }
 
+/// Validate a hook entry (and the members of a `parallel:` group).
+fn validate_hook_entry(entry: &HookEntry, prefix: &str) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    if entry.is_parallel() {
+        if !entry.command.is_empty() {
+            errors.push(format!(
+                "  - {prefix}: command and parallel are mutually exclusive"
+            ));
+        }
+        // Execution options belong to the individual commands of a group
+        let group_options = [
+            ("timeout", entry.timeout.is_some()),
+            ("env", !entry.env.is_empty()),
+            ("cwd", entry.cwd.is_some()),
+            ("retries", entry.retries > 0),
+            ("continue_on_error", entry.continue_on_error),
+        ];
+        for (option, is_set) in group_options {
+            if is_set {
+                errors.push(format!(
+                    "  - {prefix}.{option}: set it on the commands of the parallel group"
+                ));
+            }
+        }
+        for (i, member) in entry.parallel.iter().enumerate() {
+            let member_prefix = format!("{prefix}.parallel[{i}]");
+            if member.is_parallel() {
+                errors.push(format!(
+                    "  - {member_prefix}: parallel groups cannot be nested"
+                ));
+                continue;
+            }
+            errors.extend(validate_hook_entry(member, &member_prefix));
+        }
+    } else {
+        if entry.command.is_empty() {
+            errors.push(format!("  - {prefix}: command is required"));
+        }
+        if entry.output.is_some() {
+            errors.push(format!(
+                "  - {prefix}.output: only allowed on parallel groups"
+            ));
+        }
+    }
+
+    if entry.timeout == Some(0) {
+        errors.push(format!("  - {prefix}.timeout: must be greater than 0"));
+    }
+
+    if let Some(cwd) = &entry.cwd
+        && let Some(err) = validate_path(cwd)
+    {
+        errors.push(format!("  - {prefix}.cwd: {err}"));
+    }
+
+    for key in entry.env.keys() {
+        if key.is_empty() || key.contains(['=', '\0']) {
+            errors.push(format!("  - {prefix}.env: invalid variable name: {key:?}"));
+        }
+    }
+
+    if let Some(when) = &entry.when {
+        errors.extend(when.validate(&format!("{prefix}.when")));
+    }
+
+    errors
+}
+
+/// Parse a `source_root:` value, recording an error and falling back to the repository.
+fn parse_source_root(value: Option<&str>, prefix: &str, errors: &mut Vec<String>) -> SourceRoot {
+    match value.map(SourceRoot::parse) {
+        None => SourceRoot::Repo,
+        Some(Ok(root)) => root,
+        Some(Err(err)) => {
+            errors.push(format!("  - {prefix}.source_root: {err}"));
+            SourceRoot::Repo
+        }
+    }
+}
+
+/// Parse a `max_size:` value: a number of bytes, or a number with a B, KB, MB or GB
+/// suffix (powers of 1024, case-insensitive, e.g. `500MB` or `1.5 GB`).
+fn parse_size(value: &RawSize) -> Option<u64> {
+    let text = match value {
+        RawSize::Bytes(bytes) => return Some(*bytes),
+        RawSize::Text(text) => text.trim(),
+    };
+    let split = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let multiplier: u64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1 << 10,
+        "m" | "mb" | "mib" => 1 << 20,
+        "g" | "gb" | "gib" => 1 << 30,
+        _ => return None,
+    };
+    let bytes = number * multiplier as f64;
+    (bytes.is_finite() && bytes <= u64::MAX as f64).then_some(bytes as u64)
+}
+
+/// Validate glob patterns of a copy entry's `include:` or `exclude:` list.
+fn validate_globs(patterns: &[String], prefix: &str, errors: &mut Vec<String>) {
+    for pattern in patterns {
+        if let Err(e) = globset::Glob::new(pattern) {
+            errors.push(format!("  - {prefix}: invalid glob pattern: {e}"));
+        }
+    }
+}
+
 /// Validate a path and return an error message if invalid.
 fn validate_path(path: &Path) -> Option<String> {
     // Check for absolute paths (including Unix-style on Windows for consistent validation)
@@ -663,6 +1845,103 @@ pub(crate) struct Worktree {
     pub branch_template: Option<String>,
 }
 
+/// `kabu prune` configuration.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Prune {
+    pub base: Option<String>,
+}
+
+/// Gitignore-style file listing files to link or copy into new worktrees.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct IncludeFile {
+    /// Relative to the repository root; None looks for [`INCLUDE_FILES`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    pub mode: IncludeMode,
+}
+
+/// Include files looked for when `include_file.path` is not set, in order.
+pub(crate) const INCLUDE_FILES: [&str; 2] = [".worktreeinclude", ".kabu/include"];
+
+impl IncludeFile {
+    /// Link or copy entries for paths matched by the include file.
+    ///
+    /// The entries use the defaults of their kind, with the path as source and target.
+    pub(crate) fn entries(&self, paths: Vec<PathBuf>) -> (Vec<Link>, Vec<Copy>) {
+        match self.mode {
+            IncludeMode::Link => {
+                let links = paths
+                    .into_iter()
+                    .map(|path| Link {
+                        source: path.clone(),
+                        source_root: SourceRoot::Repo,
+                        target: path,
+                        on_conflict: None,
+                        description: None,
+                        ignore_tracked: false,
+                        when: None,
+                        global: false,
+                    })
+                    .collect();
+                (links, Vec::new())
+            }
+            IncludeMode::Copy => {
+                let copies = paths
+                    .into_iter()
+                    .map(|path| Copy {
+                        source: path.clone(),
+                        source_root: SourceRoot::Repo,
+                        target: path,
+                        on_conflict: None,
+                        description: None,
+                        render: false,
+                        mode: CopyMode::Copy,
+                        ignore_tracked: false,
+                        include: Vec::new(),
+                        exclude: Vec::new(),
+                        preserve: Vec::new(),
+                        max_size: None,
+                        when: None,
+                        global: false,
+                    })
+                    .collect();
+                (Vec::new(), copies)
+            }
+        }
+    }
+}
+
+/// How files listed in an include file are brought into a worktree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[schemars(
+    title = "Include Mode",
+    description = "link or copy the files listed in the include file"
+)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum IncludeMode {
+    Link,
+    Copy,
+}
+
+impl IncludeMode {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            IncludeMode::Link => "link",
+            IncludeMode::Copy => "copy",
+        }
+    }
+}
+
+/// Port handed out per worktree.
+///
+/// A worktree with slot `n` gets ports `base + n * size` through
+/// `base + n * size + size - 1`. Slot 0 is the main worktree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Port {
+    pub base: u16,
+    pub size: u16,
+}
+
 /// Interactive UI configuration.
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub(crate) struct Ui {
@@ -1099,49 +2378,109 @@ fn validate_branch_template(template: &str) -> Vec<String> {
 }
 
 /// Hook commands configuration.
+///
+/// Events added after the first release are skipped when empty, so that the trust
+/// hash of configs that don't use them stays the same.
 #[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
 pub(crate) struct Hooks {
     pub hook_shell: Option<String>,
     pub pre_add: Vec<HookEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_create: Vec<HookEntry>,
     pub post_add: Vec<HookEntry>,
     pub pre_remove: Vec<HookEntry>,
     pub post_remove: Vec<HookEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_move: Vec<HookEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_move: Vec<HookEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_enter: Vec<HookEntry>,
 }
 
 impl Hooks {
     /// Check if any hooks are defined.
     pub fn has_hooks(&self) -> bool {
-        !self.pre_add.is_empty()
-            || !self.post_add.is_empty()
-            || !self.pre_remove.is_empty()
-            || !self.post_remove.is_empty()
+        self.events().iter().any(|(_, entries)| !entries.is_empty())
+    }
+
+    /// All hook events with their entries, in lifecycle order.
+    pub fn events(&self) -> [(&'static str, &[HookEntry]); 8] {
+        [
+            ("pre_add", &self.pre_add),
+            ("post_create", &self.post_create),
+            ("post_add", &self.post_add),
+            ("pre_remove", &self.pre_remove),
+            ("post_remove", &self.post_remove),
+            ("pre_move", &self.pre_move),
+            ("post_move", &self.post_move),
+            ("on_enter", &self.on_enter),
+        ]
     }
 }
 
+/// Named set of hooks and mkdir/link/copy entries applied on top of the config.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Profile {
+    pub description: Option<String>,
+    pub hooks: Hooks,
+    pub mkdir: Vec<Mkdir>,
+    pub link: Vec<Link>,
+    pub copy: Vec<Copy>,
+}
+
 /// Directory creation configuration entry.
 #[derive(Debug, Clone)]
 pub(crate) struct Mkdir {
     pub path: PathBuf,
     pub description: Option<String>,
+    pub when: Option<When>,
 }
 
 /// Symlink configuration entry.
 #[derive(Debug, Clone)]
 pub(crate) struct Link {
     pub source: PathBuf,
+    pub source_root: SourceRoot,
     pub target: PathBuf, // Always resolved (no Option)
     pub on_conflict: Option<OnConflict>,
     pub description: Option<String>,
     pub ignore_tracked: bool,
+    pub when: Option<When>,
+    pub global: bool, // Comes from the global config
 }
 
 /// File copy configuration entry.
 #[derive(Debug, Clone)]
 pub(crate) struct Copy {
     pub source: PathBuf,
+    pub source_root: SourceRoot,
     pub target: PathBuf, // Always resolved (no Option)
     pub on_conflict: Option<OnConflict>,
     pub description: Option<String>,
+    pub render: bool, // Expand template variables in file contents
+    pub mode: CopyMode,
+    pub ignore_tracked: bool, // Skip VCS-tracked files (for glob patterns)
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub preserve: Vec<Preserve>, // Sorted, without duplicates
+    pub max_size: Option<u64>,
+    pub when: Option<When>,
+    pub global: bool, // Comes from the global config
+}
+
+impl Link {
+    /// Full path of the source, resolved against its `source_root:`.
+    pub(crate) fn source_path(&self, ctx: &SourceContext) -> Result<PathBuf> {
+        Ok(self.source_root.resolve(ctx)?.join(&self.source))
+    }
+}
+
+impl Copy {
+    /// Full path of the source, resolved against its `source_root:`.
+    pub(crate) fn source_path(&self, ctx: &SourceContext) -> Result<PathBuf> {
+        Ok(self.source_root.resolve(ctx)?.join(&self.source))
+    }
 }
 
 /// Conflict resolution mode.
@@ -1158,6 +2497,65 @@ pub(crate) enum OnConflict {
     Backup,
 }
 
+/// How a copy entry copies files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[schemars(
+    title = "Copy Mode",
+    description = "How files are copied: copy (default), reflink, hardlink or auto"
+)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum CopyMode {
+    /// Same as `Reflink`
+    Auto,
+    /// Copy-on-write clone (FICLONE on Linux) where the filesystem supports it, a plain
+    /// copy elsewhere
+    Reflink,
+    /// Hard link each file; changing it in the worktree also changes the source
+    Hardlink,
+    /// Plain byte copy
+    #[default]
+    Copy,
+}
+
+impl CopyMode {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            CopyMode::Auto => "auto",
+            CopyMode::Reflink => "reflink",
+            CopyMode::Hardlink => "hardlink",
+            CopyMode::Copy => "copy",
+        }
+    }
+}
+
+/// What a copy entry carries over besides file contents.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, JsonSchema,
+)]
+#[schemars(
+    title = "Preserve",
+    description = "What to carry over when copying: symlinks, mode or mtime"
+)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Preserve {
+    /// Copy symlinks as symlinks instead of copying what they point to
+    Symlinks,
+    /// File and directory permissions
+    Mode,
+    /// File modification times
+    Mtime,
+}
+
+impl Preserve {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Preserve::Symlinks => "symlinks",
+            Preserve::Mode => "mode",
+            Preserve::Mtime => "mtime",
+        }
+    }
+}
+
 /// Behavior after removing a worktree when the current directory is removed.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 #[schemars(
@@ -1179,12 +2577,33 @@ pub(crate) enum AfterRemove {
 /// tracking (not just hooks) for comprehensive security coverage.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub(crate) struct ConfigSnapshot {
+    /// Omitted when empty so hashes of configs without `extends:` stay unchanged.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extends: Vec<String>,
     pub on_conflict: Option<OnConflict>,
     pub worktree: WorktreeSnapshot,
     pub hooks: Hooks,
     pub mkdir: Vec<MkdirSnapshot>,
     pub link: Vec<LinkSnapshot>,
     pub copy: Vec<CopySnapshot>,
+    /// Omitted when unset so hashes of configs without an include file stay unchanged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include_file: Option<IncludeFile>,
+    /// Omitted when empty so hashes of configs without profiles stay unchanged.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, ProfileSnapshot>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<String>,
+}
+
+/// Profile snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub(crate) struct ProfileSnapshot {
+    pub description: Option<String>,
+    pub hooks: Hooks,
+    pub mkdir: Vec<MkdirSnapshot>,
+    pub link: Vec<LinkSnapshot>,
+    pub copy: Vec<CopySnapshot>,
 }
 
 /// Worktree configuration snapshot.
@@ -1199,74 +2618,161 @@ pub(crate) struct WorktreeSnapshot {
 pub(crate) struct MkdirSnapshot {
     pub path: String,
     pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<When>,
 }
 
 /// Link operation snapshot.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub(crate) struct LinkSnapshot {
     pub source: String,
+    /// Omitted for the repository root so hashes of existing configs stay unchanged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_root: Option<String>,
     pub target: String,
     pub on_conflict: Option<OnConflict>,
     pub description: Option<String>,
     pub ignore_tracked: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<When>,
 }
 
 /// Copy operation snapshot.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub(crate) struct CopySnapshot {
     pub source: String,
+    /// Omitted for the repository root so hashes of existing configs stay unchanged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_root: Option<String>,
     pub target: String,
     pub on_conflict: Option<OnConflict>,
     pub description: Option<String>,
+    /// Omitted when false so hashes of configs without rendered copies stay unchanged.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub render: bool,
+    /// Omitted for plain copies so hashes of existing configs stay unchanged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<CopyMode>,
+    /// Omitted when false, like `render`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ignore_tracked: bool,
+    /// Filter and fidelity options are omitted when unset, like `mode`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub preserve: Vec<Preserve>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<When>,
 }
 
 impl ConfigSnapshot {
     /// Convert a Config into a ConfigSnapshot.
     pub(crate) fn from_config(config: &Config) -> Self {
         ConfigSnapshot {
+            extends: config
+                .extends
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect(),
             on_conflict: config.on_conflict,
             worktree: WorktreeSnapshot {
                 path_template: config.worktree.path_template.clone(),
                 branch_template: config.worktree.branch_template.clone(),
             },
             hooks: config.hooks.clone(),
-            mkdir: config
-                .mkdir
-                .iter()
-                .map(|m| MkdirSnapshot {
-                    path: m.path.to_string_lossy().to_string(),
-                    description: m.description.clone(),
-                })
-                .collect(),
-            link: config
-                .link
-                .iter()
-                .map(|l| LinkSnapshot {
-                    source: l.source.to_string_lossy().to_string(),
-                    target: l.target.to_string_lossy().to_string(),
-                    on_conflict: l.on_conflict,
-                    description: l.description.clone(),
-                    ignore_tracked: l.ignore_tracked,
-                })
-                .collect(),
-            copy: config
-                .copy
+            mkdir: mkdir_snapshots(&config.mkdir),
+            link: link_snapshots(&config.link),
+            copy: copy_snapshots(&config.copy),
+            include_file: config.include_file.clone(),
+            profiles: config
+                .profiles
                 .iter()
-                .map(|c| CopySnapshot {
-                    source: c.source.to_string_lossy().to_string(),
-                    target: c.target.to_string_lossy().to_string(),
-                    on_conflict: c.on_conflict,
-                    description: c.description.clone(),
+                .map(|(name, profile)| {
+                    let snapshot = ProfileSnapshot {
+                        description: profile.description.clone(),
+                        hooks: profile.hooks.clone(),
+                        mkdir: mkdir_snapshots(&profile.mkdir),
+                        link: link_snapshots(&profile.link),
+                        copy: copy_snapshots(&profile.copy),
+                    };
+                    (name.clone(), snapshot)
                 })
                 .collect(),
+            default_profile: config.default_profile.clone(),
         }
     }
 }
 
+fn mkdir_snapshots(mkdir: &[Mkdir]) -> Vec<MkdirSnapshot> {
+    mkdir
+        .iter()
+        .map(|m| MkdirSnapshot {
+            path: m.path.to_string_lossy().to_string(),
+            description: m.description.clone(),
+            when: m.when.clone(),
+        })
+        .collect()
+}
+
+fn link_snapshots(link: &[Link]) -> Vec<LinkSnapshot> {
+    link.iter()
+        .map(|l| LinkSnapshot {
+            source: l.source.to_string_lossy().to_string(),
+            source_root: (l.source_root != SourceRoot::Repo).then(|| l.source_root.to_string()),
+            target: l.target.to_string_lossy().to_string(),
+            on_conflict: l.on_conflict,
+            description: l.description.clone(),
+            ignore_tracked: l.ignore_tracked,
+            when: l.when.clone(),
+        })
+        .collect()
+}
+
+fn copy_snapshots(copy: &[Copy]) -> Vec<CopySnapshot> {
+    copy.iter()
+        .map(|c| CopySnapshot {
+            source: c.source.to_string_lossy().to_string(),
+            source_root: (c.source_root != SourceRoot::Repo).then(|| c.source_root.to_string()),
+            target: c.target.to_string_lossy().to_string(),
+            on_conflict: c.on_conflict,
+            description: c.description.clone(),
+            render: c.render,
+            mode: (c.mode != CopyMode::Copy).then_some(c.mode),
+            ignore_tracked: c.ignore_tracked,
+            include: c.include.clone(),
+            exclude: c.exclude.clone(),
+            preserve: c.preserve.clone(),
+            max_size: c.max_size,
+            when: c.when.clone(),
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_resolve_extends() {
+        let scope = ConfigScope::Repo(Path::new("/repo"));
+        assert_eq!(
+            resolve_extends(Path::new("base.yaml"), scope),
+            Some(PathBuf::from("/repo/base.yaml"))
+        );
+        assert_eq!(
+            resolve_extends(Path::new(".kabu/shared.yaml"), scope),
+            Some(PathBuf::from("/repo/.kabu/shared.yaml"))
+        );
+        assert_eq!(
+            resolve_extends(Path::new("global:rust.yaml"), scope),
+            global_config_dir().map(|dir| dir.join("rust.yaml"))
+        );
+    }
+
     #[test]
     fn test_parse_minimal_config() {
         let yaml = r#"
@@ -1327,28 +2833,225 @@ copy:
     }
 
     #[test]
-    fn test_parse_empty_config() {
-        let yaml = "";
+    fn test_parse_copy_render() {
+        let yaml = r#"
+copy:
+  - source: ".env.example"
+    target: ".env"
+    render: true
+  - source: "config.json"
+        "#;
+
         let raw: RawConfig = serde_yaml::from_str(yaml).unwrap();
         let config = Config::try_from(raw).unwrap();
-        assert!(config.link.is_empty());
-        assert!(config.copy.is_empty());
-        assert!(config.mkdir.is_empty());
-    }
-
-    #[test]
-    fn test_parse_invalid_yaml() {
-        let yaml = "invalid: yaml: [[[";
-        let result: std::result::Result<RawConfig, _> = serde_yaml::from_str(yaml);
-        assert!(result.is_err());
+        assert!(config.copy[0].render);
+        assert!(!config.copy[1].render);
     }
 
     #[test]
-    fn test_validate_missing_source() {
+    fn test_snapshot_copy_render_serialization() {
         let yaml = r#"
-link:
-  - target: ".env"
-        "#;
+copy:
+  - source: ".env.example"
+    render: true
+  - source: "config.json"
+        "#;
+
+        let raw: RawConfig = serde_yaml::from_str(yaml).unwrap();
+        let config = Config::try_from(raw).unwrap();
+        let snapshot = ConfigSnapshot::from_config(&config);
+        let json = serde_json::to_string(&snapshot.copy).unwrap();
+
+        // render is only serialized when set, keeping existing trust hashes stable
+        assert_eq!(json.matches("\"render\":true").count(), 1);
+        assert!(!json.contains("\"render\":false"));
+
+        let parsed: Vec<CopySnapshot> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, snapshot.copy);
+    }
+
+    #[test]
+    fn test_parse_copy_mode() {
+        let yaml = r#"
+copy:
+  - source: "node_modules"
+    mode: hardlink
+  - source: "target"
+    mode: auto
+  - source: "config.json"
+        "#;
+
+        let raw: RawConfig = serde_yaml::from_str(yaml).unwrap();
+        let config = Config::try_from(raw).unwrap();
+        assert_eq!(config.copy[0].mode, CopyMode::Hardlink);
+        assert_eq!(config.copy[1].mode, CopyMode::Auto);
+        assert_eq!(config.copy[2].mode, CopyMode::Copy);
+
+        // The default mode is left out of the snapshot, keeping trust hashes stable
+        let snapshot = ConfigSnapshot::from_config(&config);
+        let json = serde_json::to_string(&snapshot.copy).unwrap();
+        assert_eq!(json.matches("\"mode\"").count(), 2);
+    }
+
+    #[test]
+    fn test_copy_mode_with_render() {
+        let yaml = r#"
+copy:
+  - source: ".env.example"
+    render: true
+    mode: reflink
+        "#;
+
+        let raw: RawConfig = serde_yaml::from_str(yaml).unwrap();
+        let err = Config::try_from(raw).unwrap_err().to_string();
+        assert!(err.contains("copy[0].mode: cannot be combined with render: true"));
+    }
+
+    #[test]
+    fn test_parse_copy_filters() {
+        let yaml = r#"
+copy:
+  - source: ".venv"
+    exclude: ["__pycache__", "*.pyc"]
+    preserve: [mtime, symlinks, mode, mtime]
+    max_size: 1.5 GB
+  - source: "secrets"
+    include: ["*.key"]
+    max_size: 4096
+  - source: "config.json"
+        "#;
+
+        let raw: RawConfig = serde_yaml::from_str(yaml).unwrap();
+        let config = Config::try_from(raw).unwrap();
+        assert_eq!(config.copy[0].exclude, vec!["__pycache__", "*.pyc"]);
+        assert_eq!(
+            config.copy[0].preserve,
+            vec![Preserve::Symlinks, Preserve::Mode, Preserve::Mtime]
+        );
+        assert_eq!(config.copy[0].max_size, Some(1_610_612_736));
+        assert_eq!(config.copy[1].include, vec!["*.key"]);
+        assert_eq!(config.copy[1].max_size, Some(4096));
+
+        // Unset options are left out of the snapshot, keeping trust hashes stable
+        let snapshot = ConfigSnapshot::from_config(&config);
+        let json = serde_json::to_string(&snapshot.copy[2]).unwrap();
+        assert!(!json.contains("include"));
+        assert!(!json.contains("exclude"));
+        assert!(!json.contains("preserve"));
+        assert!(!json.contains("max_size"));
+        assert!(!json.contains("ignore_tracked"));
+    }
+
+    #[test]
+    fn test_copy_filter_validation() {
+        let yaml = r#"
+copy:
+  - source: ".venv"
+    exclude: ["[invalid"]
+    max_size: lots
+        "#;
+
+        let raw: RawConfig = serde_yaml::from_str(yaml).unwrap();
+        let err = Config::try_from(raw).unwrap_err().to_string();
+        assert!(err.contains("copy[0].exclude: invalid glob pattern"));
+        assert!(err.contains("copy[0].max_size: must be a number of bytes"));
+    }
+
+    #[test]
+    fn test_parse_size() {
+        let text = |s: &str| parse_size(&RawSize::Text(s.to_string()));
+        assert_eq!(parse_size(&RawSize::Bytes(100)), Some(100));
+        assert_eq!(text("100"), Some(100));
+        assert_eq!(text("2KB"), Some(2048));
+        assert_eq!(text("500mb"), Some(500 << 20));
+        assert_eq!(text("1 GiB"), Some(1 << 30));
+        assert_eq!(text("0.5K"), Some(512));
+        assert_eq!(text("10TB"), None);
+        assert_eq!(text("MB"), None);
+    }
+
+    #[test]
+    fn test_parse_include_file() {
+        let config = parse_config(
+            r#"
+include_file:
+  mode: link
+"#,
+        );
+        assert_eq!(
+            config.include_file,
+            Some(IncludeFile {
+                path: None,
+                mode: IncludeMode::Link,
+            })
+        );
+
+        let config = parse_config(
+            r#"
+include_file:
+  path: .kabu/include
+  mode: copy
+"#,
+        );
+        let include_file = config.include_file.unwrap();
+        assert_eq!(include_file.path, Some(PathBuf::from(".kabu/include")));
+        assert_eq!(include_file.mode, IncludeMode::Copy);
+
+        // Unset, it stays out of the snapshot so existing trust hashes are unchanged
+        let json = serde_json::to_string(&ConfigSnapshot::from_config(&parse_config(""))).unwrap();
+        assert!(!json.contains("include_file"));
+    }
+
+    #[test]
+    fn test_include_file_validation() {
+        let raw: RawConfig = serde_yaml::from_str(
+            r#"
+include_file:
+  path: /etc/worktreeinclude
+"#,
+        )
+        .unwrap();
+        let err = Config::try_from(raw).unwrap_err().to_string();
+        assert!(err.contains("include_file.path: absolute paths are not allowed"));
+        assert!(err.contains("include_file.mode: is required (link or copy)"));
+    }
+
+    #[test]
+    fn test_include_file_entries() {
+        let include_file = IncludeFile {
+            path: None,
+            mode: IncludeMode::Copy,
+        };
+        let (links, copies) = include_file.entries(vec![PathBuf::from(".env")]);
+        assert!(links.is_empty());
+        assert_eq!(copies[0].source, PathBuf::from(".env"));
+        assert_eq!(copies[0].target, PathBuf::from(".env"));
+        assert_eq!(copies[0].mode, CopyMode::Copy);
+    }
+
+    #[test]
+    fn test_parse_empty_config() {
+        let yaml = "";
+        let raw: RawConfig = serde_yaml::from_str(yaml).unwrap();
+        let config = Config::try_from(raw).unwrap();
+        assert!(config.link.is_empty());
+        assert!(config.copy.is_empty());
+        assert!(config.mkdir.is_empty());
+    }
+
+    #[test]
+    fn test_parse_invalid_yaml() {
+        let yaml = "invalid: yaml: [[[";
+        let result: std::result::Result<RawConfig, _> = serde_yaml::from_str(yaml);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_validate_missing_source() {
+        let yaml = r#"
+link:
+  - target: ".env"
+        "#;
 
         let raw: RawConfig = serde_yaml::from_str(yaml).unwrap();
         let err = Config::try_from(raw).unwrap_err();
@@ -1572,6 +3275,7 @@ worktree:
             pre_add: vec![HookEntry {
                 command: "echo test".to_string(),
                 description: None,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -1584,6 +3288,7 @@ worktree:
             post_add: vec![HookEntry {
                 command: "npm install".to_string(),
                 description: None,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -1596,6 +3301,7 @@ worktree:
             pre_remove: vec![HookEntry {
                 command: "echo cleanup".to_string(),
                 description: None,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -1608,6 +3314,7 @@ worktree:
             post_remove: vec![HookEntry {
                 command: "./scripts/cleanup.sh".to_string(),
                 description: None,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -1637,6 +3344,14 @@ hooks:
     - command: "echo 'pre remove'"
   post_remove:
     - command: "./scripts/cleanup.sh"
+  post_create:
+    - command: "git lfs pull"
+  on_enter:
+    - command: "git fetch --quiet"
+  pre_move:
+    - command: "docker compose down"
+  post_move:
+    - command: "docker compose up -d"
         "#;
 
         let raw: RawConfig = serde_yaml::from_str(yaml).unwrap();
@@ -1660,6 +3375,33 @@ hooks:
 
         assert_eq!(config.hooks.post_remove.len(), 1);
         assert_eq!(config.hooks.post_remove[0].command, "./scripts/cleanup.sh");
+
+        assert_eq!(config.hooks.post_create.len(), 1);
+        assert_eq!(config.hooks.post_create[0].command, "git lfs pull");
+        assert_eq!(config.hooks.on_enter.len(), 1);
+        assert_eq!(config.hooks.on_enter[0].command, "git fetch --quiet");
+        assert_eq!(config.hooks.pre_move[0].command, "docker compose down");
+        assert_eq!(config.hooks.post_move[0].command, "docker compose up -d");
+
+        let events: Vec<&str> = config
+            .hooks
+            .events()
+            .iter()
+            .map(|(name, _)| *name)
+            .collect();
+        assert_eq!(
+            events,
+            [
+                "pre_add",
+                "post_create",
+                "post_add",
+                "pre_remove",
+                "post_remove",
+                "pre_move",
+                "post_move",
+                "on_enter"
+            ]
+        );
     }
 
     #[test]
@@ -2411,6 +4153,383 @@ ui:
         assert_eq!(merged.ui.add_default_mode(), AddDefaultMode::New); // None -> default
     }
 
+    #[test]
+    fn test_parse_prune_base() {
+        let yaml = r#"
+prune:
+  base: develop
+        "#;
+        let raw: RawConfig = serde_yaml::from_str(yaml).unwrap();
+        let config = Config::try_from(raw).unwrap();
+        assert_eq!(config.prune.base, Some("develop".to_string()));
+    }
+
+    #[test]
+    fn test_merge_prune_base_global_fallback() {
+        let global = Config {
+            prune: Prune {
+                base: Some("main".to_string()),
+            },
+            ..Default::default()
+        };
+        let merged = merge_with_global(Config::default(), Some(&global));
+        assert_eq!(merged.prune.base, Some("main".to_string()));
+
+        let repo = Config {
+            prune: Prune {
+                base: Some("develop".to_string()),
+            },
+            ..Default::default()
+        };
+        let merged = merge_with_global(repo, Some(&global));
+        assert_eq!(merged.prune.base, Some("develop".to_string()));
+    }
+
+    #[test]
+    fn test_parse_hook_entry_options() {
+        let yaml = r#"
+hooks:
+  post_add:
+    - command: "npm install"
+      timeout: 600
+      cwd: frontend
+      env:
+        NODE_ENV: development
+      retries: 2
+      continue_on_error: true
+        "#;
+        let raw: RawConfig = serde_yaml::from_str(yaml).unwrap();
+        let config = Config::try_from(raw).unwrap();
+        let entry = &config.hooks.post_add[0];
+        assert_eq!(entry.timeout, Some(600));
+        assert_eq!(entry.cwd, Some(PathBuf::from("frontend")));
+        assert_eq!(
+            entry.env.get("NODE_ENV").map(String::as_str),
+            Some("development")
+        );
+        assert_eq!(entry.retries, 2);
+        assert!(entry.continue_on_error);
+        assert_eq!(
+            entry.option_lines(),
+            vec![
+                "timeout: 600s",
+                "cwd: frontend",
+                "env: NODE_ENV=development",
+                "retries: 2",
+                "continue_on_error: true",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_hook_entry_options_invalid() {
+        let yaml = r#"
+hooks:
+  pre_add:
+    - command: "true"
+      timeout: 0
+      cwd: ../outside
+      env:
+        "A=B": value
+        "#;
+        let raw: RawConfig = serde_yaml::from_str(yaml).unwrap();
+        let err = Config::try_from(raw).unwrap_err().to_string();
+        assert!(err.contains("hooks.pre_add[0].timeout: must be greater than 0"));
+        assert!(err.contains("hooks.pre_add[0].cwd: path traversal"));
+        assert!(err.contains("hooks.pre_add[0].env: invalid variable name"));
+    }
+
+    #[test]
+    fn test_hook_entry_serialization_omits_unset_options() {
+        // Keeps trust hashes of existing configs unchanged
+        let entry = HookEntry {
+            command: "echo hi".to_string(),
+            description: None,
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_string(&entry).unwrap(),
+            r#"{"command":"echo hi","description":null}"#
+        );
+    }
+
+    #[test]
+    fn test_parse_parallel_hook_group() {
+        let yaml = r#"
+hooks:
+  post_add:
+    - description: Install dependencies
+      output: buffer
+      parallel:
+        - command: npm install
+        - command: cargo fetch
+          timeout: 300
+    - command: echo done
+        "#;
+        let raw: RawConfig = serde_yaml::from_str(yaml).unwrap();
+        let config = Config::try_from(raw).unwrap();
+        let group = &config.hooks.post_add[0];
+        assert!(group.is_parallel());
+        assert_eq!(group.output, Some(ParallelOutput::Buffer));
+        assert_eq!(
+            group
+                .commands()
+                .map(|e| e.command.as_str())
+                .collect::<Vec<_>>(),
+            vec!["npm install", "cargo fetch"]
+        );
+        assert_eq!(
+            group.option_lines(),
+            vec![
+                "output: buffer",
+                "- npm install",
+                "- cargo fetch",
+                "  timeout: 300s"
+            ]
+        );
+        assert_eq!(config.hooks.post_add[1].commands().count(), 1);
+    }
+
+    #[test]
+    fn test_parse_parallel_hook_group_invalid() {
+        let yaml = r#"
+hooks:
+  post_add:
+    - command: npm install
+      timeout: 60
+      parallel:
+        - command: cargo fetch
+        - parallel:
+            - command: uv sync
+    - output: prefix
+        "#;
+        let raw: RawConfig = serde_yaml::from_str(yaml).unwrap();
+        let err = Config::try_from(raw).unwrap_err().to_string();
+        assert!(err.contains("hooks.post_add[0]: command and parallel are mutually exclusive"));
+        assert!(err.contains("hooks.post_add[0].timeout: set it on the commands"));
+        assert!(err.contains("hooks.post_add[0].parallel[1]: parallel groups cannot be nested"));
+        assert!(err.contains("hooks.post_add[1]: command is required"));
+        assert!(err.contains("hooks.post_add[1].output: only allowed on parallel groups"));
+    }
+
+    #[test]
+    fn test_parse_when_clauses() {
+        let yaml = r#"
+mkdir:
+  - path: node_modules
+    when:
+      file_exists: package.json
+link:
+  - source: .envrc
+    when:
+      vcs: jj
+copy:
+  - source: .env.ci
+    target: .env
+    when:
+      env: CI=true
+      os: linux
+hooks:
+  post_add:
+    - command: ./release.sh
+      when:
+        branch: "release/*"
+        "#;
+        let raw: RawConfig = serde_yaml::from_str(yaml).unwrap();
+        let config = Config::try_from(raw).unwrap();
+        assert_eq!(
+            config.mkdir[0].when.as_ref().unwrap().file_exists,
+            Some(PathBuf::from("package.json"))
+        );
+        assert_eq!(config.link[0].when.as_ref().unwrap().vcs, Some(WhenVcs::Jj));
+        assert_eq!(
+            config.copy[0].when.as_ref().unwrap().to_string(),
+            "os: linux, env: CI=true"
+        );
+        assert_eq!(
+            config.hooks.post_add[0].option_lines(),
+            vec!["when: branch: release/*"]
+        );
+    }
+
+    #[test]
+    fn test_parse_when_clauses_invalid() {
+        let yaml = r#"
+link:
+  - source: .envrc
+    when:
+      file_exists: /etc/passwd
+      os: mac
+        "#;
+        let raw: RawConfig = serde_yaml::from_str(yaml).unwrap();
+        let err = Config::try_from(raw).unwrap_err().to_string();
+        assert!(err.contains("link[0].when.file_exists: absolute paths are not allowed"));
+        assert!(err.contains("link[0].when.os: unknown operating system: mac"));
+
+        let yaml = r#"
+hooks:
+  pre_add:
+    - command: "true"
+      when:
+        branch: "release/[*"
+        env: "=1"
+        "#;
+        let raw: RawConfig = serde_yaml::from_str(yaml).unwrap();
+        let err = Config::try_from(raw).unwrap_err().to_string();
+        assert!(err.contains("hooks.pre_add[0].when.branch: invalid glob pattern"));
+        assert!(err.contains("hooks.pre_add[0].when.env: invalid variable name"));
+
+        let yaml = r#"
+mkdir:
+  - path: build
+    when:
+      vcs: svn
+        "#;
+        assert!(serde_yaml::from_str::<RawConfig>(yaml).is_err());
+    }
+
+    #[test]
+    fn test_when_matches() {
+        let ctx = WhenContext {
+            vcs: "git",
+            branch: Some("release/1.0"),
+            base_dir: Path::new(env!("CARGO_MANIFEST_DIR")),
+        };
+        let when = |yaml: &str| serde_yaml::from_str::<When>(yaml).unwrap();
+
+        assert!(when("{}").matches(&ctx));
+        assert!(when("vcs: git").matches(&ctx));
+        assert!(!when("vcs: jj").matches(&ctx));
+        assert!(when("branch: release/*").matches(&ctx));
+        assert!(!when("branch: main").matches(&ctx));
+        assert!(when("file_exists: Cargo.toml").matches(&ctx));
+        assert!(!when("file_exists: no-such-file").matches(&ctx));
+        assert!(when(&format!("os: {}", env::consts::OS)).matches(&ctx));
+        assert_eq!(when("os: unix").matches(&ctx), cfg!(unix));
+        assert!(!when("env: KABU_TEST_UNSET_VARIABLE").matches(&ctx));
+        // Every condition must hold
+        assert!(!when("vcs: git\nbranch: main").matches(&ctx));
+
+        let detached = WhenContext {
+            branch: None,
+            ..ctx
+        };
+        assert!(!when("branch: '*'").matches(&detached));
+    }
+
+    #[test]
+    fn test_when_changes_snapshot_only_when_set() {
+        let link = Link {
+            source: PathBuf::from(".envrc"),
+            source_root: Default::default(),
+            target: PathBuf::from(".envrc"),
+            on_conflict: None,
+            description: None,
+            ignore_tracked: false,
+            when: None,
+            global: false,
+        };
+        let mut config = Config {
+            link: vec![link],
+            ..Default::default()
+        };
+        let before = serde_json::to_string(&ConfigSnapshot::from_config(&config)).unwrap();
+        assert!(!before.contains("when"));
+
+        config.link[0].when = Some(When {
+            vcs: Some(WhenVcs::Jj),
+            ..Default::default()
+        });
+        let after = serde_json::to_string(&ConfigSnapshot::from_config(&config)).unwrap();
+        assert!(after.contains(r#""when":{"vcs":"jj"}"#));
+    }
+
+    #[test]
+    fn test_parse_ports() {
+        let yaml = r#"
+ports:
+  web: 3000
+  debug:
+    base: 9200
+    size: 10
+        "#;
+        let raw: RawConfig = serde_yaml::from_str(yaml).unwrap();
+        let config = Config::try_from(raw).unwrap();
+        assert_eq!(
+            config.ports.get("web"),
+            Some(&Port {
+                base: 3000,
+                size: 1
+            })
+        );
+        assert_eq!(
+            config.ports.get("debug"),
+            Some(&Port {
+                base: 9200,
+                size: 10
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_ports_invalid() {
+        let yaml = r#"
+ports:
+  "web server": 3000
+  zero: 0
+  empty:
+    base: 4000
+    size: 0
+        "#;
+        let raw: RawConfig = serde_yaml::from_str(yaml).unwrap();
+        let err = Config::try_from(raw).unwrap_err().to_string();
+        assert!(err.contains("ports.web server: name may only contain"));
+        assert!(err.contains("ports.zero: base must be greater than 0"));
+        assert!(err.contains("ports.empty.size: must be greater than 0"));
+    }
+
+    #[test]
+    fn test_parse_ports_unknown_field() {
+        let yaml = r#"
+ports:
+  web:
+    base: 3000
+    count: 2
+        "#;
+        let result: std::result::Result<RawConfig, _> = serde_yaml::from_str(yaml);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_merge_ports_global_fallback() {
+        let global = Config {
+            ports: BTreeMap::from([(
+                "web".to_string(),
+                Port {
+                    base: 3000,
+                    size: 1,
+                },
+            )]),
+            ..Default::default()
+        };
+        let merged = merge_with_global(Config::default(), Some(&global));
+        assert_eq!(merged.ports.len(), 1);
+
+        let repo = Config {
+            ports: BTreeMap::from([(
+                "api".to_string(),
+                Port {
+                    base: 4000,
+                    size: 1,
+                },
+            )]),
+            ..Default::default()
+        };
+        let merged = merge_with_global(repo, Some(&global));
+        assert!(merged.ports.contains_key("api"));
+        assert!(!merged.ports.contains_key("web"));
+    }
+
     // TOML format tests
 
     #[test]
@@ -2579,4 +4698,475 @@ source = ".env"
         let result = parse_raw_config(toml_content, ConfigFormat::Toml);
         assert!(result.is_err());
     }
+
+    fn parse_config(yaml: &str) -> Config {
+        let raw: RawConfig = serde_yaml::from_str(yaml).unwrap();
+        Config::try_from(raw).unwrap()
+    }
+
+    #[test]
+    fn test_extend_config_overrides_settings_and_appends_hooks() {
+        let base = parse_config(
+            r#"
+on_conflict: skip
+worktree:
+  path_template: "../{{ repository }}-{{ branch }}"
+ports:
+  web: 3000
+  db: 5432
+hooks:
+  post_add:
+    - command: "base"
+"#,
+        );
+        let overlay = parse_config(
+            r#"
+on_conflict: backup
+ports:
+  web: 4000
+hooks:
+  post_add:
+    - command: "overlay"
+"#,
+        );
+
+        let merged = extend_config(base, overlay);
+        assert_eq!(merged.on_conflict, Some(OnConflict::Backup));
+        assert_eq!(
+            merged.worktree.path_template.as_deref(),
+            Some("../{{ repository }}-{{ branch }}")
+        );
+        assert_eq!(merged.ports["web"].base, 4000);
+        assert_eq!(merged.ports["db"].base, 5432);
+        let commands: Vec<_> = merged
+            .hooks
+            .post_add
+            .iter()
+            .map(|entry| entry.command.as_str())
+            .collect();
+        assert_eq!(commands, ["base", "overlay"]);
+    }
+
+    #[test]
+    fn test_extend_config_replaces_entries_with_same_target() {
+        let base = parse_config(
+            r#"
+mkdir:
+  - path: "tmp"
+link:
+  - source: ".env"
+  - source: "node_modules"
+copy:
+  - source: "settings.json"
+    target: ".vscode/settings.json"
+"#,
+        );
+        let overlay = parse_config(
+            r#"
+copy:
+  - source: ".env.example"
+    target: ".env"
+link:
+  - source: "shared/vscode.json"
+    target: ".vscode/settings.json"
+"#,
+        );
+
+        let merged = extend_config(base, overlay);
+        assert_eq!(merged.mkdir.len(), 1);
+        let links: Vec<_> = merged.link.iter().map(|l| l.source.clone()).collect();
+        assert_eq!(
+            links,
+            [
+                PathBuf::from("node_modules"),
+                PathBuf::from("shared/vscode.json")
+            ]
+        );
+        assert_eq!(merged.copy.len(), 1);
+        assert_eq!(merged.copy[0].source, PathBuf::from(".env.example"));
+    }
+
+    #[test]
+    fn test_merge_global_setup_runs_before_repo() {
+        let global = parse_config(
+            r#"
+link:
+  - source: ".claude"
+  - source: ".envrc"
+hooks:
+  post_add:
+    - command: "global"
+"#,
+        );
+        let repo = parse_config(
+            r#"
+copy:
+  - source: ".envrc.example"
+    target: ".envrc"
+hooks:
+  post_add:
+    - command: "repo"
+"#,
+        );
+
+        let merged = merge_with_global(repo, Some(&global));
+        let commands: Vec<_> = merged
+            .hooks
+            .post_add
+            .iter()
+            .map(|h| h.command.as_str())
+            .collect();
+        assert_eq!(commands, ["global", "repo"]);
+        assert_eq!(merged.link.len(), 1);
+        assert_eq!(merged.link[0].source, PathBuf::from(".claude"));
+        assert!(merged.link[0].global);
+        assert_eq!(merged.copy.len(), 1);
+        assert!(!merged.copy[0].global);
+    }
+
+    #[test]
+    fn test_parse_source_root() {
+        let config = parse_config(
+            r#"
+link:
+  - source: ".env"
+    source_root: main_worktree
+  - source: ".ssh/config"
+    source_root: home
+copy:
+  - source: "cache"
+    source_root: "/srv/cache/{{repository}}"
+"#,
+        );
+        assert_eq!(config.link[0].source_root, SourceRoot::MainWorktree);
+        assert_eq!(config.link[1].source_root, SourceRoot::Home);
+
+        let ctx = SourceContext {
+            repo_root: PathBuf::from("/work/app-feature"),
+            main_worktree_path: PathBuf::from("/work/app"),
+            repository: "app".to_string(),
+        };
+        assert_eq!(
+            config.link[0].source_path(&ctx).unwrap(),
+            PathBuf::from("/work/app/.env")
+        );
+        assert_eq!(
+            config.copy[0].source_path(&ctx).unwrap(),
+            PathBuf::from("/srv/cache/app/cache")
+        );
+    }
+
+    #[test]
+    fn test_source_root_validation() {
+        let raw: RawConfig = serde_yaml::from_str(
+            r#"
+link:
+  - source: ".env"
+    source_root: secrets
+  - source: "*.key"
+    source_root: home
+    ignore_tracked: true
+copy:
+  - source: "config/*.local.json"
+    source_root: ~/config
+    ignore_tracked: true
+"#,
+        )
+        .unwrap();
+        let err = Config::try_from(raw).unwrap_err().to_string();
+        assert!(err.contains("link[0].source_root: must be repo, main_worktree, home"));
+        assert!(err.contains("link[1].ignore_tracked: only applies to sources in the repository"));
+        assert!(err.contains("copy[0].ignore_tracked: only applies to sources in the repository"));
+    }
+
+    #[test]
+    fn test_external_sources_require_trust() {
+        let config = parse_config(
+            r#"
+link:
+  - source: ".env"
+    source_root: main_worktree
+copy:
+  - source: "app.env"
+    source_root: "~/secrets/{{repository}}/"
+    target: ".env.local"
+"#,
+        );
+        assert!(!config.has_hooks());
+        assert!(config.requires_trust());
+        assert_eq!(
+            config.external_sources(),
+            ["copy: ~/secrets/{{repository}}/app.env -> .env.local"]
+        );
+
+        let config = parse_config("link:\n  - source: .env\n    source_root: main_worktree\n");
+        assert!(!config.requires_trust());
+    }
+
+    #[test]
+    fn test_default_source_root_keeps_snapshot() {
+        let implicit = parse_config("link:\n  - source: .env\n");
+        let explicit = parse_config("link:\n  - source: .env\n    source_root: repo\n");
+        let home = parse_config("link:\n  - source: .env\n    source_root: home\n");
+        assert_eq!(
+            ConfigSnapshot::from_config(&implicit),
+            ConfigSnapshot::from_config(&explicit)
+        );
+        assert_ne!(
+            ConfigSnapshot::from_config(&implicit),
+            ConfigSnapshot::from_config(&home)
+        );
+    }
+
+    #[test]
+    fn test_with_profile_adds_to_setup() {
+        let config = parse_config(
+            r#"
+default_profile: full
+link:
+  - source: .env
+hooks:
+  post_add:
+    - command: "echo base"
+profiles:
+  full:
+    hooks:
+      post_add:
+        - command: "npm install"
+  review:
+    description: "Read-only review checkout"
+    copy:
+      - source: .env.review
+        target: .env
+"#,
+        );
+
+        let full = config.with_profile(None).unwrap();
+        assert_eq!(full.profile.as_deref(), Some("full"));
+        assert_eq!(full.hooks.post_add.len(), 2);
+        assert_eq!(full.link.len(), 1);
+
+        let review = config.with_profile(Some("review")).unwrap();
+        assert_eq!(review.hooks.post_add.len(), 1);
+        assert!(review.link.is_empty());
+        assert_eq!(review.copy[0].source, PathBuf::from(".env.review"));
+
+        let err = config.with_profile(Some("missing")).unwrap_err();
+        assert!(matches!(err, Error::UnknownProfile { .. }));
+        assert!(err.to_string().contains("full, review"));
+    }
+
+    #[test]
+    fn test_has_hooks_includes_profiles() {
+        let config = parse_config(
+            r#"
+profiles:
+  full:
+    hooks:
+      post_add:
+        - command: "npm install"
+"#,
+        );
+        assert!(!config.hooks.has_hooks());
+        assert!(config.has_hooks());
+        assert!(
+            config
+                .hook_events()
+                .iter()
+                .any(|(event, entries)| event == "profiles.full.post_add" && !entries.is_empty())
+        );
+    }
+
+    #[test]
+    fn test_profile_validation_errors_are_prefixed() {
+        let yaml = r#"
+profiles:
+  "bad name":
+    mkdir:
+      - path: "/abs"
+"#;
+        let raw: RawConfig = serde_yaml::from_str(yaml).unwrap();
+        let message = Config::try_from(raw).unwrap_err().to_string();
+        assert!(message.contains("  - profiles.bad name: name may only contain"));
+        assert!(message.contains("  - profiles.bad name.mkdir[0].path:"));
+    }
+
+    #[test]
+    fn test_profiles_change_snapshot() {
+        let base = parse_config("link:\n  - source: .env\n");
+        let with_profile = parse_config(
+            "link:\n  - source: .env\nprofiles:\n  review:\n    mkdir:\n      - path: tmp\n",
+        );
+
+        let base_json = serde_json::to_string(&ConfigSnapshot::from_config(&base)).unwrap();
+        assert!(!base_json.contains("profiles"));
+        assert_ne!(
+            ConfigSnapshot::from_config(&base),
+            ConfigSnapshot::from_config(&with_profile)
+        );
+    }
+}
+
+#[cfg(all(test, feature = "impure-test"))]
+#[allow(clippy::unwrap_used)]
+mod impure_tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_config(repo: &Path, path: &str, content: &str) {
+        let path = repo.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_load_extends_relative_to_repo_root() {
+        let temp = TempDir::new().unwrap();
+        write_config(
+            temp.path(),
+            ".kabu/config.yaml",
+            "extends: [.kabu/base.yaml]\nlink:\n  - source: .env\n",
+        );
+        write_config(
+            temp.path(),
+            ".kabu/base.yaml",
+            "extends: [shared/common.toml]\nmkdir:\n  - path: tmp\n",
+        );
+        write_config(
+            temp.path(),
+            "shared/common.toml",
+            "[[copy]]\nsource = \"settings.json\"\n",
+        );
+
+        let config = load(temp.path()).unwrap().unwrap();
+        assert_eq!(config.mkdir.len(), 1);
+        assert_eq!(config.link.len(), 1);
+        assert_eq!(config.copy.len(), 1);
+        assert_eq!(
+            config.extends,
+            [
+                PathBuf::from("shared/common.toml"),
+                PathBuf::from(".kabu/base.yaml")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_extends_shared_base_once() {
+        let temp = TempDir::new().unwrap();
+        write_config(
+            temp.path(),
+            ".kabu/config.yaml",
+            "extends: [.kabu/a.yaml, .kabu/b.yaml]\n",
+        );
+        write_config(
+            temp.path(),
+            ".kabu/a.yaml",
+            "extends: [.kabu/common.yaml]\n",
+        );
+        write_config(
+            temp.path(),
+            ".kabu/b.yaml",
+            "extends: [.kabu/common.yaml]\n",
+        );
+        write_config(
+            temp.path(),
+            ".kabu/common.yaml",
+            "hooks:\n  post_add:\n    - command: echo common\n",
+        );
+
+        let config = load(temp.path()).unwrap().unwrap();
+        assert_eq!(config.hooks.post_add.len(), 1);
+        assert_eq!(config.extends.len(), 3);
+    }
+
+    #[test]
+    fn test_load_extends_cycle() {
+        let temp = TempDir::new().unwrap();
+        write_config(
+            temp.path(),
+            ".kabu/config.yaml",
+            "extends: [.kabu/a.yaml]\n",
+        );
+        write_config(
+            temp.path(),
+            ".kabu/a.yaml",
+            "extends: [.kabu/config.yaml]\n",
+        );
+
+        let err = load(temp.path()).unwrap_err();
+        assert!(matches!(err, Error::ConfigValidation { .. }));
+        assert!(err.to_string().contains(".kabu/a.yaml:\n  - extends[0]"));
+    }
+
+    #[test]
+    fn test_load_extends_missing_file() {
+        let temp = TempDir::new().unwrap();
+        write_config(
+            temp.path(),
+            ".kabu/config.yaml",
+            "extends: [.kabu/missing.yaml]\n",
+        );
+
+        let err = load(temp.path()).unwrap_err();
+        assert!(err.to_string().contains("file not found"));
+    }
+
+    #[test]
+    fn test_load_extends_errors_name_their_file() {
+        let temp = TempDir::new().unwrap();
+        write_config(
+            temp.path(),
+            ".kabu/config.yaml",
+            "extends: [.kabu/base.yaml]\nmkdir:\n  - path: ../outside\n",
+        );
+        write_config(
+            temp.path(),
+            ".kabu/base.yaml",
+            "link:\n  - source: /etc/passwd\n",
+        );
+
+        let message = load(temp.path()).unwrap_err().to_string();
+        let base = message
+            .find(".kabu/base.yaml:\n  - link[0].source")
+            .unwrap();
+        let repo = message
+            .find(".kabu/config.yaml:\n  - mkdir[0].path")
+            .unwrap();
+        assert!(base < repo);
+    }
+
+    #[test]
+    fn test_load_default_profile_from_extended_file() {
+        let temp = TempDir::new().unwrap();
+        write_config(
+            temp.path(),
+            ".kabu/config.yaml",
+            "extends: [.kabu/profiles.yaml]\ndefault_profile: review\n",
+        );
+        write_config(
+            temp.path(),
+            ".kabu/profiles.yaml",
+            "profiles:\n  review:\n    mkdir:\n      - path: tmp\n",
+        );
+
+        let config = load(temp.path()).unwrap().unwrap();
+        assert_eq!(config.default_profile.as_deref(), Some("review"));
+    }
+
+    #[test]
+    fn test_load_unknown_default_profile() {
+        let temp = TempDir::new().unwrap();
+        write_config(
+            temp.path(),
+            ".kabu/config.yaml",
+            "default_profile: review\n",
+        );
+
+        let err = load(temp.path()).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("default_profile: no profile named 'review'")
+        );
+    }
 }
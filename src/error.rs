@@ -13,6 +13,9 @@ pub(crate) enum Error {
     )]
     ConfigValidation { message: String },
 
+    #[error("Unknown profile: {name}\n  Available profiles: {available}")]
+    UnknownProfile { name: String, available: String },
+
     #[error("Failed to parse global config: {message}")]
     GlobalConfigParse { message: String },
 
@@ -42,6 +45,12 @@ pub(crate) enum Error {
     )]
     SourceNotFound { path: String },
 
+    #[error(
+        "A link/copy source is its own target; applying it would replace the file with itself.\n  Path: {}",
+        .path.display()
+    )]
+    SourceIsTarget { path: PathBuf },
+
     #[error("Failed to create symlink: {source} -> {target}")]
     SymlinkFailed {
         source: PathBuf,
@@ -58,6 +67,15 @@ pub(crate) enum Error {
         cause: std::io::Error,
     },
 
+    #[error(
+        "Copy exceeds max_size: {path} holds {size} bytes to copy, the limit is {max_size} bytes"
+    )]
+    CopyTooLarge {
+        path: PathBuf,
+        size: u64,
+        max_size: u64,
+    },
+
     #[error("Operation aborted by user")]
     Aborted,
 
@@ -78,6 +96,23 @@ pub(crate) enum Error {
     #[error("The main worktree/workspace cannot be removed.\n  Path: {}", .path.display())]
     CannotRemoveMainWorktree { path: PathBuf },
 
+    #[error(
+        "The main worktree/workspace is the setup source and cannot be set up.\n  Path: {}",
+        .path.display()
+    )]
+    CannotSetupMainWorktree { path: PathBuf },
+
+    #[error("The main worktree/workspace cannot be moved.\n  Path: {}", .path.display())]
+    CannotMoveMainWorktree { path: PathBuf },
+
+    #[error(
+        "Selecting worktrees/workspaces to prune requires an interactive terminal\n  Use --yes to prune all candidates or --dry-run to list them."
+    )]
+    PruneRequiresConfirmation,
+
+    #[error("{count} problem(s) found")]
+    DoctorProblemsFound { count: usize },
+
     #[error("No worktrees/workspaces available to remove")]
     NoWorktreesToRemove,
 
@@ -87,15 +122,46 @@ pub(crate) enum Error {
     #[error("Current directory is not inside any worktree/workspace")]
     NotInWorktree,
 
+    #[error("No hook logs found for {}", .path.display())]
+    NoHookLogs { path: PathBuf },
+
     #[error("Has uncommitted changes: {}\n  Use --force to remove anyway.", .path.display())]
     WorktreeHasUncommittedChanges { path: PathBuf },
 
     #[error("Has unpushed commits: {}\n  Use --force to remove anyway.", .path.display())]
     WorktreeHasUnpushedCommits { path: PathBuf },
 
+    #[error(
+        "Is locked{}: {}\n  Use --force to remove anyway.",
+        .reason.as_deref().map(|r| format!(" ({r})")).unwrap_or_default(),
+        .path.display()
+    )]
+    WorktreeLocked {
+        path: PathBuf,
+        reason: Option<String>,
+    },
+
+    #[error("Is locked: {}\n  Run 'kabu unlock' on it first.", .path.display())]
+    CannotMoveLockedWorktree { path: PathBuf },
+
+    #[error("Already locked: {}", .path.display())]
+    WorktreeAlreadyLocked { path: PathBuf },
+
+    #[error("Not locked: {}", .path.display())]
+    WorktreeNotLocked { path: PathBuf },
+
+    #[error("The main worktree/workspace cannot be locked.\n  Path: {}", .path.display())]
+    CannotLockMainWorktree { path: PathBuf },
+
     #[error("Worktree/workspace not found: {path}")]
     WorktreeNotFound { path: PathBuf },
 
+    #[error("git worktree move failed:\n{stderr}")]
+    GitWorktreeMoveFailed { stderr: String },
+
+    #[error("Destination already exists: {}", .path.display())]
+    MoveDestinationExists { path: PathBuf },
+
     #[error("git worktree remove failed:\n{stderr}")]
     GitWorktreeRemoveFailed { stderr: String },
 
@@ -132,6 +198,19 @@ pub(crate) enum Error {
         stderr: String,
     },
 
+    #[error("Hook timed out after {timeout}s: {command}")]
+    HookTimedOut { command: String, timeout: u64 },
+
+    #[error("Hook interrupted: {command}")]
+    HookInterrupted { command: String },
+
+    #[error(
+        "{} parallel hook(s) failed: {}",
+        .failures.len(),
+        .failures.iter().map(|f| f.command.as_str()).collect::<Vec<_>>().join(", ")
+    )]
+    ParallelHooksFailed { failures: Vec<HookFailure> },
+
     #[error("Trust storage directory not found")]
     TrustStorageNotFound,
 
@@ -144,6 +223,21 @@ pub(crate) enum Error {
     #[error("Trust verification failed: {message}")]
     TrustVerificationFailed { message: String },
 
+    #[error("State storage directory not found")]
+    StateStorageNotFound,
+
+    #[error("State file corrupted: {}\n  {message}", .path.display())]
+    StateFileCorrupted { path: PathBuf, message: String },
+
+    #[error(
+        "State file is locked by another kabu process: {}\n  Remove the lock file if no kabu command is running.",
+        .path.display()
+    )]
+    StateLocked { path: PathBuf },
+
+    #[error("Port '{name}' for slot {slot} exceeds 65535. Lower the base port or size.")]
+    PortOutOfRange { name: String, slot: u32 },
+
     #[error("Config file not found: {path}")]
     ConfigNotFound { path: PathBuf },
 
@@ -153,9 +247,18 @@ pub(crate) enum Error {
     #[error("Global config directory not found")]
     GlobalConfigDirNotFound,
 
+    #[error("Home directory not found")]
+    HomeDirNotFound,
+
+    #[error("Global config file not found")]
+    GlobalConfigNotFound,
+
     #[error("No hooks defined in .kabu/config.yaml or .kabu/config.toml")]
     NoHooksDefined,
 
+    #[error("No hooks defined in the global config")]
+    NoGlobalHooksDefined,
+
     #[error("Internal error: {0}")]
     Internal(String),
 
@@ -163,5 +266,13 @@ pub(crate) enum Error {
     Io(#[from] std::io::Error),
 }
 
+/// A failed command of a `parallel:` hook group.
+#[derive(Debug)]
+pub(crate) struct HookFailure {
+    pub command: String,
+    pub description: Option<String>,
+    pub exit_code: Option<i32>,
+}
+
 /// Result type alias for this crate.
 pub(crate) type Result<T> = std::result::Result<T, Error>;
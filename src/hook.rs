@@ -1,16 +1,61 @@
 use crate::color::ColorScheme;
-use crate::config::{HookEntry, Hooks};
-use crate::error::{Error, Result};
+use crate::config::{Config, HookEntry, Hooks, ParallelOutput, WhenContext};
+use crate::error::{Error, HookFailure, Result};
 use crate::output::Output;
+use crate::state::{Allocation, HookLog};
 
-use std::io::IsTerminal;
-use std::path::Path;
-use std::process::{Command, Stdio};
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::Mutex;
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+
+/// How often a running hook is checked for timeout and Ctrl-C.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Time a hook gets to exit after SIGTERM before it is killed.
+#[cfg(unix)]
+const KILL_GRACE_PERIOD: Duration = Duration::from_secs(2);
+
+/// Longest label shown in front of the output of a command in a `parallel:` group.
+const MAX_LABEL_WIDTH: usize = 24;
+
+/// Stream a line of hook output was written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HookStream {
+    Stdout,
+    Stderr,
+}
+
+/// Receives the output of a hook line by line, instead of it going to the terminal.
+type LineSink<'a> = dyn Fn(HookStream, &str) + Sync + 'a;
+
+/// Receives the output of a hook as soon as it is read, in chunks of any size.
+type ChunkSink<'a> = dyn Fn(HookStream, &[u8]) + Sync + 'a;
+
+/// Where the output of a hook process goes.
+#[derive(Clone, Copy)]
+enum HookIo<'a> {
+    /// Straight to kabu's stdout/stderr
+    Inherit,
+    /// Chunk by chunk to a sink, with stdin still connected to the terminal. When kabu's
+    /// output is a terminal, the hook writes to a pseudo terminal (Unix), so it keeps
+    /// colors and progress bars; both streams then arrive as [`HookStream::Stdout`].
+    Tee(&'a ChunkSink<'a>),
+    /// Line by line to a sink, with stdin closed since several hooks can't share the terminal
+    Capture(&'a LineSink<'a>),
+}
 
 /// Template variables for hooks.
 ///
 /// Provides context information that can be used in hook commands.
-/// All variables are automatically shell-escaped when expanded.
+/// All variables are automatically shell-escaped when expanded. The same values are
+/// exported to hook processes as `KABU_*` environment variables.
 pub(crate) struct HookEnv {
     /// Worktree/workspace path (unified name for both VCS)
     pub worktree_path: String,
@@ -24,8 +69,12 @@ pub(crate) struct HookEnv {
     pub vcs_type: String,
     /// jj-specific: change ID (short form)
     pub change_id: Option<String>,
-    /// jj-specific: commit ID (short form)
+    /// Commit ID (short form); None before the worktree exists (pre_add)
     pub commit_id: Option<String>,
+    /// Slot and ports allocated to the worktree when `ports:` is configured
+    pub allocation: Option<Allocation>,
+    /// Directory hook output is logged to (see [`crate::state::hook_log_dir`])
+    pub log_dir: Option<PathBuf>,
     /// Windows-only: override hook shell selection
     #[cfg_attr(not(windows), allow(dead_code))]
     pub hook_shell: Option<String>,
@@ -41,10 +90,15 @@ impl HookEnv {
     /// - `{{repo_root}}`: Repository root path
     /// - `{{vcs_type}}`: VCS type ("git" or "jj")
     ///
-    /// # jj-specific Variables
+    /// # Revision Variables (empty in `pre_add`, before the worktree exists)
     /// - `{{change_id}}`: jj change ID (short form, empty for git)
     /// - `{{commit_id}}`: Commit/revision ID (short form)
     ///
+    /// # Port Variables (when `ports:` is configured)
+    /// - `{{slot}}`: Slot number allocated to the worktree
+    /// - `{{port.NAME}}`: First port of the block allocated for `NAME`
+    /// - `{{port.NAME.end}}`: Last port of the block allocated for `NAME`
+    ///
     /// All values are automatically shell-escaped to prevent command injection.
     fn expand_template_with<E>(&self, cmd: &str, escape: E) -> String
     where
@@ -69,7 +123,7 @@ impl HookEnv {
             result = result.replace("{{bookmark}}", "");
         }
 
-        // jj-specific variables
+        // Revision variables
         if let Some(change_id) = &self.change_id {
             result = result.replace("{{change_id}}", &escape(change_id));
         } else {
@@ -82,9 +136,83 @@ impl HookEnv {
             result = result.replace("{{commit_id}}", "");
         }
 
+        // Slot and ports
+        if let Some(allocation) = &self.allocation {
+            result = result.replace("{{slot}}", &escape(&allocation.slot.to_string()));
+            for (name, (first, last)) in &allocation.ports {
+                result = result.replace(
+                    &format!("{{{{port.{name}.end}}}}"),
+                    &escape(&last.to_string()),
+                );
+                result =
+                    result.replace(&format!("{{{{port.{name}}}}}"), &escape(&first.to_string()));
+            }
+        } else {
+            result = result.replace("{{slot}}", "");
+        }
+
         result
     }
 
+    /// Environment variables describing the hook context, exported to every hook.
+    ///
+    /// Values that are not available (e.g. `KABU_BRANCH` on a detached HEAD) are
+    /// `None`, so that a value inherited from kabu's own environment can be removed.
+    fn context_vars(&self, hook_event: &str) -> Vec<(String, Option<String>)> {
+        let mut vars = vec![
+            ("KABU_HOOK_EVENT".to_string(), Some(hook_event.to_string())),
+            (
+                "KABU_WORKTREE_PATH".to_string(),
+                Some(self.worktree_path.clone()),
+            ),
+            (
+                "KABU_WORKTREE_NAME".to_string(),
+                Some(self.worktree_name.clone()),
+            ),
+            ("KABU_BRANCH".to_string(), self.branch.clone()),
+            ("KABU_REPO_ROOT".to_string(), Some(self.repo_root.clone())),
+            ("KABU_VCS_TYPE".to_string(), Some(self.vcs_type.clone())),
+            ("KABU_CHANGE_ID".to_string(), self.change_id.clone()),
+            ("KABU_COMMIT_ID".to_string(), self.commit_id.clone()),
+            (
+                "KABU_SLOT".to_string(),
+                self.allocation.as_ref().map(|a| a.slot.to_string()),
+            ),
+        ];
+        if let Some(allocation) = &self.allocation {
+            for (name, (first, last)) in &allocation.ports {
+                let name = name.to_ascii_uppercase().replace('-', "_");
+                vars.push((format!("KABU_PORT_{name}"), Some(first.to_string())));
+                vars.push((format!("KABU_PORT_{name}_END"), Some(last.to_string())));
+            }
+        }
+        vars
+    }
+
+    /// Export the hook context to a hook process (see [`HookEnv::context_vars`]).
+    fn export_to(&self, cmd: &mut Command, hook_event: &str) {
+        for (key, value) in self.context_vars(hook_event) {
+            match value {
+                Some(value) => cmd.env(key, value),
+                None => cmd.env_remove(key),
+            };
+        }
+    }
+
+    /// Expand template variables in `env:` values of a hook entry.
+    ///
+    /// Values are passed to the process directly, so they are not shell-escaped.
+    fn expand_env(&self, vars: &BTreeMap<String, String>) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|(key, value)| {
+                (
+                    key.clone(),
+                    self.expand_template_with(value, |v| v.to_string()),
+                )
+            })
+            .collect()
+    }
+
     #[cfg(unix)]
     fn expand_template(&self, cmd: &str) -> String {
         self.expand_template_with(cmd, shell_escape)
@@ -143,28 +271,52 @@ fn cmd_escape(s: &str) -> String {
 
 /// Execute a single hook command
 #[cfg(unix)]
-fn execute_hook(command: &str, env: &HookEnv, working_dir: &Path) -> Result<()> {
+fn execute_hook(
+    hook_type: &str,
+    entry: &HookEntry,
+    env: &HookEnv,
+    working_dir: &Path,
+    io: HookIo<'_>,
+) -> Result<()> {
+    use std::os::unix::process::CommandExt;
+
     let shell = "sh";
     let shell_arg = "-c";
 
     // Expand template variables
-    let expanded_command = env.expand_template(command);
+    let expanded_command = env.expand_template(&entry.command);
 
     let mut cmd = Command::new(shell);
     cmd.arg(shell_arg)
         .arg(&expanded_command)
-        .current_dir(working_dir)
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit());
+        .current_dir(working_dir);
+    env.export_to(&mut cmd, hook_type);
+    cmd.envs(env.expand_env(&entry.env));
+
+    // A hook with a timeout gets its own session, and so its own process group, so that
+    // everything it started (e.g. `npm install` and its children) can be killed together.
+    // A process group of kabu's session that is not in the foreground would be stopped
+    // with SIGTTIN/SIGTTOU when it touches the terminal. Outside the session the terminal
+    // is not the hook's controlling terminal, so reading from it, writing to it and
+    // changing its settings work as usual; only opening /dev/tty fails.
+    let own_group = entry.timeout.is_some();
+    if own_group {
+        // SAFETY: setsid(2) is async-signal-safe and touches no memory of the parent
+        unsafe {
+            cmd.pre_exec(|| {
+                if libc::setsid() == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+    }
 
-    let status = cmd.status().map_err(|e| Error::HookExecutionFailed {
-        command: command.to_string(),
-        cause: e.to_string(),
-    })?;
+    let status = spawn_hook(cmd, entry, io, |child| terminate(child, own_group))?;
 
     if !status.success() {
         return Err(Error::HookFailed {
-            command: command.to_string(),
+            command: entry.command.clone(),
             exit_code: status.code(),
             stderr: String::new(), // stderr is already displayed
         });
@@ -174,10 +326,16 @@ fn execute_hook(command: &str, env: &HookEnv, working_dir: &Path) -> Result<()>
 }
 
 #[cfg(windows)]
-fn execute_hook(command: &str, env: &HookEnv, working_dir: &Path) -> Result<()> {
+fn execute_hook(
+    hook_type: &str,
+    entry: &HookEntry,
+    env: &HookEnv,
+    working_dir: &Path,
+    io: HookIo<'_>,
+) -> Result<()> {
     let shell = select_windows_shell_with_override(env.hook_shell.as_deref())?;
 
-    let expanded_command = env.expand_template_with(command, |value| match shell.kind {
+    let expanded_command = env.expand_template_with(&entry.command, |value| match shell.kind {
         WindowsShellKind::Pwsh | WindowsShellKind::PowerShell => powershell_escape(value),
         WindowsShellKind::Cmd => cmd_escape(value),
         WindowsShellKind::Bash | WindowsShellKind::Wsl => posix_shell_escape(value),
@@ -185,18 +343,18 @@ fn execute_hook(command: &str, env: &HookEnv, working_dir: &Path) -> Result<()>
 
     let mut cmd = Command::new(&shell.program);
     cmd.args(&shell.args_for_command(&expanded_command))
-        .current_dir(working_dir)
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit());
+        .current_dir(working_dir);
+    env.export_to(&mut cmd, hook_type);
+    cmd.envs(env.expand_env(&entry.env));
 
-    let status = cmd.status().map_err(|e| Error::HookExecutionFailed {
-        command: command.to_string(),
-        cause: e.to_string(),
+    let status = spawn_hook(cmd, entry, io, |child| {
+        let _ = child.kill();
+        let _ = child.wait();
     })?;
 
     if !status.success() {
         return Err(Error::HookFailed {
-            command: command.to_string(),
+            command: entry.command.clone(),
             exit_code: status.code(),
             stderr: String::new(), // stderr is already displayed
         });
@@ -205,6 +363,295 @@ fn execute_hook(command: &str, env: &HookEnv, working_dir: &Path) -> Result<()>
     Ok(())
 }
 
+/// Open a pseudo terminal for the output of a hook if kabu's stdout is a terminal.
+#[cfg(unix)]
+fn terminal_pty() -> Option<(std::fs::File, std::fs::File)> {
+    if std::io::stdout().is_terminal() {
+        open_pty()
+    } else {
+        None
+    }
+}
+
+#[cfg(windows)]
+fn terminal_pty() -> Option<(std::fs::File, std::fs::File)> {
+    None
+}
+
+/// Open a pseudo terminal, returning the master end kabu reads and the slave end the
+/// hook writes to.
+///
+/// The slave gets the window size of kabu's terminal, if any, and passes newlines
+/// through unchanged, so the log gets no carriage returns; the real terminal still
+/// translates them.
+#[cfg(unix)]
+fn open_pty() -> Option<(std::fs::File, std::fs::File)> {
+    use std::os::fd::FromRawFd;
+
+    let mut master: libc::c_int = -1;
+    let mut slave: libc::c_int = -1;
+    // SAFETY: all-zero is a valid winsize; ioctl/openpty/tcgetattr/tcsetattr only write
+    // through the pointers given, which point to live locals
+    unsafe {
+        let mut size: libc::winsize = std::mem::zeroed();
+        let has_size = libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) == 0;
+        let size_ptr: *mut libc::winsize = if has_size {
+            &mut size
+        } else {
+            std::ptr::null_mut()
+        };
+        let opened = libc::openpty(
+            &mut master,
+            &mut slave,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            size_ptr,
+        );
+        if opened != 0 {
+            return None;
+        }
+        let mut termios: libc::termios = std::mem::zeroed();
+        if libc::tcgetattr(slave, &mut termios) == 0 {
+            termios.c_oflag &= !libc::ONLCR;
+            libc::tcsetattr(slave, libc::TCSANOW, &termios);
+        }
+        Some((
+            std::fs::File::from_raw_fd(master),
+            std::fs::File::from_raw_fd(slave),
+        ))
+    }
+}
+
+/// Spawn a hook process and wait for it.
+///
+/// Unless the output is inherited, it is read and passed to the sink: as it arrives
+/// for [`HookIo::Tee`], line by line for [`HookIo::Capture`].
+fn spawn_hook(
+    mut cmd: Command,
+    entry: &HookEntry,
+    io: HookIo<'_>,
+    terminate: impl FnOnce(&mut Child),
+) -> Result<ExitStatus> {
+    let mut pty_master = None;
+    match io {
+        HookIo::Inherit => {
+            cmd.stdout(Stdio::inherit()).stderr(Stdio::inherit());
+        }
+        HookIo::Tee(_) => match terminal_pty() {
+            Some((master, slave)) => {
+                cmd.stdout(slave.try_clone()?).stderr(slave);
+                pty_master = Some(master);
+            }
+            None => {
+                cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+            }
+        },
+        HookIo::Capture(_) => {
+            cmd.stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped());
+        }
+    }
+
+    let mut child = cmd.spawn().map_err(|e| Error::HookExecutionFailed {
+        command: entry.command.clone(),
+        cause: e.to_string(),
+    })?;
+    // The command holds kabu's copies of the pty slave; the master only reaches end of
+    // file once they are closed
+    drop(cmd);
+
+    // Processes started by a Windows hook are not killed with it; a job object collects
+    // them so that a timeout or Ctrl-C stops them all and their output pipes get closed
+    #[cfg(windows)]
+    let job = HookJob::assign(&child);
+    let terminate = |child: &mut Child| {
+        #[cfg(windows)]
+        if let Some(job) = &job {
+            job.terminate();
+        }
+        terminate(child);
+    };
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    thread::scope(|scope| {
+        match io {
+            HookIo::Inherit => {}
+            HookIo::Tee(sink) => {
+                if let Some(master) = pty_master {
+                    scope.spawn(move || forward_chunks(master, HookStream::Stdout, sink));
+                }
+                if let Some(stdout) = stdout {
+                    scope.spawn(move || forward_chunks(stdout, HookStream::Stdout, sink));
+                }
+                if let Some(stderr) = stderr {
+                    scope.spawn(move || forward_chunks(stderr, HookStream::Stderr, sink));
+                }
+            }
+            HookIo::Capture(sink) => {
+                if let Some(stdout) = stdout {
+                    scope.spawn(move || forward_lines(stdout, HookStream::Stdout, sink));
+                }
+                if let Some(stderr) = stderr {
+                    scope.spawn(move || forward_lines(stderr, HookStream::Stderr, sink));
+                }
+            }
+        }
+        wait_for_hook(child, entry, terminate)
+    })
+}
+
+/// Pass everything read from `reader` to `sink` as soon as it arrives, until end of
+/// file or a read error (a pty master fails with EIO once the hook closed it). Prompts
+/// without a trailing newline are passed on right away.
+fn forward_chunks(mut reader: impl Read, stream: HookStream, sink: &ChunkSink<'_>) {
+    let mut buffer = [0; 8192];
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => sink(stream, &buffer[..n]),
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(_) => break,
+        }
+    }
+}
+
+/// Pass every line read from `reader` to `sink`, until end of file or a read error.
+fn forward_lines(reader: impl Read, stream: HookStream, sink: &LineSink<'_>) {
+    let mut reader = BufReader::new(reader);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {
+                let text = String::from_utf8_lossy(&line);
+                sink(stream, text.trim_end_matches(['\n', '\r']));
+            }
+        }
+    }
+}
+
+/// Wait for a hook process, killing it with `terminate` on timeout or Ctrl-C.
+fn wait_for_hook(
+    mut child: Child,
+    entry: &HookEntry,
+    terminate: impl FnOnce(&mut Child),
+) -> Result<ExitStatus> {
+    let deadline = entry
+        .timeout
+        .map(|secs| Instant::now() + Duration::from_secs(secs));
+
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+
+        if crate::SIGNAL_RECEIVED.load(Ordering::SeqCst) {
+            terminate(&mut child);
+            return Err(Error::HookInterrupted {
+                command: entry.command.clone(),
+            });
+        }
+
+        if let (Some(deadline), Some(timeout)) = (deadline, entry.timeout)
+            && Instant::now() >= deadline
+        {
+            terminate(&mut child);
+            return Err(Error::HookTimedOut {
+                command: entry.command.clone(),
+                timeout,
+            });
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Stop a hook process: SIGTERM first, SIGKILL if it is still running after a grace period.
+///
+/// With `own_group`, the whole process group is signalled, including processes that
+/// outlived the shell.
+#[cfg(unix)]
+fn terminate(child: &mut Child, own_group: bool) {
+    let pid = child.id() as libc::pid_t;
+    let target = if own_group { -pid } else { pid };
+    let signal = |sig| {
+        // SAFETY: kill(2) has no memory safety requirements; `target` is the process
+        // (group) we spawned and have not reaped yet, or its group
+        unsafe {
+            libc::kill(target, sig);
+        }
+    };
+
+    signal(libc::SIGTERM);
+    let deadline = Instant::now() + KILL_GRACE_PERIOD;
+    let mut exited = false;
+    while Instant::now() < deadline {
+        if matches!(child.try_wait(), Ok(Some(_))) {
+            exited = true;
+            break;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+    // An exited shell may leave the rest of its group running
+    if own_group || !exited {
+        signal(libc::SIGKILL);
+    }
+    let _ = child.wait();
+}
+
+/// Job object holding a Windows hook process and every process it starts.
+#[cfg(windows)]
+struct HookJob(windows_sys::Win32::Foundation::HANDLE);
+
+#[cfg(windows)]
+impl HookJob {
+    /// Create a job object and put `child` in it, or `None` if that is not possible
+    /// (e.g. kabu runs in a job that forbids nested jobs).
+    ///
+    /// Processes that `child` starts from then on join the job as well.
+    fn assign(child: &Child) -> Option<Self> {
+        use std::os::windows::io::AsRawHandle;
+        use windows_sys::Win32::System::JobObjects::{AssignProcessToJobObject, CreateJobObjectW};
+
+        // SAFETY: null attributes and name are allowed; the job handle is owned by the
+        // returned value, the process handle stays owned by `child`
+        unsafe {
+            let handle = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if handle.is_null() {
+                return None;
+            }
+            let job = HookJob(handle);
+            if AssignProcessToJobObject(job.0, child.as_raw_handle()) == 0 {
+                return None;
+            }
+            Some(job)
+        }
+    }
+
+    /// Kill every process in the job.
+    fn terminate(&self) {
+        // SAFETY: the handle is a job object owned by `self`
+        unsafe {
+            windows_sys::Win32::System::JobObjects::TerminateJobObject(self.0, 1);
+        }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for HookJob {
+    fn drop(&mut self) {
+        // Closing the handle leaves the processes running; hooks may start background
+        // processes on purpose
+        // SAFETY: the handle is a job object owned by `self` and not used afterwards
+        unsafe {
+            windows_sys::Win32::Foundation::CloseHandle(self.0);
+        }
+    }
+}
+
 #[cfg(windows)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 enum WindowsShellKind {
@@ -380,25 +827,24 @@ fn find_in_dir_with(
     None
 }
 
-/// Execute pre_add hooks
+/// Execute pre_add hooks, returning how many ran and succeeded
 pub(crate) fn run_pre_add(
     hooks: &Hooks,
     env: &HookEnv,
     working_dir: &Path,
     output: &Output,
-) -> Result<()> {
-    for (i, entry) in hooks.pre_add.iter().enumerate() {
-        output.hook_running(
-            "pre_add",
-            i + 1,
-            hooks.pre_add.len(),
-            &entry.command,
-            entry.description.as_deref(),
-        );
-        execute_hook(&entry.command, env, working_dir)?;
-        output.hook_separator();
-    }
-    Ok(())
+) -> Result<usize> {
+    run_hooks("pre_add", &hooks.pre_add, env, working_dir, output)
+}
+
+/// Execute post_create hooks, returning how many ran and succeeded
+pub(crate) fn run_post_create(
+    hooks: &Hooks,
+    env: &HookEnv,
+    working_dir: &Path,
+    output: &Output,
+) -> Result<usize> {
+    run_hooks("post_create", &hooks.post_create, env, working_dir, output)
 }
 
 /// Execute post_add hooks
@@ -408,18 +854,7 @@ pub(crate) fn run_post_add(
     working_dir: &Path,
     output: &Output,
 ) -> Result<()> {
-    for (i, entry) in hooks.post_add.iter().enumerate() {
-        output.hook_running(
-            "post_add",
-            i + 1,
-            hooks.post_add.len(),
-            &entry.command,
-            entry.description.as_deref(),
-        );
-        execute_hook(&entry.command, env, working_dir)?;
-        output.hook_separator();
-    }
-    Ok(())
+    run_hooks("post_add", &hooks.post_add, env, working_dir, output).map(|_| ())
 }
 
 /// Execute pre_remove hooks
@@ -429,18 +864,7 @@ pub(crate) fn run_pre_remove(
     working_dir: &Path,
     output: &Output,
 ) -> Result<()> {
-    for (i, entry) in hooks.pre_remove.iter().enumerate() {
-        output.hook_running(
-            "pre_remove",
-            i + 1,
-            hooks.pre_remove.len(),
-            &entry.command,
-            entry.description.as_deref(),
-        );
-        execute_hook(&entry.command, env, working_dir)?;
-        output.hook_separator();
-    }
-    Ok(())
+    run_hooks("pre_remove", &hooks.pre_remove, env, working_dir, output).map(|_| ())
 }
 
 /// Execute post_remove hooks
@@ -450,25 +874,383 @@ pub(crate) fn run_post_remove(
     working_dir: &Path,
     output: &Output,
 ) -> Result<()> {
-    for (i, entry) in hooks.post_remove.iter().enumerate() {
+    run_hooks("post_remove", &hooks.post_remove, env, working_dir, output).map(|_| ())
+}
+
+/// Execute pre_move hooks
+pub(crate) fn run_pre_move(
+    hooks: &Hooks,
+    env: &HookEnv,
+    working_dir: &Path,
+    output: &Output,
+) -> Result<()> {
+    run_hooks("pre_move", &hooks.pre_move, env, working_dir, output).map(|_| ())
+}
+
+/// Execute post_move hooks
+pub(crate) fn run_post_move(
+    hooks: &Hooks,
+    env: &HookEnv,
+    working_dir: &Path,
+    output: &Output,
+) -> Result<()> {
+    run_hooks("post_move", &hooks.post_move, env, working_dir, output).map(|_| ())
+}
+
+/// Execute on_enter hooks
+pub(crate) fn run_on_enter(
+    hooks: &Hooks,
+    env: &HookEnv,
+    working_dir: &Path,
+    output: &Output,
+) -> Result<()> {
+    run_hooks("on_enter", &hooks.on_enter, env, working_dir, output).map(|_| ())
+}
+
+/// Execute hook entries in order, stopping at the first failure unless the entry
+/// has `continue_on_error`.
+///
+/// Returns the number of entries that ran and succeeded; entries skipped by `when:`
+/// are not counted.
+///
+/// With a log directory in `env`, the output of the hooks is also written to a new
+/// log file (see [`HookLog`]).
+fn run_hooks(
+    hook_type: &str,
+    entries: &[HookEntry],
+    env: &HookEnv,
+    working_dir: &Path,
+    output: &Output,
+) -> Result<usize> {
+    // `file_exists` is resolved against the directory the hooks run in
+    let when_ctx = WhenContext {
+        vcs: &env.vcs_type,
+        branch: env.branch.as_deref(),
+        base_dir: working_dir,
+    };
+
+    // Hooks still run when their output can't be logged
+    let hook_log = env
+        .log_dir
+        .as_deref()
+        .and_then(|dir| match HookLog::create(dir, hook_type) {
+            Ok(log) => Some(log),
+            Err(e) => {
+                output.hook_note(&format!("Hook output is not logged: {e}"));
+                None
+            }
+        });
+    let log = hook_log.as_ref();
+    if let Some(log) = log {
+        log.line(&format!("# {hook_type} hooks for {}", env.worktree_path));
+        log.line(&format!("# started at {}", Utc::now().to_rfc3339()));
+    }
+
+    let mut succeeded = 0;
+    let mut failed = false;
+    for (i, entry) in entries.iter().enumerate() {
+        if let Some(when) = &entry.when
+            && !when.matches(&when_ctx)
+        {
+            output.hook_skipped(
+                hook_type,
+                i + 1,
+                entries.len(),
+                &command_summary(entry),
+                entry.description.as_deref(),
+                &when.to_string(),
+            );
+            if let Some(log) = log {
+                log.line(&format!(
+                    "\n# skipped: {} (when: {when})",
+                    command_summary(entry)
+                ));
+            }
+            continue;
+        }
         output.hook_running(
-            "post_remove",
+            hook_type,
             i + 1,
-            hooks.post_remove.len(),
-            &entry.command,
+            entries.len(),
+            &command_summary(entry),
             entry.description.as_deref(),
         );
-        execute_hook(&entry.command, env, working_dir)?;
-        output.hook_separator();
+        if let Some(log) = log {
+            log.line(&format!("\n$ {}", command_summary(entry)));
+        }
+        let result = if entry.is_parallel() {
+            run_parallel(hook_type, entry, env, working_dir, &when_ctx, output, log)
+        } else if let Some(log) = log {
+            let tee = |stream: HookStream, bytes: &[u8]| {
+                output.hook_output(bytes, stream == HookStream::Stderr);
+                log.write(bytes);
+            };
+            run_entry(
+                hook_type,
+                entry,
+                env,
+                working_dir,
+                output,
+                HookIo::Tee(&tee),
+                Some(log),
+            )
+        } else {
+            run_entry(
+                hook_type,
+                entry,
+                env,
+                working_dir,
+                output,
+                HookIo::Inherit,
+                None,
+            )
+        };
+        if let Some(log) = log {
+            match &result {
+                Ok(()) => log.line("# succeeded"),
+                Err(e) => log.line(&format!("# failed: {e}")),
+            }
+        }
+        match result {
+            Ok(()) => {
+                succeeded += 1;
+                output.hook_separator();
+            }
+            // Ctrl-C always stops the remaining hooks
+            Err(e) if entry.continue_on_error && !matches!(e, Error::HookInterrupted { .. }) => {
+                failed = true;
+                let exit_code = match &e {
+                    Error::HookFailed { exit_code, .. } => *exit_code,
+                    _ => None,
+                };
+                output.hook_warning(hook_type, &e.to_string(), exit_code);
+                output.hook_note("Continuing with the next hook (continue_on_error).");
+                output.hook_separator();
+            }
+            Err(e) => {
+                if let Some(log) = log {
+                    output.hook_note(&format!(
+                        "Hook output was logged to {}",
+                        log.path().display()
+                    ));
+                }
+                return Err(e);
+            }
+        }
+    }
+
+    // on_enter hooks run after every cd; logs of quiet, successful runs would only
+    // push the logs worth looking at out of `kabu logs`
+    if hook_type == "on_enter"
+        && !failed
+        && let Some(hook_log) = hook_log
+        && !hook_log.has_output()
+    {
+        hook_log.discard();
+    }
+    Ok(succeeded)
+}
+
+/// Run the commands of a `parallel:` group at the same time.
+///
+/// Each command's output is prefixed or buffered (see [`ParallelOutput`]) so lines of
+/// different commands never mix. All commands run to completion; failures of commands
+/// without `continue_on_error` are collected into [`Error::ParallelHooksFailed`].
+fn run_parallel(
+    hook_type: &str,
+    group: &HookEntry,
+    env: &HookEnv,
+    working_dir: &Path,
+    when_ctx: &WhenContext,
+    output: &Output,
+    log: Option<&HookLog>,
+) -> Result<()> {
+    let mut members = Vec::new();
+    for member in &group.parallel {
+        match &member.when {
+            Some(when) if !when.matches(when_ctx) => {
+                let note = format!("Skipped: {} (when: {when})", member_label(member));
+                if let Some(log) = log {
+                    log.line(&format!("# {note}"));
+                }
+                output.hook_note(&note);
+            }
+            _ => members.push(member),
+        }
+    }
+
+    let mode = group.output.unwrap_or_default();
+    let width = members
+        .iter()
+        .map(|member| member_label(member).chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let results: Vec<Result<()>> = thread::scope(|scope| {
+        let handles: Vec<_> = members
+            .iter()
+            .map(|member| {
+                scope.spawn(move || {
+                    let label = member_label(member);
+                    let prefix = format!("[{label:<width$}]");
+                    // The log always gets prefixed lines, whatever the terminal shows
+                    let log_line = |line: &str| {
+                        if let Some(log) = log {
+                            log.line(&format!("{prefix} {line}"));
+                        }
+                    };
+                    match mode {
+                        ParallelOutput::Prefix => {
+                            let sink = |stream: HookStream, line: &str| {
+                                output.hook_output_line(
+                                    &prefix,
+                                    line,
+                                    stream == HookStream::Stderr,
+                                );
+                                log_line(line);
+                            };
+                            run_entry(
+                                hook_type,
+                                member,
+                                env,
+                                working_dir,
+                                output,
+                                HookIo::Capture(&sink),
+                                log,
+                            )
+                        }
+                        ParallelOutput::Buffer => {
+                            let lines = Mutex::new(Vec::new());
+                            let sink = |_: HookStream, line: &str| {
+                                if let Ok(mut lines) = lines.lock() {
+                                    lines.push(line.to_string());
+                                }
+                                log_line(line);
+                            };
+                            let result = run_entry(
+                                hook_type,
+                                member,
+                                env,
+                                working_dir,
+                                output,
+                                HookIo::Capture(&sink),
+                                log,
+                            );
+                            let lines = lines.into_inner().unwrap_or_else(|e| e.into_inner());
+                            output.hook_output_block(&label, result.is_ok(), &lines);
+                            result
+                        }
+                    }
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle.join().unwrap_or_else(|_| {
+                    Err(Error::Internal("parallel hook thread panicked".to_string()))
+                })
+            })
+            .collect()
+    });
+
+    let mut failures = Vec::new();
+    for (member, result) in members.iter().zip(results) {
+        match result {
+            Ok(()) => {}
+            Err(e @ Error::HookInterrupted { .. }) => return Err(e),
+            Err(e) => {
+                let exit_code = match &e {
+                    Error::HookFailed { exit_code, .. } => *exit_code,
+                    _ => None,
+                };
+                if member.continue_on_error {
+                    output.hook_warning(hook_type, &e.to_string(), exit_code);
+                    output.hook_note("Continuing with the other hooks (continue_on_error).");
+                    continue;
+                }
+                failures.push(HookFailure {
+                    command: member.command.clone(),
+                    description: member.description.clone(),
+                    exit_code,
+                });
+            }
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::ParallelHooksFailed { failures })
+    }
+}
+
+/// Label shown in front of the output of a command in a `parallel:` group.
+fn member_label(entry: &HookEntry) -> String {
+    let text = entry.description.as_deref().unwrap_or(&entry.command);
+    if text.chars().count() <= MAX_LABEL_WIDTH {
+        text.to_string()
+    } else {
+        let mut label: String = text.chars().take(MAX_LABEL_WIDTH - 1).collect();
+        label.push('…');
+        label
+    }
+}
+
+/// Command text shown for a hook entry: the command, or the commands of a group.
+fn command_summary(entry: &HookEntry) -> String {
+    if entry.is_parallel() {
+        let commands: Vec<&str> = entry.parallel.iter().map(|e| e.command.as_str()).collect();
+        format!("parallel: {}", commands.join(", "))
+    } else {
+        entry.command.clone()
+    }
+}
+
+/// Execute one hook entry in its working directory, retrying failures as configured.
+fn run_entry(
+    hook_type: &str,
+    entry: &HookEntry,
+    env: &HookEnv,
+    working_dir: &Path,
+    output: &Output,
+    io: HookIo<'_>,
+    log: Option<&HookLog>,
+) -> Result<()> {
+    let working_dir = match &entry.cwd {
+        Some(cwd) => working_dir.join(cwd),
+        None => working_dir.to_path_buf(),
+    };
+
+    let mut attempt = 0;
+    loop {
+        match execute_hook(hook_type, entry, env, &working_dir, io) {
+            Err(e @ (Error::HookFailed { .. } | Error::HookTimedOut { .. }))
+                if attempt < entry.retries =>
+            {
+                attempt += 1;
+                if let Some(log) = log {
+                    log.line(&format!("# {e}, retrying ({attempt}/{})", entry.retries));
+                }
+                output.hook_note(&format!("{e}\nRetrying ({attempt}/{})...", entry.retries));
+            }
+            result => return result,
+        }
     }
-    Ok(())
 }
 
 /// Display dry-run output for hook entries.
 pub(crate) fn dry_run_hooks(hook_type: &str, entries: &[HookEntry], output: &Output) {
     for entry in entries {
-        let display = entry.description.as_deref().unwrap_or(&entry.command);
-        output.dry_run(&format!("Would run {} hook: {}", hook_type, display));
+        let summary = command_summary(entry);
+        let display = entry.description.as_deref().unwrap_or(&summary);
+        match &entry.when {
+            Some(when) => output.dry_run(&format!(
+                "Would run {} hook: {} (when: {})",
+                hook_type, display, when
+            )),
+            None => output.dry_run(&format!("Would run {} hook: {}", hook_type, display)),
+        }
     }
 }
 
@@ -486,7 +1268,7 @@ fn display_hook_entries(entries: &[HookEntry], hook_type: &str, use_color: bool)
     }
 
     for entry in entries {
-        eprintln!("  {}", entry.command);
+        eprintln!("  {}", entry.headline());
         if let Some(desc) = &entry.description {
             if use_color {
                 eprintln!(
@@ -506,11 +1288,18 @@ fn display_hook_entries(entries: &[HookEntry], hook_type: &str, use_color: bool)
         } else {
             eprintln!("  -> no description");
         }
+        for line in entry.option_lines() {
+            if use_color {
+                eprintln!("     {}", ColorScheme::dimmed(&line));
+            } else {
+                eprintln!("     {}", line);
+            }
+        }
     }
 }
 
 /// Display hooks for user review before trusting
-pub(crate) fn display_hooks_for_review(hooks: &Hooks) {
+pub(crate) fn display_hooks_for_review(config: &Config) {
     let use_color = std::io::stderr().is_terminal();
 
     if use_color {
@@ -524,16 +1313,40 @@ pub(crate) fn display_hooks_for_review(hooks: &Hooks) {
     eprintln!();
     eprintln!("Trusting will allow ALL hooks in this config to execute:");
 
-    display_hook_entries(&hooks.pre_add, "pre_add", use_color);
-    display_hook_entries(&hooks.post_add, "post_add", use_color);
-    display_hook_entries(&hooks.pre_remove, "pre_remove", use_color);
-    display_hook_entries(&hooks.post_remove, "post_remove", use_color);
+    for (hook_type, entries) in config.hook_events() {
+        display_hook_entries(entries, &hook_type, use_color);
+    }
+
+    let external = config.external_sources();
+    if !external.is_empty() {
+        eprintln!();
+        eprintln!("Trusting will also allow these files from outside the repository:");
+        for line in external {
+            eprintln!("  {line}");
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_forward_chunks_passes_prompt_without_newline() {
+        let chunks = Mutex::new(Vec::new());
+        let sink = |stream: HookStream, bytes: &[u8]| {
+            if let Ok(mut chunks) = chunks.lock() {
+                chunks.push((stream, bytes.to_vec()));
+            }
+        };
+        forward_chunks(&b"Continue? "[..], HookStream::Stderr, &sink);
+
+        assert_eq!(
+            chunks.into_inner().unwrap_or_default(),
+            vec![(HookStream::Stderr, b"Continue? ".to_vec())]
+        );
+    }
+
     #[cfg(unix)]
     #[test]
     fn test_shell_escape_basic() {
@@ -714,6 +1527,8 @@ mod tests {
             vcs_type: "git".to_string(),
             change_id: None,
             commit_id: None,
+            allocation: None,
+            log_dir: None,
             hook_shell: None,
         };
 
@@ -732,6 +1547,8 @@ mod tests {
             vcs_type: "git".to_string(),
             change_id: None,
             commit_id: None,
+            allocation: None,
+            log_dir: None,
             hook_shell: None,
         };
 
@@ -756,6 +1573,8 @@ mod tests {
             vcs_type: "git".to_string(),
             change_id: None,
             commit_id: None,
+            allocation: None,
+            log_dir: None,
             hook_shell: None,
         };
 
@@ -773,6 +1592,8 @@ mod tests {
             vcs_type: "git".to_string(),
             change_id: None,
             commit_id: None,
+            allocation: None,
+            log_dir: None,
             hook_shell: None,
         };
 
@@ -791,6 +1612,8 @@ mod tests {
             vcs_type: "jj".to_string(),
             change_id: Some("abc123def456".to_string()),
             commit_id: Some("xyz789".to_string()),
+            allocation: None,
+            log_dir: None,
             hook_shell: None,
         };
 
@@ -814,4 +1637,175 @@ mod tests {
         let result = env.expand_template("echo {{bookmark}}");
         assert!(result.contains("my-bookmark"));
     }
+
+    #[test]
+    fn test_expand_template_ports() {
+        let env = HookEnv {
+            worktree_path: "/worktree".to_string(),
+            worktree_name: "test".to_string(),
+            branch: None,
+            repo_root: "/repo".to_string(),
+            vcs_type: "git".to_string(),
+            change_id: None,
+            commit_id: None,
+            allocation: Some(Allocation {
+                slot: 2,
+                ports: std::collections::BTreeMap::from([
+                    ("web".to_string(), (3002, 3002)),
+                    ("debug".to_string(), (9220, 9229)),
+                ]),
+            }),
+            log_dir: None,
+            hook_shell: None,
+        };
+
+        let result = env.expand_template("serve --port {{port.web}} --slot {{slot}}");
+        assert!(result.contains("3002"));
+        assert!(result.contains("2"));
+        assert!(!result.contains("{{"));
+
+        let result = env.expand_template("debug {{port.debug}}-{{port.debug.end}}");
+        assert!(result.contains("9220"));
+        assert!(result.contains("9229"));
+        assert!(!result.contains("{{"));
+
+        let result = env.expand_template("echo {{port.unknown}}");
+        assert!(result.contains("{{port.unknown}}"));
+    }
+
+    #[test]
+    fn test_context_vars() {
+        let env = HookEnv {
+            worktree_path: "/worktree".to_string(),
+            worktree_name: "feature".to_string(),
+            branch: None,
+            repo_root: "/repo".to_string(),
+            vcs_type: "jj".to_string(),
+            change_id: Some("kxqpmnzt".to_string()),
+            commit_id: Some("abc123".to_string()),
+            allocation: Some(Allocation {
+                slot: 1,
+                ports: std::collections::BTreeMap::from([("dev-server".to_string(), (3010, 3019))]),
+            }),
+            log_dir: None,
+            hook_shell: None,
+        };
+
+        let vars: BTreeMap<String, Option<String>> =
+            env.context_vars("pre_remove").into_iter().collect();
+        let var = |key: &str| vars.get(key).cloned().flatten();
+        assert_eq!(var("KABU_HOOK_EVENT").as_deref(), Some("pre_remove"));
+        assert_eq!(var("KABU_WORKTREE_PATH").as_deref(), Some("/worktree"));
+        assert_eq!(var("KABU_WORKTREE_NAME").as_deref(), Some("feature"));
+        assert_eq!(var("KABU_REPO_ROOT").as_deref(), Some("/repo"));
+        assert_eq!(var("KABU_VCS_TYPE").as_deref(), Some("jj"));
+        assert_eq!(var("KABU_CHANGE_ID").as_deref(), Some("kxqpmnzt"));
+        assert_eq!(var("KABU_COMMIT_ID").as_deref(), Some("abc123"));
+        assert_eq!(var("KABU_SLOT").as_deref(), Some("1"));
+        assert_eq!(var("KABU_PORT_DEV_SERVER").as_deref(), Some("3010"));
+        assert_eq!(var("KABU_PORT_DEV_SERVER_END").as_deref(), Some("3019"));
+        // Unavailable values are listed so they can be removed from the environment
+        assert_eq!(vars.get("KABU_BRANCH"), Some(&None));
+    }
+
+    #[test]
+    fn test_expand_template_no_allocation() {
+        let env = HookEnv {
+            worktree_path: "/worktree".to_string(),
+            worktree_name: "test".to_string(),
+            branch: None,
+            repo_root: "/repo".to_string(),
+            vcs_type: "git".to_string(),
+            change_id: None,
+            commit_id: None,
+            allocation: None,
+            log_dir: None,
+            hook_shell: None,
+        };
+
+        let result = env.expand_template("echo {{slot}}");
+        assert_eq!(result, "echo ");
+    }
+
+    #[test]
+    fn test_expand_env_is_not_shell_escaped() {
+        let env = HookEnv {
+            worktree_path: "/worktree".to_string(),
+            worktree_name: "it's".to_string(),
+            branch: None,
+            repo_root: "/repo".to_string(),
+            vcs_type: "git".to_string(),
+            change_id: None,
+            commit_id: None,
+            allocation: None,
+            log_dir: None,
+            hook_shell: None,
+        };
+        let vars = BTreeMap::from([("NAME".to_string(), "app-{{worktree_name}}".to_string())]);
+
+        assert_eq!(
+            env.expand_env(&vars),
+            vec![("NAME".to_string(), "app-it's".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_member_label_is_truncated() {
+        let short = HookEntry {
+            command: "npm install".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(member_label(&short), "npm install");
+
+        let long = HookEntry {
+            command: "cargo fetch --locked --manifest-path crates/Cargo.toml".to_string(),
+            ..Default::default()
+        };
+        let label = member_label(&long);
+        assert_eq!(label.chars().count(), MAX_LABEL_WIDTH);
+        assert!(label.ends_with('…'));
+    }
+
+    #[test]
+    fn test_command_summary_lists_parallel_commands() {
+        let group = HookEntry {
+            parallel: vec![
+                HookEntry {
+                    command: "npm install".to_string(),
+                    ..Default::default()
+                },
+                HookEntry {
+                    command: "uv sync".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(command_summary(&group), "parallel: npm install, uv sync");
+    }
+}
+
+#[cfg(all(test, feature = "impure-test", unix))]
+#[allow(clippy::unwrap_used)]
+mod impure_tests {
+    use super::*;
+
+    #[test]
+    fn test_pty_output_reaches_master_as_terminal_output() {
+        let (master, slave) = open_pty().unwrap();
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "test -t 1 && test -t 2 && echo tty && echo err >&2"])
+            .stdin(Stdio::null())
+            .stdout(slave.try_clone().unwrap())
+            .stderr(slave);
+        let mut child = cmd.spawn().unwrap();
+        drop(cmd);
+
+        let output = Mutex::new(Vec::new());
+        let sink = |_: HookStream, bytes: &[u8]| output.lock().unwrap().extend_from_slice(bytes);
+        forward_chunks(master, HookStream::Stdout, &sink);
+
+        assert!(child.wait().unwrap().success());
+        assert_eq!(output.into_inner().unwrap(), b"tty\nerr\n");
+    }
 }
@@ -14,7 +14,7 @@ kabu() {
     local dest
     dest=$(__kabu_cmd path) || return $?
     if [ -n "$dest" ]; then
-      builtin cd "$dest"
+      builtin cd "$dest" && __kabu_cmd enter
     fi
   elif [ "${1:-}" = "add" ]; then
     local cd_to
@@ -30,7 +30,7 @@ kabu() {
       local new_path
       new_path=$(tail -1 "$tmpfile")
       if [ -d "$new_path" ]; then
-        builtin cd "$new_path"
+        builtin cd "$new_path" && __kabu_cmd enter
       fi
     fi
 
@@ -51,14 +51,14 @@ kabu() {
       case "$cd_to" in
         main)
           if [ -n "$main_path" ]; then
-            builtin cd "$main_path"
+            builtin cd "$main_path" && __kabu_cmd enter
           fi
           ;;
         select)
           local dest
           dest=$(__kabu_cmd path) || return $?
           if [ -n "$dest" ]; then
-            builtin cd "$dest"
+            builtin cd "$dest" && __kabu_cmd enter
           fi
           ;;
       esac
@@ -13,7 +13,7 @@ function kabu
     # If any arguments are provided (like --help), pass them to the command
     set -l dest (__kabu_cmd path)
     if test -n "$dest"
-      builtin cd "$dest"
+      builtin cd "$dest"; and __kabu_cmd enter
     end
   else if test (count $argv) -ge 1; and test "$argv[1]" = "add"
     set -l cd_to (__kabu_cmd config get auto_cd.after_add 2>/dev/null)
@@ -26,7 +26,7 @@ function kabu
     if test $cmd_status -eq 0; and test "$cd_to" = "true"
       set -l new_path (tail -1 $tmpfile)
       if test -d "$new_path"
-        builtin cd "$new_path"
+        builtin cd "$new_path"; and __kabu_cmd enter
       end
     end
 
@@ -44,12 +44,12 @@ function kabu
       switch "$cd_to"
         case main
           if test -n "$main_path"
-            builtin cd "$main_path"
+            builtin cd "$main_path"; and __kabu_cmd enter
           end
         case select
           set -l dest (__kabu_cmd path)
           if test -n "$dest"
-            builtin cd "$dest"
+            builtin cd "$dest"; and __kabu_cmd enter
           end
       end
     end
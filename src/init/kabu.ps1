@@ -16,6 +16,7 @@ function kabu {
     $dest = __kabu_cmd path
     if ($dest) {
       Set-Location $dest
+      __kabu_cmd enter
     }
   } elseif ($Args.Count -ge 1 -and $Args[0] -eq "add") {
     $cdTo = ""
@@ -32,6 +33,7 @@ function kabu {
       $newPath = Get-Content $tmpfile | Select-Object -Last 1
       if ($newPath -and (Test-Path $newPath)) {
         Set-Location $newPath
+        __kabu_cmd enter
       }
     }
 
@@ -56,12 +58,14 @@ function kabu {
         "main" {
           if ($mainPath) {
             Set-Location $mainPath
+            __kabu_cmd enter
           }
         }
         "select" {
           $dest = __kabu_cmd path
           if ($dest) {
             Set-Location $dest
+            __kabu_cmd enter
           }
         }
       }
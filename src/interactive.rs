@@ -20,13 +20,15 @@ use std::time::Duration;
 mod add;
 mod conflict;
 mod path;
+mod prune;
 mod remove;
 mod select;
 mod worktree_list;
 
-pub(crate) use add::{AddInteractiveInput, WorktreeSummary, run_add_interactive};
+pub(crate) use add::{AddInteractiveInput, ProfileSummary, WorktreeSummary, run_add_interactive};
 pub(crate) use conflict::{ConflictChoice, prompt_conflict};
 pub(crate) use path::run_path_interactive;
+pub(crate) use prune::{PruneCandidate, run_prune_selection};
 pub(crate) use remove::{SafetyWarning, run_remove_confirmation, run_remove_selection};
 
 // Shared step name constants for breadcrumb navigation
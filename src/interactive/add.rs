@@ -43,6 +43,11 @@ pub(crate) struct AddInteractiveInput {
     pub suggest_branch_name: Option<BranchNameSuggester>,
     pub validate_branch_name: BranchNameValidator,
     pub theme: UiTheme,
+    pub profiles: Vec<ProfileSummary>,
+    /// Profile selected when the confirm step opens
+    pub initial_profile: Option<String>,
+    /// Whether a profile must be applied (a `default_profile` is configured)
+    pub profile_required: bool,
 }
 
 pub(crate) struct WorktreeSummary {
@@ -50,9 +55,16 @@ pub(crate) struct WorktreeSummary {
     pub branch: Option<String>,
 }
 
+#[derive(Debug, Clone)]
+pub(crate) struct ProfileSummary {
+    pub name: String,
+    pub description: Option<String>,
+}
+
 pub(crate) struct AddInteractiveResult {
     pub branch_choice: BranchChoice,
     pub path: PathBuf,
+    pub profile: Option<String>,
 }
 
 pub(crate) fn run_add_interactive(input: AddInteractiveInput) -> Result<AddInteractiveResult> {
@@ -264,6 +276,9 @@ struct AddUiState {
     path_input: TextInputState,
     branch_name_error: Option<String>,
     confirm_error: Option<String>,
+    /// Profiles to choose from in the confirm step; `None` applies no profile
+    profile_choices: Vec<Option<ProfileSummary>>,
+    profile_cursor: usize,
     preview_branch: Option<String>,
     preview_log: Vec<String>,
     preview_cache: HashMap<String, Vec<String>>,
@@ -284,6 +299,18 @@ impl AddUiState {
             AddDefaultMode::New => BranchTab::New,
         };
 
+        let mut profile_choices: Vec<Option<ProfileSummary>> =
+            input.profiles.iter().cloned().map(Some).collect();
+        if !input.profile_required && !profile_choices.is_empty() {
+            profile_choices.insert(0, None);
+        }
+        let profile_cursor = profile_choices
+            .iter()
+            .position(|choice| {
+                choice.as_ref().map(|profile| &profile.name) == input.initial_profile.as_ref()
+            })
+            .unwrap_or(0);
+
         Self {
             step: AddStep::ModeSelect,
             branch_tab,
@@ -301,6 +328,8 @@ impl AddUiState {
             path_input: TextInputState::new(initial_path),
             branch_name_error: None,
             confirm_error: None,
+            profile_choices,
+            profile_cursor,
             preview_branch: None,
             preview_log: Vec::new(),
             preview_cache: HashMap::new(),
@@ -308,6 +337,24 @@ impl AddUiState {
         }
     }
 
+    fn selected_profile(&self) -> Option<&ProfileSummary> {
+        self.profile_choices
+            .get(self.profile_cursor)
+            .and_then(|choice| choice.as_ref())
+    }
+
+    fn cycle_profile(&mut self, forward: bool) {
+        let len = self.profile_choices.len();
+        if len < 2 {
+            return;
+        }
+        self.profile_cursor = if forward {
+            (self.profile_cursor + 1) % len
+        } else {
+            (self.profile_cursor + len - 1) % len
+        };
+    }
+
     fn current_branch_row(&self) -> Option<&BranchRow> {
         self.matches.get(self.branch_cursor)
     }
@@ -377,9 +424,11 @@ fn run_add_ui(
         {
             let branch_choice = build_branch_choice(&state)?;
             let path = PathBuf::from(state.path_input.value.clone());
+            let profile = state.selected_profile().map(|profile| profile.name.clone());
             return Ok(AddInteractiveResult {
                 branch_choice,
                 path,
+                profile,
             });
         }
     }
@@ -576,11 +625,9 @@ fn handle_branch_step_event(
         }
         KeyCode::Up => state.move_branch_up(),
         KeyCode::Down => state.move_branch_down(),
-        KeyCode::Backspace => {
-            if search_enabled {
-                state.branch_query.pop();
-                filter_branch_rows(state);
-            }
+        KeyCode::Backspace if search_enabled => {
+            state.branch_query.pop();
+            filter_branch_rows(state);
         }
         KeyCode::Enter => {
             if let Some(row) = state.current_branch_row().cloned() {
@@ -619,11 +666,9 @@ fn handle_branch_step_event(
                 match c {
                     'p' | 'k' => state.move_branch_up(),
                     'n' | 'j' => state.move_branch_down(),
-                    'u' => {
-                        if search_enabled {
-                            state.branch_query.clear();
-                            filter_branch_rows(state);
-                        }
+                    'u' if search_enabled => {
+                        state.branch_query.clear();
+                        filter_branch_rows(state);
                     }
                     _ => {}
                 }
@@ -706,11 +751,9 @@ fn handle_commit_input_event(
 ) -> Result<bool> {
     match key.code {
         KeyCode::Esc => return Err(Error::Aborted),
-        KeyCode::Tab => {
-            if validate_commit_input(state) {
-                state.new_branch_origin = Some(NewBranchOrigin::Commit);
-                state.step = AddStep::NewBranchName;
-            }
+        KeyCode::Tab if validate_commit_input(state) => {
+            state.new_branch_origin = Some(NewBranchOrigin::Commit);
+            state.step = AddStep::NewBranchName;
         }
         KeyCode::BackTab => {
             state.commit_input.clear();
@@ -728,11 +771,9 @@ fn handle_commit_input_event(
             state.commit_input.backspace();
             state.commit_error = None;
         }
-        KeyCode::Enter => {
-            if validate_commit_input(state) {
-                state.new_branch_origin = Some(NewBranchOrigin::Commit);
-                state.step = AddStep::NewBranchName;
-            }
+        KeyCode::Enter if validate_commit_input(state) => {
+            state.new_branch_origin = Some(NewBranchOrigin::Commit);
+            state.step = AddStep::NewBranchName;
         }
         KeyCode::Char(c) => {
             if key.modifiers.contains(KeyModifiers::CONTROL) {
@@ -874,6 +915,8 @@ fn handle_confirm_event(
             state.confirm_error = None;
             state.step = AddStep::Path;
         }
+        KeyCode::Left => state.cycle_profile(false),
+        KeyCode::Right => state.cycle_profile(true),
         _ => {}
     }
     Ok(false)
@@ -960,6 +1003,9 @@ fn get_footer_hints(state: &AddUiState) -> &'static str {
         AddStep::NewCommitInput | AddStep::NewBranchName | AddStep::Path => {
             "[Enter/Tab] next  [Shift+Tab] back  [Esc] cancel  [F1] help"
         }
+        AddStep::Confirm if state.profile_choices.len() > 1 => {
+            "[Enter] confirm  [Left/Right] profile  [Shift+Tab] back  [Esc] cancel  [F1] help"
+        }
         AddStep::Confirm => "[Enter] confirm  [Shift+Tab] back  [Esc] cancel  [F1] help",
     }
 }
@@ -1336,6 +1382,25 @@ fn draw_confirm_step(
         format!("Worktree Path: {}", state.path_input.value),
         input.theme.text_style(),
     )));
+    if !state.profile_choices.is_empty() {
+        let profile = match state.selected_profile() {
+            Some(ProfileSummary {
+                name,
+                description: Some(description),
+            }) => format!("{name} - {description}"),
+            Some(profile) => profile.name.clone(),
+            None => "(none)".to_string(),
+        };
+        let hint = if state.profile_choices.len() > 1 {
+            "  [Left/Right] change"
+        } else {
+            ""
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("Profile: {profile}"), input.theme.text_style()),
+            Span::styled(hint, input.theme.muted_style()),
+        ]));
+    }
 
     frame.render_widget(
         Paragraph::new(lines).block(block).wrap(Wrap { trim: true }),
@@ -1624,6 +1689,9 @@ mod tests {
             suggest_branch_name: None,
             validate_branch_name: Arc::new(|_| Ok(None)),
             theme: UiTheme::default(),
+            profiles: vec![],
+            initial_profile: None,
+            profile_required: false,
         }
     }
 
@@ -2507,6 +2575,45 @@ mod tests {
         assert_eq!(state.confirm_error, Some("Select a branch".to_string()));
     }
 
+    fn profile(name: &str) -> ProfileSummary {
+        ProfileSummary {
+            name: name.to_string(),
+            description: None,
+        }
+    }
+
+    #[test]
+    fn test_profile_choices_offer_none_without_default() {
+        let mut input = create_test_input();
+        input.profiles = vec![profile("full"), profile("review")];
+        let mut state = AddUiState::new(&input);
+        state.step = AddStep::Confirm;
+
+        assert_eq!(state.profile_choices.len(), 3);
+        assert!(state.selected_profile().is_none());
+
+        let key = create_key_event(KeyCode::Left, KeyModifiers::NONE);
+        handle_confirm_event(&mut state, &input, key).unwrap();
+        assert_eq!(state.selected_profile().unwrap().name, "review");
+    }
+
+    #[test]
+    fn test_profile_choices_start_on_default() {
+        let mut input = create_test_input();
+        input.profiles = vec![profile("full"), profile("review")];
+        input.initial_profile = Some("review".to_string());
+        input.profile_required = true;
+        let mut state = AddUiState::new(&input);
+        state.step = AddStep::Confirm;
+
+        assert_eq!(state.profile_choices.len(), 2);
+        assert_eq!(state.selected_profile().unwrap().name, "review");
+
+        let key = create_key_event(KeyCode::Right, KeyModifiers::NONE);
+        handle_confirm_event(&mut state, &input, key).unwrap();
+        assert_eq!(state.selected_profile().unwrap().name, "full");
+    }
+
     // Rendering tests with TestBackend
     #[test]
     fn test_draw_mode_select_renders() {
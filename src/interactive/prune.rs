@@ -0,0 +1,111 @@
+use crate::error::{Error, Result};
+use crate::prompt;
+use crate::vcs::WorkspaceInfo;
+
+use std::path::PathBuf;
+
+use super::STEP_SELECT_WORKTREE;
+use super::resolve_ui_theme;
+use super::worktree_list::{SelectMode, WorktreeEntry, build_worktree_entries, select_worktrees};
+
+/// A worktree/workspace proposed for pruning.
+#[derive(Debug, Clone)]
+pub(crate) struct PruneCandidate {
+    pub workspace: WorkspaceInfo,
+    /// Short reason label shown next to the entry (e.g. "merged", "gone").
+    pub reason: String,
+}
+
+pub(crate) fn run_prune_selection(candidates: &[PruneCandidate]) -> Result<Vec<PathBuf>> {
+    if !prompt::is_interactive() {
+        return Err(Error::PruneRequiresConfirmation);
+    }
+
+    let current_dir = std::env::current_dir().ok();
+    let entries = build_prune_entries(candidates, current_dir.as_deref());
+    if entries.is_empty() {
+        return Err(Error::NoWorktreesToRemove);
+    }
+
+    let theme = resolve_ui_theme()?;
+    select_worktrees(
+        &entries,
+        SelectMode::Multi,
+        "Prune worktrees",
+        &[STEP_SELECT_WORKTREE],
+        theme,
+    )
+}
+
+/// Builds selector entries with the prune reason appended to each line.
+fn build_prune_entries(
+    candidates: &[PruneCandidate],
+    current_dir: Option<&std::path::Path>,
+) -> Vec<WorktreeEntry> {
+    candidates
+        .iter()
+        .flat_map(|candidate| {
+            build_worktree_entries(
+                std::slice::from_ref(&candidate.workspace),
+                false,
+                current_dir,
+            )
+            .into_iter()
+            .map(|mut entry| {
+                entry.display.push_str(&format!(" [{}]", candidate.reason));
+                entry
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(path: &str, branch: &str, reason: &str) -> PruneCandidate {
+        PruneCandidate {
+            workspace: WorkspaceInfo {
+                path: PathBuf::from(path),
+                head: "abc1234".to_string(),
+                branch: Some(format!("refs/heads/{branch}")),
+                is_main: false,
+                is_locked: false,
+                lock_reason: None,
+                is_prunable: false,
+                workspace_name: None,
+            },
+            reason: reason.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_build_prune_entries_appends_reason() {
+        let candidates = vec![
+            candidate("/repo/feature-a", "feature-a", "merged"),
+            candidate("/repo/feature-b", "feature-b", "gone"),
+        ];
+
+        let entries = build_prune_entries(&candidates, None);
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].display, "/repo/feature-a (feature-a) [merged]");
+        assert_eq!(entries[1].display, "/repo/feature-b (feature-b) [gone]");
+        assert_eq!(entries[1].path, PathBuf::from("/repo/feature-b"));
+    }
+
+    #[test]
+    fn test_build_prune_entries_marks_current() {
+        let candidates = vec![candidate("/repo/feature-a", "feature-a", "merged")];
+
+        let entries = build_prune_entries(
+            &candidates,
+            Some(std::path::Path::new("/repo/feature-a/src")),
+        );
+
+        assert_eq!(
+            entries[0].display,
+            "/repo/feature-a (feature-a) [current] [merged]"
+        );
+    }
+}
@@ -66,7 +66,11 @@ pub(crate) fn build_worktree_entries(
             } else {
                 ""
             };
-            let lock_info = if ws.is_locked { " [locked]" } else { "" };
+            let lock_info = match (ws.is_locked, &ws.lock_reason) {
+                (true, Some(reason)) => format!(" [locked: {reason}]"),
+                (true, None) => " [locked]".to_string(),
+                (false, _) => String::new(),
+            };
             let is_current = current_dir
                 .map(|dir| dir.starts_with(&ws.path))
                 .unwrap_or(false);
@@ -433,6 +437,8 @@ mod tests {
             branch: Some("refs/heads/main".to_string()),
             is_main: true,
             is_locked: false,
+            lock_reason: None,
+            is_prunable: false,
             workspace_name: None,
         }];
 
@@ -451,6 +457,8 @@ mod tests {
                 branch: Some("refs/heads/main".to_string()),
                 is_main: true,
                 is_locked: false,
+                lock_reason: None,
+                is_prunable: false,
                 workspace_name: None,
             },
             WorkspaceInfo {
@@ -459,6 +467,8 @@ mod tests {
                 branch: Some("refs/heads/feature-1".to_string()),
                 is_main: false,
                 is_locked: false,
+                lock_reason: None,
+                is_prunable: false,
                 workspace_name: None,
             },
         ];
@@ -486,6 +496,8 @@ mod tests {
             branch: Some("refs/heads/main".to_string()),
             is_main: true,
             is_locked: false,
+            lock_reason: None,
+            is_prunable: false,
             workspace_name: None,
         }];
 
@@ -503,6 +515,8 @@ mod tests {
                 branch: Some("refs/heads/main".to_string()),
                 is_main: true,
                 is_locked: false,
+                lock_reason: None,
+                is_prunable: false,
                 workspace_name: None,
             },
             WorkspaceInfo {
@@ -511,6 +525,8 @@ mod tests {
                 branch: Some("refs/heads/feature-1".to_string()),
                 is_main: false,
                 is_locked: false,
+                lock_reason: None,
+                is_prunable: false,
                 workspace_name: None,
             },
             WorkspaceInfo {
@@ -519,6 +535,8 @@ mod tests {
                 branch: Some("refs/heads/feature-2".to_string()),
                 is_main: false,
                 is_locked: false,
+                lock_reason: None,
+                is_prunable: false,
                 workspace_name: None,
             },
         ];
@@ -539,6 +557,8 @@ mod tests {
                 branch: Some("refs/heads/main".to_string()),
                 is_main: true,
                 is_locked: false,
+                lock_reason: None,
+                is_prunable: false,
                 workspace_name: None,
             },
             WorkspaceInfo {
@@ -547,6 +567,8 @@ mod tests {
                 branch: Some("refs/heads/feature".to_string()),
                 is_main: false,
                 is_locked: false,
+                lock_reason: None,
+                is_prunable: false,
                 workspace_name: None,
             },
             WorkspaceInfo {
@@ -555,6 +577,8 @@ mod tests {
                 branch: Some("refs/heads/locked".to_string()),
                 is_main: false,
                 is_locked: true,
+                lock_reason: None,
+                is_prunable: false,
                 workspace_name: None,
             },
         ];
@@ -577,6 +601,8 @@ mod tests {
                 branch: Some("refs/heads/main".to_string()),
                 is_main: true,
                 is_locked: false,
+                lock_reason: None,
+                is_prunable: false,
                 workspace_name: None,
             },
             WorkspaceInfo {
@@ -585,6 +611,8 @@ mod tests {
                 branch: Some("refs/heads/feature-1".to_string()),
                 is_main: false,
                 is_locked: false,
+                lock_reason: None,
+                is_prunable: false,
                 workspace_name: None,
             },
             WorkspaceInfo {
@@ -593,6 +621,8 @@ mod tests {
                 branch: None,
                 is_main: false,
                 is_locked: false,
+                lock_reason: None,
+                is_prunable: false,
                 workspace_name: None,
             },
             WorkspaceInfo {
@@ -601,6 +631,8 @@ mod tests {
                 branch: Some("refs/heads/feature-3".to_string()),
                 is_main: false,
                 is_locked: true,
+                lock_reason: Some("on a USB drive".to_string()),
+                is_prunable: false,
                 workspace_name: None,
             },
         ];
@@ -610,7 +642,7 @@ mod tests {
         assert_eq!(result.len(), 3);
         assert!(result[0].display.contains("feature-1"));
         assert!(result[1].display.contains("(detached)"));
-        assert!(result[2].display.contains("[locked]"));
+        assert!(result[2].display.contains("[locked: on a USB drive]"));
     }
 
     #[test]
@@ -621,6 +653,8 @@ mod tests {
             branch: None,
             is_main: false,
             is_locked: false,
+            lock_reason: None,
+            is_prunable: false,
             workspace_name: None,
         }];
 
@@ -639,6 +673,8 @@ mod tests {
                 branch: Some("refs/heads/main".to_string()),
                 is_main: true,
                 is_locked: false,
+                lock_reason: None,
+                is_prunable: false,
                 workspace_name: None,
             },
             WorkspaceInfo {
@@ -647,6 +683,8 @@ mod tests {
                 branch: Some("refs/heads/feature-1".to_string()),
                 is_main: false,
                 is_locked: false,
+                lock_reason: None,
+                is_prunable: false,
                 workspace_name: None,
             },
         ];
@@ -667,6 +705,8 @@ mod tests {
             branch: Some("refs/heads/feature-1".to_string()),
             is_main: false,
             is_locked: false,
+            lock_reason: None,
+            is_prunable: false,
             workspace_name: None,
         }];
 
@@ -686,6 +726,8 @@ mod tests {
             branch: Some("refs/heads/feature-1".to_string()),
             is_main: false,
             is_locked: false,
+            lock_reason: None,
+            is_prunable: false,
             workspace_name: None,
         }];
 
@@ -9,6 +9,7 @@ mod interactive;
 mod operation;
 mod output;
 mod prompt;
+mod state;
 mod trust;
 mod vcs;
 
@@ -51,6 +52,15 @@ fn main() -> ExitCode {
             let color_config = color::ColorConfig::new(color_choice);
             command::remove(remove_args, color_config)
         }
+        cli::Command::Move(move_args) => {
+            let color_choice = if move_args.no_color {
+                clap::ColorChoice::Never
+            } else {
+                move_args.color
+            };
+            let color_config = color::ColorConfig::new(color_choice);
+            command::mv(move_args, color_config)
+        }
         cli::Command::List(list_args) => {
             let color_choice = if list_args.no_color {
                 clap::ColorChoice::Never
@@ -60,6 +70,54 @@ fn main() -> ExitCode {
             let color_config = color::ColorConfig::new(color_choice);
             command::list(list_args, color_config)
         }
+        cli::Command::Setup(setup_args) => {
+            let color_choice = if setup_args.no_color {
+                clap::ColorChoice::Never
+            } else {
+                setup_args.color
+            };
+            let color_config = color::ColorConfig::new(color_choice);
+            command::setup(setup_args, color_config)
+        }
+        cli::Command::Prune(prune_args) => {
+            let color_choice = if prune_args.no_color {
+                clap::ColorChoice::Never
+            } else {
+                prune_args.color
+            };
+            let color_config = color::ColorConfig::new(color_choice);
+            command::prune(prune_args, color_config)
+        }
+        cli::Command::Lock(lock_args) => {
+            let color_config = color::ColorConfig::new(clap::ColorChoice::Auto);
+            command::lock(lock_args, color_config)
+        }
+        cli::Command::Unlock(unlock_args) => {
+            let color_config = color::ColorConfig::new(clap::ColorChoice::Auto);
+            command::unlock(unlock_args, color_config)
+        }
+        cli::Command::Doctor(doctor_args) => {
+            let color_choice = if doctor_args.no_color {
+                clap::ColorChoice::Never
+            } else {
+                doctor_args.color
+            };
+            let color_config = color::ColorConfig::new(color_choice);
+            command::doctor(doctor_args, color_config)
+        }
+        cli::Command::Enter(enter_args) => {
+            let color_choice = if enter_args.no_color {
+                clap::ColorChoice::Never
+            } else {
+                enter_args.color
+            };
+            let color_config = color::ColorConfig::new(color_choice);
+            command::enter(enter_args, color_config)
+        }
+        cli::Command::Logs(logs_args) => {
+            color::ColorConfig::new(clap::ColorChoice::Auto);
+            command::logs(logs_args)
+        }
         cli::Command::Path(path_args) => {
             color::ColorConfig::new(clap::ColorChoice::Auto);
             command::path(path_args)
@@ -3,7 +3,7 @@ mod copy;
 mod link;
 mod mkdir;
 
-pub(crate) use conflict::{ConflictAction, check_conflict, resolve_conflict};
-pub(crate) use copy::copy_file;
+pub(crate) use conflict::{ConflictAction, backup_path, check_conflict, resolve_conflict};
+pub(crate) use copy::{CopyOptions, RenderVars, copy_file, render_file};
 pub(crate) use link::create_symlink;
 pub(crate) use mkdir::create_directory;
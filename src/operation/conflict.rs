@@ -1,7 +1,7 @@
 use crate::config::OnConflict;
 use crate::error::Result;
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Action to take after conflict resolution.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -16,6 +16,16 @@ pub(crate) fn check_conflict(target: &Path) -> bool {
     target.exists()
 }
 
+/// Path a conflicting target is moved to with `on_conflict: backup`.
+///
+/// `file.txt` becomes `file.txt.bak`, `file` becomes `file.bak`.
+pub(crate) fn backup_path(target: &Path) -> PathBuf {
+    match target.extension() {
+        Some(ext) => target.with_extension(format!("{}.bak", ext.to_string_lossy())),
+        None => target.with_extension("bak"),
+    }
+}
+
 /// Resolve conflict by removing or backing up the target.
 pub(crate) fn resolve_conflict(target: &Path, mode: OnConflict) -> Result<ConflictAction> {
     match mode {
@@ -31,12 +41,7 @@ pub(crate) fn resolve_conflict(target: &Path, mode: OnConflict) -> Result<Confli
             Ok(ConflictAction::Proceed)
         }
         OnConflict::Backup => {
-            // Create backup: file.txt -> file.txt.bak, file -> file.bak
-            let backup_path = match target.extension() {
-                Some(ext) => target.with_extension(format!("{}.bak", ext.to_string_lossy())),
-                None => target.with_extension("bak"),
-            };
-            std::fs::rename(target, &backup_path)?;
+            std::fs::rename(target, backup_path(target))?;
             Ok(ConflictAction::Proceed)
         }
     }
@@ -1,9 +1,204 @@
+use crate::config::{self, CopyMode, Preserve};
 use crate::error::{Error, Result};
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use walkdir::WalkDir;
+
+/// Variables expanded in the contents of `render: true` copy entries.
+pub(crate) struct RenderVars {
+    /// Worktree/workspace directory name
+    pub worktree_name: String,
+    /// Branch name (git) or bookmark name (jj), if applicable
+    pub branch: Option<String>,
+    /// Repository name
+    pub repository: String,
+}
+
+impl RenderVars {
+    /// Expand template variables in file contents.
+    ///
+    /// # Supported Variables
+    /// - `{{worktree_name}}` / `{{workspace_name}}`: Directory name
+    /// - `{{branch}}` / `{{bookmark}}`: Branch name (git) or bookmark name (jj)
+    /// - `{{repository}}`: Repository name
+    ///
+    /// Values are inserted verbatim. Unknown `{{...}}` sequences are left untouched so
+    /// files that use their own templating syntax survive rendering.
+    pub(crate) fn expand(&self, content: &str) -> String {
+        let branch = self.branch.as_deref().unwrap_or("");
+        content
+            .replace("{{worktree_name}}", &self.worktree_name)
+            .replace("{{workspace_name}}", &self.worktree_name)
+            .replace("{{branch}}", branch)
+            .replace("{{bookmark}}", branch)
+            .replace("{{repository}}", &self.repository)
+    }
+}
+
+/// How a copy entry selects and writes the files of its source.
+pub(crate) struct CopyOptions {
+    mode: CopyMode,
+    /// Files to copy; None copies everything that is not excluded
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+    preserve: Vec<Preserve>,
+    max_size: Option<u64>,
+}
+
+impl Default for CopyOptions {
+    fn default() -> Self {
+        CopyOptions {
+            mode: CopyMode::Copy,
+            include: None,
+            exclude: GlobSet::empty(),
+            preserve: Vec::new(),
+            max_size: None,
+        }
+    }
+}
+
+impl CopyOptions {
+    /// Options of a `copy:` entry.
+    pub(crate) fn new(copy: &config::Copy) -> Result<Self> {
+        let include = if copy.include.is_empty() {
+            None
+        } else {
+            Some(build_globset(&copy.include)?)
+        };
+        Ok(CopyOptions {
+            mode: copy.mode,
+            include,
+            exclude: build_globset(&copy.exclude)?,
+            preserve: copy.preserve.clone(),
+            max_size: copy.max_size,
+        })
+    }
+
+    fn preserves(&self, what: Preserve) -> bool {
+        self.preserve.contains(&what)
+    }
+
+    /// Whether a file below the source directory is included, by its own path or that
+    /// of a directory it is in.
+    fn includes(&self, relative: &Path) -> bool {
+        match &self.include {
+            None => true,
+            Some(include) => relative
+                .ancestors()
+                .any(|path| !path.as_os_str().is_empty() && include.is_match(path)),
+        }
+    }
+}
+
+/// Compile `include:`/`exclude:` patterns.
+///
+/// Patterns without a `/` match a file or directory name at any depth, as in
+/// .gitignore; others match the path relative to the source directory.
+fn build_globset(patterns: &[String]) -> Result<GlobSet> {
+    let invalid = |pattern: &str, e: globset::Error| Error::ConfigValidation {
+        message: format!("Invalid glob pattern '{}': {}", pattern, e),
+    };
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let anchored = if pattern.contains('/') {
+            pattern.trim_start_matches('/').to_string()
+        } else {
+            format!("**/{pattern}")
+        };
+        let glob = GlobBuilder::new(&anchored)
+            .literal_separator(true)
+            .build()
+            .map_err(|e| invalid(pattern, e))?;
+        builder.add(glob);
+    }
+    builder
+        .build()
+        .map_err(|e| invalid(&patterns.join(", "), e))
+}
 
 /// Copy a file or directory to target, creating parent dirs as needed.
-pub(crate) fn copy_file(source: &Path, target: &Path) -> Result<()> {
+pub(crate) fn copy_file(source: &Path, target: &Path, options: &CopyOptions) -> Result<()> {
+    let items = plan_copy(source, options)?;
+    write_items(source, target, &items, options, |source, target| {
+        copy_single_file(source, target, options.mode)
+    })
+}
+
+/// Render a file or directory to target, expanding [`RenderVars`] in file contents.
+///
+/// Source files must be UTF-8 text. Permissions of each file are carried over.
+pub(crate) fn render_file(
+    source: &Path,
+    target: &Path,
+    vars: &RenderVars,
+    options: &CopyOptions,
+) -> Result<()> {
+    let items = plan_copy(source, options)?;
+    write_items(source, target, &items, options, |source, target| {
+        render_single_file(source, target, vars)
+    })
+}
+
+/// Something to create below the target, relative to the source.
+enum CopyItem {
+    Dir(PathBuf),
+    File(PathBuf),
+    Symlink(PathBuf),
+}
+
+/// Walk the source and select what to copy.
+///
+/// `max_size` is checked here, so an oversized copy fails before anything is written.
+fn plan_copy(source: &Path, options: &CopyOptions) -> Result<Vec<CopyItem>> {
+    let relative = |path: &Path| path.strip_prefix(source).unwrap_or(path).to_path_buf();
+
+    let mut items = Vec::new();
+    let mut size: u64 = 0;
+    let walker = WalkDir::new(source)
+        .follow_links(!options.preserves(Preserve::Symlinks))
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0 || !options.exclude.is_match(relative(entry.path()))
+        });
+    for entry in walker {
+        let entry = entry.map_err(std::io::Error::from)?;
+        let path = relative(entry.path());
+        let file_type = entry.file_type();
+        if file_type.is_dir() {
+            items.push(CopyItem::Dir(path));
+        } else if entry.depth() > 0 && !options.includes(&path) {
+            continue;
+        } else if file_type.is_symlink() {
+            items.push(CopyItem::Symlink(path));
+        } else {
+            size = size.saturating_add(entry.metadata().map_err(std::io::Error::from)?.len());
+            items.push(CopyItem::File(path));
+        }
+    }
+
+    if let Some(max_size) = options.max_size
+        && size > max_size
+    {
+        return Err(Error::CopyTooLarge {
+            path: source.to_path_buf(),
+            size,
+            max_size,
+        });
+    }
+    Ok(items)
+}
+
+/// Create planned items below target, producing regular files with `write_file`.
+fn write_items(
+    source: &Path,
+    target: &Path,
+    items: &[CopyItem],
+    options: &CopyOptions,
+    write_file: impl Fn(&Path, &Path) -> Result<()>,
+) -> Result<()> {
     // Ensure parent directory exists
     if let Some(parent) = target.parent()
         && !parent.exists()
@@ -11,48 +206,255 @@ pub(crate) fn copy_file(source: &Path, target: &Path) -> Result<()> {
         std::fs::create_dir_all(parent)?;
     }
 
-    if source.is_dir() {
-        copy_dir_recursive(source, target)?;
+    let mut dirs = Vec::new();
+    for item in items {
+        match item {
+            CopyItem::Dir(relative) => {
+                // With include:, directories are only created for the files they hold
+                if options.include.is_none() || relative.as_os_str().is_empty() {
+                    std::fs::create_dir_all(join(target, relative))?;
+                }
+                dirs.push(relative);
+            }
+            CopyItem::Symlink(relative) => {
+                let source_path = join(source, relative);
+                let link = std::fs::read_link(&source_path)?;
+                super::create_symlink(&link, &join(target, relative))?;
+            }
+            CopyItem::File(relative) => {
+                let source_path = join(source, relative);
+                let target_path = join(target, relative);
+                if let Some(parent) = target_path.parent()
+                    && !parent.exists()
+                {
+                    std::fs::create_dir_all(parent)?;
+                }
+                write_file(&source_path, &target_path)?;
+                // A hard link already shares everything with its source
+                if options.mode != CopyMode::Hardlink {
+                    preserve_metadata(&source_path, &target_path, options)?;
+                }
+            }
+        }
+    }
+
+    // Directory permissions go last so read-only directories can still be filled
+    if options.preserves(Preserve::Mode) {
+        for relative in dirs.iter().rev() {
+            let target_path = join(target, relative);
+            if target_path.is_dir() {
+                let permissions = std::fs::metadata(join(source, relative))?.permissions();
+                std::fs::set_permissions(&target_path, permissions)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Join a path relative to the source; the empty path is the source itself.
+fn join(base: &Path, relative: &Path) -> PathBuf {
+    if relative.as_os_str().is_empty() {
+        base.to_path_buf()
     } else {
-        std::fs::copy(source, target).map_err(|e| Error::CopyFailed {
-            source: source.to_path_buf(),
-            target: target.to_path_buf(),
-            cause: e,
-        })?;
+        base.join(relative)
+    }
+}
+
+/// Carry over the modification time and permissions selected with `preserve:`.
+fn preserve_metadata(source: &Path, target: &Path, options: &CopyOptions) -> Result<()> {
+    let preserve_mode = options.preserves(Preserve::Mode);
+    let preserve_mtime = options.preserves(Preserve::Mtime);
+    if !preserve_mode && !preserve_mtime {
+        return Ok(());
     }
 
+    let copy_failed = |e| Error::CopyFailed {
+        source: source.to_path_buf(),
+        target: target.to_path_buf(),
+        cause: e,
+    };
+    let metadata = std::fs::metadata(source).map_err(copy_failed)?;
+    if preserve_mtime {
+        let modified = metadata.modified().map_err(copy_failed)?;
+        set_modified(target, modified).map_err(copy_failed)?;
+    }
+    if preserve_mode {
+        std::fs::set_permissions(target, metadata.permissions()).map_err(copy_failed)?;
+    }
     Ok(())
 }
 
-/// Recursively copy a directory
-fn copy_dir_recursive(source: &Path, target: &Path) -> Result<()> {
-    std::fs::create_dir_all(target)?;
+/// Set the modification time of a file without opening it for writing.
+///
+/// The target may already be read-only (`std::fs::copy` carries over the mode); its
+/// owner can still set the times through a read-only handle.
+fn set_modified(path: &Path, modified: std::time::SystemTime) -> std::io::Result<()> {
+    #[cfg(windows)]
+    let file = {
+        use std::os::windows::fs::OpenOptionsExt;
+        const FILE_WRITE_ATTRIBUTES: u32 = 0x100;
+        std::fs::OpenOptions::new()
+            .access_mode(FILE_WRITE_ATTRIBUTES)
+            .open(path)?
+    };
+    #[cfg(not(windows))]
+    let file = std::fs::File::open(path)?;
+    file.set_modified(modified)
+}
 
-    for entry in std::fs::read_dir(source)? {
-        let entry = entry?;
-        let source_path = entry.path();
-        let target_path = target.join(entry.file_name());
+/// Copy one file with the given mode.
+fn copy_single_file(source: &Path, target: &Path, mode: CopyMode) -> Result<()> {
+    let result = match mode {
+        CopyMode::Copy => std::fs::copy(source, target).map(|_| ()),
+        CopyMode::Hardlink => std::fs::hard_link(source, target),
+        CopyMode::Reflink | CopyMode::Auto => reflink_or_copy(source, target),
+    };
+    result.map_err(|e| Error::CopyFailed {
+        source: source.to_path_buf(),
+        target: target.to_path_buf(),
+        cause: e,
+    })
+}
 
-        if source_path.is_dir() {
-            copy_dir_recursive(&source_path, &target_path)?;
-        } else {
-            std::fs::copy(&source_path, &target_path).map_err(|e| Error::CopyFailed {
-                source: source_path.clone(),
-                target: target_path.clone(),
-                cause: e,
-            })?;
-        }
+/// Clone a file where the filesystem supports reflinks, and copy it otherwise.
+fn reflink_or_copy(source: &Path, target: &Path) -> std::io::Result<()> {
+    reflink(source, target).or_else(|_| std::fs::copy(source, target).map(|_| ()))
+}
+
+/// Clone a file with FICLONE, sharing its data blocks until either side is changed.
+///
+/// Supported on btrfs, xfs and other copy-on-write filesystems. The partly created
+/// target is removed if the filesystem refuses.
+#[cfg(target_os = "linux")]
+fn reflink(source: &Path, target: &Path) -> std::io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let source_file = std::fs::File::open(source)?;
+    let target_file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(target)?;
+    // SAFETY: both descriptors are open for the duration of the call and FICLONE
+    // takes the source descriptor by value
+    let ret = unsafe {
+        libc::ioctl(
+            target_file.as_raw_fd(),
+            libc::FICLONE,
+            source_file.as_raw_fd(),
+        )
+    };
+    if ret == -1 {
+        let err = std::io::Error::last_os_error();
+        drop(target_file);
+        let _ = std::fs::remove_file(target);
+        return Err(err);
     }
+    target_file.set_permissions(source_file.metadata()?.permissions())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn reflink(_source: &Path, _target: &Path) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "reflinks are only supported on Linux",
+    ))
+}
+
+/// Render one file, keeping its permissions.
+fn render_single_file(source: &Path, target: &Path, vars: &RenderVars) -> Result<()> {
+    let copy_failed = |e| Error::CopyFailed {
+        source: source.to_path_buf(),
+        target: target.to_path_buf(),
+        cause: e,
+    };
+    let content = std::fs::read_to_string(source).map_err(copy_failed)?;
+    std::fs::write(target, vars.expand(&content)).map_err(copy_failed)?;
+    let permissions = std::fs::metadata(source)
+        .map_err(copy_failed)?
+        .permissions();
+    std::fs::set_permissions(target, permissions).map_err(copy_failed)?;
 
     Ok(())
 }
 
-#[cfg(all(test, feature = "impure-test"))]
+#[cfg(test)]
 mod tests {
     use super::*;
 
+    fn vars() -> RenderVars {
+        RenderVars {
+            worktree_name: "feature-x".to_string(),
+            branch: Some("feature/x".to_string()),
+            repository: "myrepo".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_expand_variables() {
+        let content = "COMPOSE_PROJECT_NAME={{repository}}-{{worktree_name}}\nBRANCH={{branch}}\n";
+        assert_eq!(
+            vars().expand(content),
+            "COMPOSE_PROJECT_NAME=myrepo-feature-x\nBRANCH=feature/x\n"
+        );
+    }
+
+    #[test]
+    fn test_expand_jj_aliases() {
+        assert_eq!(
+            vars().expand("{{workspace_name}} {{bookmark}}"),
+            "feature-x feature/x"
+        );
+    }
+
+    #[test]
+    fn test_expand_without_branch() {
+        let vars = RenderVars {
+            branch: None,
+            ..vars()
+        };
+        assert_eq!(vars.expand("db_{{branch}}"), "db_");
+    }
+
+    #[test]
+    fn test_expand_leaves_unknown_variables() {
+        assert_eq!(
+            vars().expand("{{ .Values.name }} {{unknown}}"),
+            "{{ .Values.name }} {{unknown}}"
+        );
+    }
+}
+
+#[cfg(all(test, feature = "impure-test"))]
+#[allow(clippy::unwrap_used)]
+mod impure_tests {
+    use super::*;
+
     use tempfile::TempDir;
 
+    fn with_mode(mode: CopyMode) -> CopyOptions {
+        CopyOptions {
+            mode,
+            ..CopyOptions::default()
+        }
+    }
+
+    fn globs(patterns: &[&str]) -> GlobSet {
+        let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+        build_globset(&patterns).unwrap()
+    }
+
+    /// A small virtualenv-like tree.
+    fn venv(root: &Path) -> PathBuf {
+        let source = root.join(".venv");
+        std::fs::create_dir_all(source.join("lib/app/__pycache__")).unwrap();
+        std::fs::create_dir_all(source.join("bin")).unwrap();
+        std::fs::write(source.join("lib/app/main.py"), "print(1)").unwrap();
+        std::fs::write(source.join("lib/app/__pycache__/main.pyc"), "bytecode").unwrap();
+        std::fs::write(source.join("bin/activate"), "# activate").unwrap();
+        source
+    }
+
     #[test]
     fn test_copy_file() {
         let temp = TempDir::new().unwrap();
@@ -61,7 +463,7 @@ mod tests {
 
         std::fs::write(&source, "hello").unwrap();
 
-        copy_file(&source, &target).unwrap();
+        copy_file(&source, &target, &CopyOptions::default()).unwrap();
 
         assert!(target.exists());
         assert_eq!(std::fs::read_to_string(&target).unwrap(), "hello");
@@ -75,7 +477,7 @@ mod tests {
 
         std::fs::write(&source, "hello").unwrap();
 
-        copy_file(&source, &target).unwrap();
+        copy_file(&source, &target, &CopyOptions::default()).unwrap();
 
         assert!(target.exists());
     }
@@ -91,7 +493,7 @@ mod tests {
         std::fs::create_dir(source_dir.join("subdir")).unwrap();
         std::fs::write(source_dir.join("subdir/file2.txt"), "content2").unwrap();
 
-        copy_file(&source_dir, &target_dir).unwrap();
+        copy_file(&source_dir, &target_dir, &CopyOptions::default()).unwrap();
 
         assert!(target_dir.join("file1.txt").exists());
         assert!(target_dir.join("subdir/file2.txt").exists());
@@ -100,4 +502,220 @@ mod tests {
             "content1"
         );
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_directory_with_hardlinks() {
+        use std::os::unix::fs::MetadataExt;
+
+        let temp = TempDir::new().unwrap();
+        let source_dir = temp.path().join("node_modules");
+        let target_dir = temp.path().join("wt/node_modules");
+        std::fs::create_dir_all(source_dir.join("pkg")).unwrap();
+        std::fs::write(source_dir.join("pkg/index.js"), "module.exports = 1;").unwrap();
+
+        copy_file(&source_dir, &target_dir, &with_mode(CopyMode::Hardlink)).unwrap();
+
+        let source_meta = std::fs::metadata(source_dir.join("pkg/index.js")).unwrap();
+        let target_meta = std::fs::metadata(target_dir.join("pkg/index.js")).unwrap();
+        assert_eq!(source_meta.ino(), target_meta.ino());
+    }
+
+    #[test]
+    fn test_copy_auto_falls_back_to_plain_copy() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("cache.bin");
+        let target = temp.path().join("wt/cache.bin");
+        std::fs::write(&source, "cached").unwrap();
+
+        // Whether or not the temp filesystem supports reflinks, the data arrives
+        copy_file(&source, &target, &with_mode(CopyMode::Auto)).unwrap();
+
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "cached");
+    }
+
+    #[test]
+    fn test_copy_reflink_falls_back_to_plain_copy() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("cache.bin");
+        let target = temp.path().join("cache-copy.bin");
+        std::fs::write(&source, "cached").unwrap();
+
+        // Whether or not the temp filesystem supports reflinks, the data arrives
+        copy_file(&source, &target, &with_mode(CopyMode::Reflink)).unwrap();
+
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "cached");
+    }
+
+    #[test]
+    fn test_render_file() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join(".env.example");
+        let target = temp.path().join("wt/.env");
+
+        std::fs::write(&source, "DATABASE_NAME={{repository}}_{{worktree_name}}\n").unwrap();
+
+        let vars = RenderVars {
+            worktree_name: "feature".to_string(),
+            branch: None,
+            repository: "app".to_string(),
+        };
+        render_file(&source, &target, &vars, &CopyOptions::default()).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&target).unwrap(),
+            "DATABASE_NAME=app_feature\n"
+        );
+    }
+
+    #[test]
+    fn test_copy_directory_with_exclude() {
+        let temp = TempDir::new().unwrap();
+        let source = venv(temp.path());
+        let target = temp.path().join("wt/.venv");
+
+        let options = CopyOptions {
+            exclude: globs(&["__pycache__"]),
+            ..CopyOptions::default()
+        };
+        copy_file(&source, &target, &options).unwrap();
+
+        assert!(target.join("lib/app/main.py").exists());
+        assert!(target.join("bin/activate").exists());
+        assert!(!target.join("lib/app/__pycache__").exists());
+    }
+
+    #[test]
+    fn test_copy_directory_with_include() {
+        let temp = TempDir::new().unwrap();
+        let source = venv(temp.path());
+        let target = temp.path().join("wt/.venv");
+
+        let options = CopyOptions {
+            include: Some(globs(&["*.py", "bin"])),
+            ..CopyOptions::default()
+        };
+        copy_file(&source, &target, &options).unwrap();
+
+        assert!(target.join("lib/app/main.py").exists());
+        // Files inside an included directory come along
+        assert!(target.join("bin/activate").exists());
+        assert!(!target.join("lib/app/__pycache__").exists());
+    }
+
+    #[test]
+    fn test_copy_refuses_more_than_max_size() {
+        let temp = TempDir::new().unwrap();
+        let source = venv(temp.path());
+        let target = temp.path().join("wt/.venv");
+
+        let options = CopyOptions {
+            max_size: Some(10),
+            ..CopyOptions::default()
+        };
+        let err = copy_file(&source, &target, &options).unwrap_err();
+
+        assert!(matches!(err, Error::CopyTooLarge { size: 26, .. }));
+        assert!(!target.exists());
+
+        // Excluded files do not count
+        let options = CopyOptions {
+            exclude: globs(&["lib"]),
+            max_size: Some(10),
+            ..CopyOptions::default()
+        };
+        copy_file(&source, &target, &options).unwrap();
+        assert!(target.join("bin/activate").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_preserves_symlinks() {
+        let temp = TempDir::new().unwrap();
+        let source = venv(temp.path());
+        std::os::unix::fs::symlink("/usr/bin/python3", source.join("bin/python")).unwrap();
+        std::os::unix::fs::symlink("missing", source.join("bin/dangling")).unwrap();
+        let target = temp.path().join("wt/.venv");
+
+        let options = CopyOptions {
+            preserve: vec![Preserve::Symlinks],
+            ..CopyOptions::default()
+        };
+        copy_file(&source, &target, &options).unwrap();
+
+        assert_eq!(
+            std::fs::read_link(target.join("bin/python")).unwrap(),
+            PathBuf::from("/usr/bin/python3")
+        );
+        assert_eq!(
+            std::fs::read_link(target.join("bin/dangling")).unwrap(),
+            PathBuf::from("missing")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_preserves_mode_and_mtime() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("secrets");
+        std::fs::create_dir(&source).unwrap();
+        let secret = source.join("app.key");
+        std::fs::write(&secret, "key").unwrap();
+        let modified =
+            std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+        std::fs::File::options()
+            .write(true)
+            .open(&secret)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        std::fs::set_permissions(&secret, std::fs::Permissions::from_mode(0o400)).unwrap();
+        std::fs::set_permissions(&source, std::fs::Permissions::from_mode(0o700)).unwrap();
+        let target = temp.path().join("wt/secrets");
+
+        let options = CopyOptions {
+            preserve: vec![Preserve::Mode, Preserve::Mtime],
+            ..CopyOptions::default()
+        };
+        copy_file(&source, &target, &options).unwrap();
+
+        let metadata = std::fs::metadata(target.join("app.key")).unwrap();
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o400);
+        assert_eq!(metadata.modified().unwrap(), modified);
+        let dir_mode = std::fs::metadata(&target).unwrap().permissions().mode();
+        assert_eq!(dir_mode & 0o777, 0o700);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_preserves_mtime_of_read_only_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("tool.lock");
+        std::fs::write(&source, "lock").unwrap();
+        let modified =
+            std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+        std::fs::File::options()
+            .write(true)
+            .open(&source)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        // Copied along with the content, so the target is read-only before its time is set
+        std::fs::set_permissions(&source, std::fs::Permissions::from_mode(0o444)).unwrap();
+        let target = temp.path().join("wt/tool.lock");
+
+        let options = CopyOptions {
+            preserve: vec![Preserve::Mtime],
+            ..CopyOptions::default()
+        };
+        copy_file(&source, &target, &options).unwrap();
+
+        let metadata = std::fs::metadata(&target).unwrap();
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o444);
+        assert_eq!(metadata.modified().unwrap(), modified);
+    }
 }
@@ -120,6 +120,16 @@ impl Output {
         self.print_file_op("Copying", source, target, description);
     }
 
+    /// Print render operation.
+    pub fn render(
+        &self,
+        source: &std::path::Path,
+        target: &std::path::Path,
+        description: Option<&str>,
+    ) {
+        self.print_file_op("Rendering", source, target, description);
+    }
+
     /// Print skip message.
     pub fn skip(&self, path: &std::path::Path) {
         if !self.quiet {
@@ -135,6 +145,75 @@ impl Output {
         }
     }
 
+    /// Print skip message for an entry whose `when:` condition is not met.
+    pub fn skip_condition(&self, path: &std::path::Path, condition: &str) {
+        if !self.quiet {
+            if self.color.is_enabled() {
+                println!(
+                    "{}: {} {}",
+                    ColorScheme::skip("Skipped"),
+                    ColorScheme::path(&path.display().to_string()),
+                    ColorScheme::dimmed(&format!("(when: {condition})"))
+                );
+            } else {
+                println!("Skipped: {} (when: {})", path.display(), condition);
+            }
+        }
+    }
+
+    /// Print skip message for a global config entry whose source is missing in this repository.
+    pub fn skip_missing_source(&self, path: &std::path::Path, source: &std::path::Path) {
+        if !self.quiet {
+            if self.color.is_enabled() {
+                println!(
+                    "{}: {} {}",
+                    ColorScheme::skip("Skipped"),
+                    ColorScheme::path(&path.display().to_string()),
+                    ColorScheme::dimmed(&format!(
+                        "(global config source not found: {})",
+                        source.display()
+                    ))
+                );
+            } else {
+                println!(
+                    "Skipped: {} (global config source not found: {})",
+                    path.display(),
+                    source.display()
+                );
+            }
+        }
+    }
+
+    /// Print message for a target that already matches the config.
+    pub fn up_to_date(&self, path: &std::path::Path) {
+        if !self.quiet {
+            if self.color.is_enabled() {
+                println!(
+                    "{}: {} (already linked)",
+                    ColorScheme::skip("Unchanged"),
+                    ColorScheme::path(&path.display().to_string())
+                );
+            } else {
+                println!("Unchanged: {} (already linked)", path.display());
+            }
+        }
+    }
+
+    /// Print the worktree being set up by `kabu setup`.
+    pub fn setup_target(&self, path: &std::path::Path) {
+        if !self.quiet {
+            if self.color.is_enabled() {
+                println!(
+                    "{}: {}",
+                    ColorScheme::header("Setting up"),
+                    ColorScheme::path(&path.display().to_string())
+                );
+            } else {
+                println!("Setting up: {}", path.display());
+            }
+        }
+    }
+
     /// Print dry-run message.
     pub fn dry_run(&self, message: &str) {
         if !self.quiet {
@@ -161,6 +240,54 @@ impl Output {
         }
     }
 
+    /// Print worktree lock message.
+    pub fn lock(&self, path: &std::path::Path, reason: Option<&str>) {
+        if !self.quiet {
+            let reason = reason.map(|r| format!(" ({r})")).unwrap_or_default();
+            if self.color.is_enabled() {
+                println!(
+                    "{}: {}{}",
+                    ColorScheme::operation("Locked"),
+                    ColorScheme::path(&path.display().to_string()),
+                    reason
+                );
+            } else {
+                println!("Locked: {}{}", path.display(), reason);
+            }
+        }
+    }
+
+    /// Print worktree unlock message.
+    pub fn unlock(&self, path: &std::path::Path) {
+        if !self.quiet {
+            if self.color.is_enabled() {
+                println!(
+                    "{}: {}",
+                    ColorScheme::operation("Unlocked"),
+                    ColorScheme::path(&path.display().to_string())
+                );
+            } else {
+                println!("Unlocked: {}", path.display());
+            }
+        }
+    }
+
+    /// Print worktree move message.
+    pub fn moved(&self, old_path: &std::path::Path, new_path: &std::path::Path) {
+        if !self.quiet {
+            if self.color.is_enabled() {
+                println!(
+                    "{}: {} -> {}",
+                    ColorScheme::operation("Moved"),
+                    ColorScheme::path(&old_path.display().to_string()),
+                    ColorScheme::path(&new_path.display().to_string())
+                );
+            } else {
+                println!("Moved: {} -> {}", old_path.display(), new_path.display());
+            }
+        }
+    }
+
     /// Print safety warning.
     pub fn safety_warning(&self, path: &std::path::Path, message: &str) {
         if !self.quiet {
@@ -177,6 +304,40 @@ impl Output {
         }
     }
 
+    /// Print a problem found by `kabu doctor` with a remediation hint.
+    pub fn doctor_problem(&self, path: &std::path::Path, message: &str, hint: &str) {
+        if !self.quiet {
+            if self.color.is_enabled() {
+                println!(
+                    "{}: {} - {}",
+                    ColorScheme::warning("Problem"),
+                    ColorScheme::path(&path.display().to_string()),
+                    message
+                );
+                println!("  {}", ColorScheme::dimmed(&format!("hint: {hint}")));
+            } else {
+                println!("Problem: {} - {}", path.display(), message);
+                println!("  hint: {hint}");
+            }
+        }
+    }
+
+    /// Print a problem repaired by `kabu doctor --fix`.
+    pub fn doctor_fixed(&self, path: &std::path::Path, message: &str) {
+        if !self.quiet {
+            if self.color.is_enabled() {
+                println!(
+                    "{}: {} - {}",
+                    ColorScheme::operation("Fixed"),
+                    ColorScheme::path(&path.display().to_string()),
+                    message
+                );
+            } else {
+                println!("Fixed: {} - {}", path.display(), message);
+            }
+        }
+    }
+
     /// Print list item (suppressed in quiet mode).
     pub fn list(&self, line: &str) {
         if !self.quiet {
@@ -212,6 +373,92 @@ impl Output {
         }
     }
 
+    /// Print message for a hook whose `when:` condition is not met.
+    pub fn hook_skipped(
+        &self,
+        hook_type: &str,
+        index: usize,
+        total: usize,
+        command: &str,
+        description: Option<&str>,
+        condition: &str,
+    ) {
+        if !self.quiet {
+            let display_text = description.unwrap_or(command);
+            if self.color.is_enabled() {
+                println!(
+                    "{} {} hook {}: {} {}",
+                    ColorScheme::skip("Skipped"),
+                    ColorScheme::hook_type(hook_type),
+                    ColorScheme::dimmed(&format!("[{}/{}]", index, total)),
+                    ColorScheme::dimmed(display_text),
+                    ColorScheme::dimmed(&format!("(when: {condition})"))
+                );
+            } else {
+                println!(
+                    "Skipped {} hook [{}/{}]: {} (when: {})",
+                    hook_type, index, total, display_text, condition
+                );
+            }
+        }
+    }
+
+    /// Print output of a hook as the hook wrote it, flushed right away so prompts
+    /// without a trailing newline show up.
+    ///
+    /// Used while the output is also written to the hook log; shown even with --quiet.
+    pub fn hook_output(&self, bytes: &[u8], is_stderr: bool) {
+        use std::io::Write;
+
+        if is_stderr {
+            let mut stderr = std::io::stderr().lock();
+            let _ = stderr.write_all(bytes);
+            let _ = stderr.flush();
+        } else {
+            let mut stdout = std::io::stdout().lock();
+            let _ = stdout.write_all(bytes);
+            let _ = stdout.flush();
+        }
+    }
+
+    /// Print one line of output of a command in a `parallel:` hook group.
+    ///
+    /// Like the output of sequential hooks, this is shown even with --quiet.
+    pub fn hook_output_line(&self, prefix: &str, line: &str, is_stderr: bool) {
+        let prefix = if self.color.is_enabled() {
+            ColorScheme::hook_type(prefix)
+        } else {
+            prefix.to_string()
+        };
+        if is_stderr {
+            eprintln!("{} {}", prefix, line);
+        } else {
+            println!("{} {}", prefix, line);
+        }
+    }
+
+    /// Print the collected output of a finished command in a `parallel:` hook group.
+    ///
+    /// The block is written under one stdout lock, so blocks of commands that finish
+    /// at the same time don't mix. Shown even with --quiet, like sequential hook output.
+    pub fn hook_output_block(&self, label: &str, succeeded: bool, lines: &[String]) {
+        use std::io::Write;
+
+        let status = if succeeded { "succeeded" } else { "failed" };
+        let header = format!("Output of {label} ({status}):");
+        let header = if self.color.is_enabled() {
+            ColorScheme::hook_type(&header)
+        } else {
+            header
+        };
+        let mut stdout = std::io::stdout().lock();
+        let _ = writeln!(stdout, "{}", header);
+        for line in lines {
+            let _ = writeln!(stdout, "{}", line);
+        }
+        let _ = writeln!(stdout);
+    }
+
     /// Print blank line after hook execution (separator).
     pub fn hook_separator(&self) {
         if !self.quiet {
@@ -293,12 +540,13 @@ impl Output {
         }
     }
 
-    /// Print failure details for a hook.
+    /// Print failure details for a hook, pointing at the log of its output if there is one.
     pub fn results_failed_detail(
         &self,
         description: Option<&str>,
         command: &str,
         exit_code: Option<i32>,
+        log: Option<&std::path::Path>,
     ) {
         if !self.quiet {
             let display = description.unwrap_or(command);
@@ -309,11 +557,17 @@ impl Output {
                 if let Some(code) = exit_code {
                     println!("        {}: {}", ColorScheme::exit_code("Exit code"), code);
                 }
+                if let Some(log) = log {
+                    println!("        {}: {}", ColorScheme::dimmed("Log"), log.display());
+                }
             } else {
                 println!("        Command: {}", command);
                 if let Some(code) = exit_code {
                     println!("        Exit code: {}", code);
                 }
+                if let Some(log) = log {
+                    println!("        Log: {}", log.display());
+                }
             }
         }
     }
@@ -0,0 +1,208 @@
+//! Persistent per-repository state
+//!
+//! Stores what kabu needs to remember about worktrees outside of the repository itself,
+//! such as slot allocations, setup manifests, hook logs and jj workspace locks. State
+//! lives next to the trust store in the data directory
+//! (e.g. `~/.local/share/kabu/state/v1/{main-worktree-hash}/`), keyed by the main
+//! worktree path so that every worktree of a repository shares the same state.
+//!
+//! Files are written to a temporary file first and renamed into place so that an
+//! interrupted write never leaves a truncated state file behind. Read-modify-write
+//! cycles that parallel kabu runs may race on hold a [`StateLock`].
+
+mod hook_log;
+mod lock;
+mod manifest;
+mod slot;
+
+pub(crate) use hook_log::{
+    HookLog, hook_log_dir, hook_logs, latest_hook_log, log_hook_type, move_hook_logs,
+};
+pub(crate) use lock::{lock_worktree, read_locks, unlock_worktree};
+pub(crate) use manifest::{
+    Manifest, ManifestEntry, ManifestOp, hash_path, list_manifests, move_manifest, read_manifest,
+    record_manifest, remove_manifest,
+};
+pub(crate) use slot::{Allocation, allocate_slot, find_slot, move_slot, release_slot};
+
+use crate::error::{Error, Result};
+use crate::trust::main_worktree_dir_name;
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+const STATE_DIR_NAME: &str = "kabu/state";
+const STATE_VERSION: u32 = 1;
+
+/// How long to wait for another kabu process to release a state lock.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+/// Age after which a lock file is considered left behind by a crashed process.
+const STALE_LOCK_AGE: Duration = Duration::from_secs(60);
+
+/// Get state storage directory with versioning support.
+/// Uses XDG_DATA_HOME or falls back to ~/.local/share on Linux
+/// Path format: ~/.local/share/kabu/state/v1/
+fn state_dir() -> Result<PathBuf> {
+    if let Some(path) = std::env::var_os("KABU_STATE_DIR") {
+        return Ok(PathBuf::from(path));
+    }
+    let base = dirs::data_dir().ok_or(Error::StateStorageNotFound)?;
+    Ok(base
+        .join(STATE_DIR_NAME)
+        .join(format!("v{}", STATE_VERSION)))
+}
+
+/// Directory holding the state files of the repository whose main worktree is given.
+pub(crate) fn repo_state_dir(main_worktree_path: &Path) -> Result<PathBuf> {
+    let canonical_path = main_worktree_path.canonicalize()?;
+    Ok(state_dir()?.join(main_worktree_dir_name(&canonical_path)))
+}
+
+/// Normalize a worktree path for use as a state key.
+///
+/// The worktree may not exist yet (e.g. before `git worktree add`), so only its
+/// parent directory is canonicalized in that case.
+pub(crate) fn normalize_worktree_path(path: &Path) -> PathBuf {
+    if let Ok(canonical) = path.canonicalize() {
+        return canonical;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => parent
+            .canonicalize()
+            .map(|p| p.join(name))
+            .unwrap_or_else(|_| path.to_path_buf()),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Read a state file, returning the default value if it does not exist.
+fn read_state<T: DeserializeOwned + Default>(path: &Path) -> Result<T> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(T::default()),
+        Err(e) => return Err(e.into()),
+    };
+    serde_yaml::from_str(&content).map_err(|e| Error::StateFileCorrupted {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    })
+}
+
+/// Write a state file atomically, creating parent directories as needed.
+fn write_state<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let content = serde_yaml::to_string(value).map_err(|e| Error::StateFileCorrupted {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+    let tmp_path = path.with_extension("yaml.tmp");
+    fs::write(&tmp_path, content)?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+/// Exclusive lock on a state file, released when dropped.
+///
+/// The lock is a `.lock` file next to the state file, created with O_EXCL, so it works
+/// the same on every platform.
+struct StateLock {
+    path: PathBuf,
+}
+
+impl StateLock {
+    /// Wait until the lock of `state_path` is free and take it.
+    fn acquire(state_path: &Path) -> Result<StateLock> {
+        if let Some(parent) = state_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let path = state_path.with_extension("lock");
+        let deadline = Instant::now() + LOCK_TIMEOUT;
+        loop {
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(_) => return Ok(StateLock { path }),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    if is_stale_lock(&path) {
+                        // Another process may have removed it already
+                        let _ = fs::remove_file(&path);
+                        continue;
+                    }
+                    if Instant::now() >= deadline {
+                        return Err(Error::StateLocked { path });
+                    }
+                    thread::sleep(Duration::from_millis(20));
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+impl Drop for StateLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn is_stale_lock(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age > STALE_LOCK_AGE)
+}
+
+#[cfg(all(test, feature = "impure-test"))]
+#[allow(clippy::unwrap_used)]
+mod impure_tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_normalize_worktree_path_missing_leaf() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("not-yet-created");
+        let normalized = normalize_worktree_path(&path);
+        assert_eq!(
+            normalized,
+            temp.path().canonicalize().unwrap().join("not-yet-created")
+        );
+    }
+
+    #[test]
+    fn test_read_state_missing_file_is_default() {
+        let temp = TempDir::new().unwrap();
+        let value: Vec<u32> = read_state(&temp.path().join("missing.yaml")).unwrap();
+        assert!(value.is_empty());
+    }
+
+    #[test]
+    fn test_write_then_read_state() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("nested/state.yaml");
+        write_state(&path, &vec![1u32, 2, 3]).unwrap();
+        let value: Vec<u32> = read_state(&path).unwrap();
+        assert_eq!(value, vec![1, 2, 3]);
+        assert!(!path.with_extension("yaml.tmp").exists());
+    }
+
+    #[test]
+    fn test_read_state_corrupted() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("state.yaml");
+        fs::write(&path, "[[[").unwrap();
+        let result: Result<Vec<u32>> = read_state(&path);
+        assert!(matches!(result, Err(Error::StateFileCorrupted { .. })));
+    }
+}
@@ -0,0 +1,271 @@
+//! Hook output logs.
+//!
+//! Every run of a hook event (e.g. all `post_add` hooks of one `kabu add`) writes its
+//! output to a log file, so the reason a hook failed can still be looked up once the
+//! terminal scrollback is gone. Logs live in
+//! `{repo-state}/logs/{worktree-hash}/{timestamp}-{hook_type}.log`; only the most
+//! recent runs of each event are kept per worktree, so frequent `on_enter` runs never
+//! push out the log of the last `post_add`.
+
+use super::{normalize_worktree_path, repo_state_dir};
+use crate::error::Result;
+use crate::trust::main_worktree_dir_name;
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::Utc;
+
+const LOGS_DIR: &str = "logs";
+
+/// Number of hook logs kept per worktree and event; older ones are deleted when a new
+/// run of the event starts.
+const MAX_LOGS_PER_EVENT: usize = 20;
+
+/// Directory holding the hook logs of a worktree.
+///
+/// The worktree does not need to exist, so logs of removed worktrees can still be found.
+pub(crate) fn hook_log_dir(main_worktree_path: &Path, worktree_path: &Path) -> Result<PathBuf> {
+    let worktree_path = normalize_worktree_path(worktree_path);
+    Ok(repo_state_dir(main_worktree_path)?
+        .join(LOGS_DIR)
+        .join(main_worktree_dir_name(&worktree_path)))
+}
+
+/// List the hook logs in a log directory, oldest first.
+pub(crate) fn hook_logs(log_dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(log_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut logs = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "log") {
+            logs.push(path);
+        }
+    }
+    // File names start with a UTC timestamp, so they sort chronologically
+    logs.sort();
+    Ok(logs)
+}
+
+/// The most recent hook log in a log directory.
+pub(crate) fn latest_hook_log(log_dir: &Path) -> Result<Option<PathBuf>> {
+    Ok(hook_logs(log_dir)?.pop())
+}
+
+/// Move the hook logs of a worktree to its new path after it was moved.
+pub(crate) fn move_hook_logs(
+    main_worktree_path: &Path,
+    old_path: &Path,
+    new_path: &Path,
+) -> Result<()> {
+    let old_dir = hook_log_dir(main_worktree_path, old_path)?;
+    let logs = hook_logs(&old_dir)?;
+    if logs.is_empty() {
+        return Ok(());
+    }
+    // Logs of a removed worktree that used to live at the new path are kept too
+    let new_dir = hook_log_dir(main_worktree_path, new_path)?;
+    fs::create_dir_all(&new_dir)?;
+    for log in &logs {
+        if let Some(name) = log.file_name() {
+            fs::rename(log, new_dir.join(name))?;
+        }
+    }
+    let _ = fs::remove_dir(&old_dir);
+    let mut hook_types: Vec<String> = logs
+        .iter()
+        .filter_map(|log| log_hook_type(log).map(str::to_string))
+        .collect();
+    hook_types.sort();
+    hook_types.dedup();
+    for hook_type in hook_types {
+        prune_logs(&new_dir, &hook_type, MAX_LOGS_PER_EVENT)?;
+    }
+    Ok(())
+}
+
+/// Log file the output of one hook run is written to.
+///
+/// Writes are serialized so commands of a `parallel:` group can share a log. Write
+/// errors are ignored: a full disk must not fail the hooks themselves.
+pub(crate) struct HookLog {
+    path: PathBuf,
+    file: Mutex<LogFile>,
+}
+
+struct LogFile {
+    file: fs::File,
+    /// Whether the last write ended with a newline
+    at_line_start: bool,
+    /// Whether any hook output was written
+    has_output: bool,
+}
+
+impl HookLog {
+    /// Create a new log for a run of `hook_type` hooks, pruning the oldest logs.
+    pub(crate) fn create(log_dir: &Path, hook_type: &str) -> Result<Self> {
+        fs::create_dir_all(log_dir)?;
+        prune_logs(log_dir, hook_type, MAX_LOGS_PER_EVENT - 1)?;
+
+        let timestamp = Utc::now().format("%Y%m%dT%H%M%S%.3fZ");
+        let path = log_dir.join(format!("{timestamp}-{hook_type}.log"));
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        Ok(Self {
+            path,
+            file: Mutex::new(LogFile {
+                file,
+                at_line_start: true,
+                has_output: false,
+            }),
+        })
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append a line to the log, starting a new line if raw output left one open.
+    pub(crate) fn line(&self, text: &str) {
+        if let Ok(mut log) = self.file.lock() {
+            let newline = if log.at_line_start { "" } else { "\n" };
+            let _ = writeln!(log.file, "{newline}{text}");
+            log.at_line_start = true;
+        }
+    }
+
+    /// Append hook output as it was written.
+    pub(crate) fn write(&self, bytes: &[u8]) {
+        if let Ok(mut log) = self.file.lock()
+            && let Some(last) = bytes.last()
+        {
+            let _ = log.file.write_all(bytes);
+            log.at_line_start = *last == b'\n';
+            log.has_output = true;
+        }
+    }
+
+    /// Whether any hook output was written to the log.
+    pub(crate) fn has_output(&self) -> bool {
+        self.file.lock().is_ok_and(|log| log.has_output)
+    }
+
+    /// Close and delete the log.
+    pub(crate) fn discard(self) {
+        let HookLog { path, file } = self;
+        drop(file);
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// Hook event a log was written for, from its file name.
+pub(crate) fn log_hook_type(path: &Path) -> Option<&str> {
+    let stem = path.file_stem()?.to_str()?;
+    stem.rsplit_once('-').map(|(_, hook_type)| hook_type)
+}
+
+/// Delete the oldest logs of `hook_type` so that at most `keep` of them remain.
+fn prune_logs(log_dir: &Path, hook_type: &str, keep: usize) -> Result<()> {
+    let logs: Vec<PathBuf> = hook_logs(log_dir)?
+        .into_iter()
+        .filter(|log| log_hook_type(log) == Some(hook_type))
+        .collect();
+    let excess = logs.len().saturating_sub(keep);
+    for path in &logs[..excess] {
+        let _ = fs::remove_file(path);
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "impure-test"))]
+#[allow(clippy::unwrap_used)]
+mod impure_tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_latest_hook_log_is_last_created() {
+        let temp = TempDir::new().unwrap();
+        let log_dir = temp.path().join("logs");
+        assert_eq!(latest_hook_log(&log_dir).unwrap(), None);
+
+        let first = HookLog::create(&log_dir, "pre_add").unwrap();
+        first.line("one");
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        let second = HookLog::create(&log_dir, "post_add").unwrap();
+        second.line("two");
+
+        assert_eq!(
+            latest_hook_log(&log_dir).unwrap().as_deref(),
+            Some(second.path())
+        );
+        assert_eq!(fs::read_to_string(first.path()).unwrap(), "one\n");
+        assert!(second.path().to_string_lossy().ends_with("-post_add.log"));
+    }
+
+    #[test]
+    fn test_line_after_unterminated_output() {
+        let temp = TempDir::new().unwrap();
+        let log = HookLog::create(temp.path(), "post_add").unwrap();
+        log.line("$ ./setup.sh");
+        log.write(b"Continue? ");
+        log.write(b"yes\nDone");
+        log.line("# succeeded");
+
+        assert_eq!(
+            fs::read_to_string(log.path()).unwrap(),
+            "$ ./setup.sh\nContinue? yes\nDone\n# succeeded\n"
+        );
+    }
+
+    #[test]
+    fn test_create_prunes_oldest_logs() {
+        let temp = TempDir::new().unwrap();
+        for i in 0..MAX_LOGS_PER_EVENT {
+            fs::write(temp.path().join(format!("2020010{i:02}-post_add.log")), "").unwrap();
+        }
+        fs::write(temp.path().join("notes.txt"), "").unwrap();
+
+        let log = HookLog::create(temp.path(), "post_add").unwrap();
+
+        let logs = hook_logs(temp.path()).unwrap();
+        assert_eq!(logs.len(), MAX_LOGS_PER_EVENT);
+        assert_eq!(logs.last().map(PathBuf::as_path), Some(log.path()));
+        assert!(!temp.path().join("2020010000-post_add.log").exists());
+        assert!(temp.path().join("notes.txt").exists());
+    }
+
+    #[test]
+    fn test_create_prunes_per_event() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("20200101-post_add.log"), "").unwrap();
+        for i in 0..MAX_LOGS_PER_EVENT {
+            fs::write(temp.path().join(format!("2020020{i:02}-on_enter.log")), "").unwrap();
+        }
+
+        HookLog::create(temp.path(), "on_enter").unwrap();
+
+        assert!(temp.path().join("20200101-post_add.log").exists());
+        assert!(!temp.path().join("2020020000-on_enter.log").exists());
+    }
+
+    #[test]
+    fn test_discard_removes_log() {
+        let temp = TempDir::new().unwrap();
+        let log = HookLog::create(temp.path(), "on_enter").unwrap();
+        log.line("# on_enter hooks");
+        assert!(!log.has_output());
+
+        let path = log.path().to_path_buf();
+        log.discard();
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,117 @@
+//! Workspace locks for jj.
+//!
+//! git records locks in the worktree's administrative files; jj has no lock concept,
+//! so `kabu lock` records jj workspace locks here instead. A locked workspace is shown
+//! as such by `kabu list` and is not removed, moved or pruned by kabu.
+
+use super::{STATE_VERSION, normalize_worktree_path, read_state, repo_state_dir, write_state};
+use crate::error::Result;
+
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+const LOCKS_FILE: &str = "locks.yaml";
+
+/// Locks of one repository.
+#[derive(Debug, Serialize, Deserialize)]
+struct LockStore {
+    version: u32,
+    #[serde(default)]
+    locks: Vec<LockEntry>,
+}
+
+impl Default for LockStore {
+    fn default() -> Self {
+        LockStore {
+            version: STATE_VERSION,
+            locks: Vec::new(),
+        }
+    }
+}
+
+/// A locked workspace.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct LockEntry {
+    pub worktree_path: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub locked_at: String,
+}
+
+impl LockStore {
+    /// Lock a workspace, replacing the reason if it was already locked.
+    fn lock(&mut self, worktree_path: &Path, reason: Option<&str>) {
+        self.unlock(worktree_path);
+        self.locks.push(LockEntry {
+            worktree_path: worktree_path.to_path_buf(),
+            reason: reason.map(str::to_string),
+            locked_at: Utc::now().to_rfc3339(),
+        });
+    }
+
+    /// Unlock a workspace. Returns false if it was not locked.
+    fn unlock(&mut self, worktree_path: &Path) -> bool {
+        let before = self.locks.len();
+        self.locks
+            .retain(|entry| entry.worktree_path != worktree_path);
+        self.locks.len() != before
+    }
+}
+
+/// Record a lock for a workspace.
+pub(crate) fn lock_worktree(
+    main_worktree_path: &Path,
+    worktree_path: &Path,
+    reason: Option<&str>,
+) -> Result<()> {
+    let store_path = repo_state_dir(main_worktree_path)?.join(LOCKS_FILE);
+    let mut store: LockStore = read_state(&store_path)?;
+    store.lock(&normalize_worktree_path(worktree_path), reason);
+    write_state(&store_path, &store)
+}
+
+/// Drop the lock of a workspace. Missing locks are not an error.
+pub(crate) fn unlock_worktree(main_worktree_path: &Path, worktree_path: &Path) -> Result<()> {
+    let store_path = repo_state_dir(main_worktree_path)?.join(LOCKS_FILE);
+    let mut store: LockStore = read_state(&store_path)?;
+    if store.unlock(&normalize_worktree_path(worktree_path)) {
+        write_state(&store_path, &store)?;
+    }
+    Ok(())
+}
+
+/// Read the locks recorded for a repository.
+pub(crate) fn read_locks(main_worktree_path: &Path) -> Result<Vec<LockEntry>> {
+    let store_path = repo_state_dir(main_worktree_path)?.join(LOCKS_FILE);
+    let store: LockStore = read_state(&store_path)?;
+    Ok(store.locks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_replaces_reason() {
+        let mut store = LockStore::default();
+        store.lock(Path::new("/ws/a"), None);
+        store.lock(Path::new("/ws/a"), Some("on a USB drive"));
+
+        assert_eq!(store.locks.len(), 1);
+        assert_eq!(store.locks[0].reason.as_deref(), Some("on a USB drive"));
+    }
+
+    #[test]
+    fn test_unlock() {
+        let mut store = LockStore::default();
+        store.lock(Path::new("/ws/a"), None);
+        store.lock(Path::new("/ws/b"), None);
+
+        assert!(store.unlock(Path::new("/ws/a")));
+        assert!(!store.unlock(Path::new("/ws/a")));
+        assert_eq!(store.locks.len(), 1);
+        assert_eq!(store.locks[0].worktree_path, PathBuf::from("/ws/b"));
+    }
+}
@@ -0,0 +1,355 @@
+//! Per-worktree setup manifest.
+//!
+//! Records every directory, symlink and copy that `run_setup` produced in a worktree,
+//! together with the conflict resolution taken and the hash of the configuration that
+//! produced it. This tells kabu-made files apart from user-made ones: an entry is
+//! "pristine" while the file on disk still matches what kabu wrote.
+
+use super::{STATE_VERSION, normalize_worktree_path, read_state, repo_state_dir, write_state};
+use crate::config::OnConflict;
+use crate::error::Result;
+use crate::trust::main_worktree_dir_name;
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+const MANIFESTS_DIR: &str = "manifests";
+
+/// Setup operation recorded in a manifest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ManifestOp {
+    Mkdir,
+    Link,
+    Copy,
+    Render,
+}
+
+/// One setup operation performed in a worktree.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ManifestEntry {
+    pub op: ManifestOp,
+    /// Absolute source path (link/copy/render only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<PathBuf>,
+    /// Target path relative to the worktree
+    pub target: PathBuf,
+    /// Conflict resolution taken when the target already existed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_conflict: Option<OnConflict>,
+    /// Backup created for `on_conflict: backup`, relative to the worktree
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup: Option<PathBuf>,
+    /// SHA256 of the written contents (copy/render only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+}
+
+impl ManifestEntry {
+    /// Whether kabu produced this target (as opposed to skipping it on conflict).
+    pub(crate) fn is_owned(&self) -> bool {
+        self.on_conflict != Some(OnConflict::Skip)
+    }
+
+    /// Whether the target still matches what kabu wrote.
+    ///
+    /// Directories from `mkdir` are never pristine since users put their own files in them.
+    pub(crate) fn is_pristine(&self, worktree_path: &Path) -> bool {
+        if !self.is_owned() {
+            return false;
+        }
+        let target = worktree_path.join(&self.target);
+        match self.op {
+            ManifestOp::Mkdir => false,
+            ManifestOp::Link => match (&self.source, fs::read_link(&target)) {
+                (Some(source), Ok(link)) => &link == source,
+                _ => false,
+            },
+            ManifestOp::Copy | ManifestOp::Render => match (&self.content_hash, hash_path(&target))
+            {
+                (Some(expected), Ok(actual)) => expected == &actual,
+                _ => false,
+            },
+        }
+    }
+}
+
+/// Everything kabu set up in one worktree.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Manifest {
+    pub version: u32,
+    pub worktree_path: PathBuf,
+    /// Trust hash of the configuration used by the last setup run
+    pub config_hash: String,
+    /// Profile applied by the last setup run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    pub updated_at: String,
+    #[serde(default)]
+    pub entries: Vec<ManifestEntry>,
+}
+
+impl Manifest {
+    /// Add entries from a setup run, replacing earlier entries with the same target.
+    fn merge(&mut self, entries: Vec<ManifestEntry>) {
+        for entry in entries {
+            self.entries
+                .retain(|existing| existing.target != entry.target);
+            self.entries.push(entry);
+        }
+    }
+
+    /// Look up the entry for a target path relative to the worktree.
+    pub(crate) fn entry(&self, target: &Path) -> Option<&ManifestEntry> {
+        self.entries.iter().find(|entry| entry.target == target)
+    }
+}
+
+fn manifest_path(main_worktree_path: &Path, worktree_path: &Path) -> Result<PathBuf> {
+    let worktree_path = normalize_worktree_path(worktree_path);
+    Ok(repo_state_dir(main_worktree_path)?
+        .join(MANIFESTS_DIR)
+        .join(format!("{}.yaml", main_worktree_dir_name(&worktree_path))))
+}
+
+/// Record the entries of a setup run in the worktree's manifest.
+///
+/// Entries for targets that were not touched in this run are kept, so re-running
+/// `kabu setup` never forgets what an earlier run created. No manifest is created
+/// for a worktree where nothing was set up and no profile was applied.
+pub(crate) fn record_manifest(
+    main_worktree_path: &Path,
+    worktree_path: &Path,
+    config_hash: &str,
+    profile: Option<&str>,
+    entries: Vec<ManifestEntry>,
+) -> Result<()> {
+    let path = manifest_path(main_worktree_path, worktree_path)?;
+    let existing = read_manifest(main_worktree_path, worktree_path)?;
+    if existing.is_none() && entries.is_empty() && profile.is_none() {
+        return Ok(());
+    }
+    let mut manifest = existing.unwrap_or(Manifest {
+        version: STATE_VERSION,
+        worktree_path: normalize_worktree_path(worktree_path),
+        config_hash: String::new(),
+        profile: None,
+        updated_at: String::new(),
+        entries: Vec::new(),
+    });
+    manifest.config_hash = config_hash.to_string();
+    manifest.profile = profile.map(str::to_string);
+    manifest.updated_at = Utc::now().to_rfc3339();
+    manifest.merge(entries);
+    write_state(&path, &manifest)
+}
+
+/// Read the manifest of a worktree, if kabu has set it up.
+pub(crate) fn read_manifest(
+    main_worktree_path: &Path,
+    worktree_path: &Path,
+) -> Result<Option<Manifest>> {
+    let path = manifest_path(main_worktree_path, worktree_path)?;
+    read_state(&path)
+}
+
+/// Read the manifests of all worktrees of a repository.
+pub(crate) fn list_manifests(main_worktree_path: &Path) -> Result<Vec<Manifest>> {
+    let dir = repo_state_dir(main_worktree_path)?.join(MANIFESTS_DIR);
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut manifests = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "yaml")
+            && let Some(manifest) = read_state::<Option<Manifest>>(&path)?
+        {
+            manifests.push(manifest);
+        }
+    }
+    Ok(manifests)
+}
+
+/// Re-key the manifest of a worktree to its new path after it was moved.
+pub(crate) fn move_manifest(
+    main_worktree_path: &Path,
+    old_path: &Path,
+    new_path: &Path,
+) -> Result<()> {
+    let Some(mut manifest) = read_manifest(main_worktree_path, old_path)? else {
+        return Ok(());
+    };
+    manifest.worktree_path = normalize_worktree_path(new_path);
+    write_state(&manifest_path(main_worktree_path, new_path)?, &manifest)?;
+    remove_manifest(main_worktree_path, old_path)
+}
+
+/// Delete the manifest of a worktree. Missing manifests are not an error.
+pub(crate) fn remove_manifest(main_worktree_path: &Path, worktree_path: &Path) -> Result<()> {
+    let path = manifest_path(main_worktree_path, worktree_path)?;
+    match fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Compute the SHA256 of a file, or of a directory tree (relative paths and contents).
+pub(crate) fn hash_path(path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    if path.is_dir() {
+        for entry in walkdir::WalkDir::new(path).sort_by_file_name() {
+            let entry = entry.map_err(io::Error::other)?;
+            let relative = entry.path().strip_prefix(path).unwrap_or(entry.path());
+            hasher.update(relative.to_string_lossy().as_bytes());
+            hasher.update(b"\0");
+            if entry.file_type().is_file() {
+                hash_file_into(entry.path(), &mut hasher)?;
+            }
+        }
+    } else {
+        hash_file_into(path, &mut hasher)?;
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn hash_file_into(path: &Path, hasher: &mut Sha256) -> Result<()> {
+    let mut file = fs::File::open(path)?;
+    let mut buffer = [0u8; 8192];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(op: ManifestOp, target: &str) -> ManifestEntry {
+        ManifestEntry {
+            op,
+            source: None,
+            target: PathBuf::from(target),
+            on_conflict: None,
+            backup: None,
+            content_hash: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_replaces_same_target() {
+        let mut manifest = Manifest {
+            version: STATE_VERSION,
+            worktree_path: PathBuf::from("/wt"),
+            config_hash: String::new(),
+            profile: None,
+            updated_at: String::new(),
+            entries: vec![
+                entry(ManifestOp::Copy, ".env"),
+                entry(ManifestOp::Mkdir, "tmp"),
+            ],
+        };
+        manifest.merge(vec![entry(ManifestOp::Link, ".env")]);
+
+        assert_eq!(manifest.entries.len(), 2);
+        assert_eq!(
+            manifest.entry(Path::new(".env")).map(|e| e.op),
+            Some(ManifestOp::Link)
+        );
+        assert!(manifest.entry(Path::new("tmp")).is_some());
+    }
+
+    #[test]
+    fn test_skipped_entry_is_not_owned() {
+        let mut skipped = entry(ManifestOp::Copy, ".env");
+        skipped.on_conflict = Some(OnConflict::Skip);
+        assert!(!skipped.is_owned());
+        assert!(!skipped.is_pristine(Path::new("/nonexistent")));
+
+        let mut backed_up = entry(ManifestOp::Copy, ".env");
+        backed_up.on_conflict = Some(OnConflict::Backup);
+        assert!(backed_up.is_owned());
+    }
+
+    #[test]
+    fn test_entry_serialization_omits_empty_fields() {
+        let yaml = serde_yaml::to_string(&entry(ManifestOp::Mkdir, "tmp")).unwrap();
+        assert_eq!(yaml, "op: mkdir\ntarget: tmp\n");
+    }
+}
+
+#[cfg(all(test, feature = "impure-test"))]
+#[allow(clippy::unwrap_used)]
+mod impure_tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_copy_is_pristine_until_modified() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join(".env"), "A=1\n").unwrap();
+
+        let entry = ManifestEntry {
+            op: ManifestOp::Copy,
+            source: Some(PathBuf::from("/repo/.env.example")),
+            target: PathBuf::from(".env"),
+            on_conflict: None,
+            backup: None,
+            content_hash: Some(hash_path(&temp.path().join(".env")).unwrap()),
+        };
+        assert!(entry.is_pristine(temp.path()));
+
+        fs::write(temp.path().join(".env"), "A=2\n").unwrap();
+        assert!(!entry.is_pristine(temp.path()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_link_is_pristine_while_pointing_at_source() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("source");
+        fs::write(&source, "").unwrap();
+        std::os::unix::fs::symlink(&source, temp.path().join("target")).unwrap();
+
+        let entry = ManifestEntry {
+            op: ManifestOp::Link,
+            source: Some(source.clone()),
+            target: PathBuf::from("target"),
+            on_conflict: None,
+            backup: None,
+            content_hash: None,
+        };
+        assert!(entry.is_pristine(temp.path()));
+
+        fs::remove_file(temp.path().join("target")).unwrap();
+        fs::write(temp.path().join("target"), "").unwrap();
+        assert!(!entry.is_pristine(temp.path()));
+    }
+
+    #[test]
+    fn test_hash_directory_changes_with_contents() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join("dir");
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("sub/file"), "one").unwrap();
+
+        let before = hash_path(&dir).unwrap();
+        assert_eq!(before, hash_path(&dir).unwrap());
+
+        fs::write(dir.join("sub/file"), "two").unwrap();
+        assert_ne!(before, hash_path(&dir).unwrap());
+    }
+}
@@ -0,0 +1,303 @@
+//! Per-worktree slot allocation.
+//!
+//! Each worktree created while `ports:` is configured gets a small integer slot that is
+//! unique within its repository and stays the same until the worktree is removed. Ports
+//! are derived from the slot, so two worktrees never share a port. Slot 0 is reserved for
+//! the main worktree, which uses the base ports as-is.
+
+use super::{
+    STATE_VERSION, StateLock, normalize_worktree_path, read_state, repo_state_dir, write_state,
+};
+use crate::config::Port;
+use crate::error::{Error, Result};
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+const SLOTS_FILE: &str = "slots.yaml";
+
+/// Slot allocations of one repository.
+#[derive(Debug, Serialize, Deserialize)]
+struct SlotStore {
+    version: u32,
+    #[serde(default)]
+    slots: Vec<SlotEntry>,
+}
+
+impl Default for SlotStore {
+    fn default() -> Self {
+        SlotStore {
+            version: STATE_VERSION,
+            slots: Vec::new(),
+        }
+    }
+}
+
+/// A slot held by a worktree.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SlotEntry {
+    slot: u32,
+    worktree_path: PathBuf,
+    allocated_at: String,
+}
+
+impl SlotStore {
+    fn find(&self, worktree_path: &Path) -> Option<u32> {
+        self.slots
+            .iter()
+            .find(|entry| entry.worktree_path == worktree_path)
+            .map(|entry| entry.slot)
+    }
+
+    /// Return the worktree's slot, or the smallest free slot (starting at 1) if it has none.
+    ///
+    /// The second value is true when a new slot was handed out.
+    fn allocate(&mut self, worktree_path: &Path) -> (u32, bool) {
+        if let Some(slot) = self.find(worktree_path) {
+            return (slot, false);
+        }
+        let mut slot = 1;
+        while self.slots.iter().any(|entry| entry.slot == slot) {
+            slot += 1;
+        }
+        self.slots.push(SlotEntry {
+            slot,
+            worktree_path: worktree_path.to_path_buf(),
+            allocated_at: Utc::now().to_rfc3339(),
+        });
+        self.slots.sort_by_key(|entry| entry.slot);
+        (slot, true)
+    }
+
+    /// Hand the slot of a worktree over to its new path. Returns false if it had none.
+    fn rename(&mut self, old_path: &Path, new_path: &Path) -> bool {
+        match self
+            .slots
+            .iter_mut()
+            .find(|entry| entry.worktree_path == old_path)
+        {
+            Some(entry) => {
+                entry.worktree_path = new_path.to_path_buf();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn release(&mut self, worktree_path: &Path) -> Option<u32> {
+        let index = self
+            .slots
+            .iter()
+            .position(|entry| entry.worktree_path == worktree_path)?;
+        Some(self.slots.remove(index).slot)
+    }
+}
+
+/// Slot allocated to a worktree and the ports derived from it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Allocation {
+    pub slot: u32,
+    /// First and last port of each configured port name
+    pub ports: BTreeMap<String, (u16, u16)>,
+}
+
+impl Allocation {
+    /// Compute the ports for a slot, failing if any of them exceeds 65535.
+    fn new(slot: u32, ports: &BTreeMap<String, Port>) -> Result<Self> {
+        let mut resolved = BTreeMap::new();
+        for (name, port) in ports {
+            let first = u64::from(port.base) + u64::from(slot) * u64::from(port.size);
+            let last = first + u64::from(port.size) - 1;
+            let (Ok(first), Ok(last)) = (u16::try_from(first), u16::try_from(last)) else {
+                return Err(Error::PortOutOfRange {
+                    name: name.clone(),
+                    slot,
+                });
+            };
+            resolved.insert(name.clone(), (first, last));
+        }
+        Ok(Allocation {
+            slot,
+            ports: resolved,
+        })
+    }
+}
+
+/// Allocate (or look up) the slot of a worktree and persist it.
+///
+/// Calling this again for the same worktree returns the same slot.
+pub(crate) fn allocate_slot(
+    main_worktree_path: &Path,
+    worktree_path: &Path,
+    ports: &BTreeMap<String, Port>,
+) -> Result<Allocation> {
+    let store_path = repo_state_dir(main_worktree_path)?.join(SLOTS_FILE);
+    let slot = allocate_in(&store_path, &normalize_worktree_path(worktree_path))?;
+    Allocation::new(slot, ports)
+}
+
+/// Allocate the slot of a worktree in the store at `store_path`.
+///
+/// The store is locked from reading to writing, so parallel `kabu add` runs never hand
+/// out the same slot.
+fn allocate_in(store_path: &Path, worktree_path: &Path) -> Result<u32> {
+    let _lock = StateLock::acquire(store_path)?;
+    let mut store: SlotStore = read_state(store_path)?;
+    let (slot, is_new) = store.allocate(worktree_path);
+    if is_new {
+        write_state(store_path, &store)?;
+    }
+    Ok(slot)
+}
+
+/// Look up the slot of a worktree without allocating one.
+pub(crate) fn find_slot(
+    main_worktree_path: &Path,
+    worktree_path: &Path,
+    ports: &BTreeMap<String, Port>,
+) -> Result<Option<Allocation>> {
+    let store_path = repo_state_dir(main_worktree_path)?.join(SLOTS_FILE);
+    let store: SlotStore = read_state(&store_path)?;
+    store
+        .find(&normalize_worktree_path(worktree_path))
+        .map(|slot| Allocation::new(slot, ports))
+        .transpose()
+}
+
+/// Keep the slot of a worktree after it was moved, so its ports stay the same.
+pub(crate) fn move_slot(main_worktree_path: &Path, old_path: &Path, new_path: &Path) -> Result<()> {
+    let store_path = repo_state_dir(main_worktree_path)?.join(SLOTS_FILE);
+    let _lock = StateLock::acquire(&store_path)?;
+    let mut store: SlotStore = read_state(&store_path)?;
+    if store.rename(
+        &normalize_worktree_path(old_path),
+        &normalize_worktree_path(new_path),
+    ) {
+        write_state(&store_path, &store)?;
+    }
+    Ok(())
+}
+
+/// Release the slot of a worktree. Returns the released slot, if any.
+pub(crate) fn release_slot(main_worktree_path: &Path, worktree_path: &Path) -> Result<Option<u32>> {
+    let store_path = repo_state_dir(main_worktree_path)?.join(SLOTS_FILE);
+    let _lock = StateLock::acquire(&store_path)?;
+    let mut store: SlotStore = read_state(&store_path)?;
+    let released = store.release(&normalize_worktree_path(worktree_path));
+    if released.is_some() {
+        write_state(&store_path, &store)?;
+    }
+    Ok(released)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocate_smallest_free_slot() {
+        let mut store = SlotStore::default();
+        assert_eq!(store.allocate(Path::new("/wt/a")), (1, true));
+        assert_eq!(store.allocate(Path::new("/wt/b")), (2, true));
+        assert_eq!(store.allocate(Path::new("/wt/c")), (3, true));
+
+        assert_eq!(store.release(Path::new("/wt/b")), Some(2));
+        assert_eq!(store.allocate(Path::new("/wt/d")), (2, true));
+    }
+
+    #[test]
+    fn test_allocate_is_stable() {
+        let mut store = SlotStore::default();
+        store.allocate(Path::new("/wt/a"));
+        store.allocate(Path::new("/wt/b"));
+        assert_eq!(store.allocate(Path::new("/wt/a")), (1, false));
+        assert_eq!(store.find(Path::new("/wt/b")), Some(2));
+    }
+
+    #[test]
+    fn test_rename_keeps_slot() {
+        let mut store = SlotStore::default();
+        store.allocate(Path::new("/wt/a"));
+        store.allocate(Path::new("/wt/b"));
+
+        assert!(store.rename(Path::new("/wt/a"), Path::new("/wt/c")));
+        assert_eq!(store.find(Path::new("/wt/a")), None);
+        assert_eq!(store.find(Path::new("/wt/c")), Some(1));
+        assert!(!store.rename(Path::new("/wt/a"), Path::new("/wt/d")));
+    }
+
+    #[test]
+    fn test_release_unknown_worktree() {
+        let mut store = SlotStore::default();
+        assert_eq!(store.release(Path::new("/wt/a")), None);
+    }
+
+    #[test]
+    fn test_allocation_ports() {
+        let ports = BTreeMap::from([
+            (
+                "web".to_string(),
+                Port {
+                    base: 3000,
+                    size: 1,
+                },
+            ),
+            (
+                "debug".to_string(),
+                Port {
+                    base: 9200,
+                    size: 10,
+                },
+            ),
+        ]);
+        let allocation = Allocation::new(2, &ports).unwrap();
+        assert_eq!(allocation.slot, 2);
+        assert_eq!(allocation.ports.get("web"), Some(&(3002, 3002)));
+        assert_eq!(allocation.ports.get("debug"), Some(&(9220, 9229)));
+    }
+
+    #[test]
+    fn test_allocation_port_out_of_range() {
+        let ports = BTreeMap::from([(
+            "web".to_string(),
+            Port {
+                base: 65530,
+                size: 5,
+            },
+        )]);
+        assert!(Allocation::new(0, &ports).is_ok());
+        assert!(matches!(
+            Allocation::new(1, &ports),
+            Err(Error::PortOutOfRange { slot: 1, .. })
+        ));
+    }
+}
+
+#[cfg(all(test, feature = "impure-test"))]
+#[allow(clippy::unwrap_used)]
+mod impure_tests {
+    use super::*;
+
+    #[test]
+    fn test_parallel_allocations_get_distinct_slots() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let store_path = temp.path().join(SLOTS_FILE);
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let store_path = store_path.clone();
+                std::thread::spawn(move || {
+                    allocate_in(&store_path, &PathBuf::from(format!("/wt/{i}"))).unwrap()
+                })
+            })
+            .collect();
+        let mut slots: Vec<u32> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        slots.sort();
+
+        assert_eq!(slots, (1..=8).collect::<Vec<_>>());
+        assert!(!store_path.with_extension("lock").exists());
+    }
+}
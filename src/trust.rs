@@ -29,6 +29,14 @@
 //! `is_trusted()` returns true immediately without checking disk. Rationale: no hooks means
 //! nothing to trust; the user cannot create a `.kabu/config.yaml` without hooks and then claim
 //! hooks exist.
+//!
+//! **Sources Outside the Repository**: link/copy entries with a `source_root:` of `home`
+//! or an absolute path can pull files from anywhere into a worktree, so a config with such
+//! entries needs trust just like a config with hooks.
+//!
+//! **Global Config**: Hooks in the global config are trusted separately from any
+//! repository. The global config file path takes the place of the main worktree path, so
+//! the global config has its own trust directory and is re-trusted when it changes.
 
 use crate::config::{Config, ConfigSnapshot};
 use crate::error::{Error, Result};
@@ -103,12 +111,21 @@ fn set_test_trust_dir(path: PathBuf) {
 ///
 /// Hash includes (field-by-field with JSON serialization):
 /// - Canonicalized main_worktree_path (prevents different path representations)
+/// - extends (files the config was composed from; their settings, hooks and
+///   operations are part of the fields below)
 /// - defaults (OnConflict option)
 /// - worktree (path_template, branch_template)
-/// - hooks (pre_add, post_add, pre_remove, post_remove)
-/// - mkdir operations (path, description)
-/// - link operations (source, target, on_conflict, description, ignore_tracked)
-/// - copy operations (source, target, on_conflict, description)
+/// - hooks (pre_add, post_create, post_add, pre_remove, post_remove, pre_move,
+///   post_move, on_enter)
+/// - mkdir operations (path, description, when)
+/// - link operations (source, source_root, target, on_conflict, description,
+///   ignore_tracked, when)
+/// - copy operations (source, source_root, target, on_conflict, description, render,
+///   mode, ignore_tracked, include, exclude, preserve, max_size, when)
+/// - include_file (path, mode)
+///
+/// Hook entries and mkdir/link/copy entries include their `when:` conditions, so
+/// changing when something runs requires re-trust just like changing what runs.
 ///
 /// **Stability**: Uses explicit JSON serialization to ensure the hash remains stable
 /// across Rust compiler versions. JSON's stable text representation ensures consistency.
@@ -160,7 +177,7 @@ pub(crate) fn compute_hash(main_worktree_path: &Path, config: &Config) -> Result
 /// ensuring compatibility across Unix and Windows filesystems.
 /// Performance benefit: Prevents a single directory from containing thousands of files
 /// when many repositories are trusted. Instead, each main worktree gets its own directory.
-fn main_worktree_dir_name(path: &Path) -> String {
+pub(crate) fn main_worktree_dir_name(path: &Path) -> String {
     use sha2::{Digest, Sha256};
 
     let path_str = path.to_string_lossy();
@@ -175,7 +192,7 @@ fn main_worktree_dir_name(path: &Path) -> String {
 /// Check if configuration is trusted for the given main worktree.
 ///
 /// Returns true if:
-/// 1. No hooks are defined (empty configuration)
+/// 1. No hooks and no sources outside the repository are defined
 /// 2. A trust file exists with a matching hash AND the stored main_worktree_path matches
 ///
 /// Returns false if:
@@ -190,8 +207,8 @@ fn main_worktree_dir_name(path: &Path) -> String {
 /// **Path Verification**: Prevents symlink attacks where an attacker could replace the
 /// worktree with a symlink to a different location containing old trusted hooks.
 pub(crate) fn is_trusted(main_worktree_path: &Path, config: &Config) -> Result<bool> {
-    if !config.hooks.has_hooks() {
-        return Ok(true); // No hooks = implicitly trusted
+    if !config.requires_trust() {
+        return Ok(true); // Nothing to trust = implicitly trusted
     }
 
     // Canonicalize path - fail if it doesn't exist, ensuring consistent behavior
@@ -299,7 +316,7 @@ pub(crate) fn read_trust_entry(main_worktree_path: &Path) -> Result<Option<Trust
 /// 5. OLD BEHAVIOR: hash=ABC123 still exists, config trusted without re-review
 /// 6. NEW BEHAVIOR: hash=ABC123 was deleted in step 3, reversion fails
 pub(crate) fn trust(main_worktree_path: &Path, config: &Config) -> Result<()> {
-    if !config.hooks.has_hooks() {
+    if !config.requires_trust() {
         return Ok(());
     }
 
@@ -354,7 +371,7 @@ pub(crate) fn trust(main_worktree_path: &Path, config: &Config) -> Result<()> {
 /// Deletes the trust file matching the current configuration hash. Returns true if a trust file
 /// existed and was deleted, false if no trust file was found for this configuration.
 pub(crate) fn untrust(main_worktree_path: &Path, config: &Config) -> Result<bool> {
-    if !config.hooks.has_hooks() {
+    if !config.requires_trust() {
         return Ok(false);
     }
 
@@ -378,6 +395,20 @@ pub(crate) fn untrust(main_worktree_path: &Path, config: &Config) -> Result<bool
     }
 }
 
+/// Remove every trust file stored for a main worktree path.
+///
+/// Used to clean up trust entries of repositories that were moved or deleted, where
+/// `untrust` cannot be used because the path no longer canonicalizes. Returns true if
+/// anything was removed.
+pub(crate) fn forget_trusted(main_worktree_path: &Path) -> Result<bool> {
+    let dir = trust_dir()?.join(main_worktree_dir_name(main_worktree_path));
+    match fs::remove_dir_all(&dir) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
 /// List all trusted repository hooks.
 ///
 /// Traverses the nested directory structure at `~/.local/share/kabu/trusted/`
@@ -432,7 +463,7 @@ pub(crate) fn list_trusted() -> Result<Vec<TrustEntry>> {
 #[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
-    use crate::config::{AutoCd, Config, Hooks, Mkdir, Ui, Worktree};
+    use crate::config::{AutoCd, Config, Hooks, Mkdir, Prune, Ui, Worktree};
     use std::sync::OnceLock;
     use tempfile::TempDir;
 
@@ -450,11 +481,18 @@ mod tests {
                 path_template: None,
                 branch_template: None,
             },
+            prune: Prune::default(),
+            ports: Default::default(),
             ui: Ui::default(),
             hooks: Hooks::default(),
             mkdir: Vec::new(),
             link: Vec::new(),
             copy: Vec::new(),
+            include_file: None,
+            extends: Vec::new(),
+            profiles: Default::default(),
+            default_profile: None,
+            profile: None,
         }
     }
 
@@ -477,6 +515,7 @@ mod tests {
             pre_add: vec![HookEntry {
                 command: "echo 'test1'".to_string(),
                 description: None,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -486,6 +525,7 @@ mod tests {
             pre_add: vec![HookEntry {
                 command: "echo 'test2'".to_string(),
                 description: None,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -505,12 +545,14 @@ mod tests {
         config1.mkdir = vec![Mkdir {
             path: PathBuf::from("dir1"),
             description: None,
+            when: None,
         }];
 
         let mut config2 = create_test_config();
         config2.mkdir = vec![Mkdir {
             path: PathBuf::from("dir2"),
             description: None,
+            when: None,
         }];
 
         let hash1 = compute_hash(temp_dir.path(), &config1).unwrap();
@@ -552,6 +594,7 @@ mod tests {
             pre_add: vec![HookEntry {
                 command: "echo 'test'".to_string(),
                 description: None,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -571,6 +614,34 @@ mod tests {
         assert!(!untrust(temp_dir.path(), &config).unwrap());
     }
 
+    #[test]
+    fn test_forget_trusted_after_repo_deleted() {
+        init_test_data_dir();
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = create_test_config();
+        config.hooks = Hooks {
+            pre_add: vec![HookEntry {
+                command: "echo 'test'".to_string(),
+                description: None,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        trust(temp_dir.path(), &config).unwrap();
+
+        let canonical_path = temp_dir.path().canonicalize().unwrap();
+        drop(temp_dir);
+
+        assert!(forget_trusted(&canonical_path).unwrap());
+        assert!(
+            !list_trusted()
+                .unwrap()
+                .iter()
+                .any(|e| e.main_worktree_path == canonical_path)
+        );
+        assert!(!forget_trusted(&canonical_path).unwrap());
+    }
+
     #[test]
     fn test_list_trusted_no_error() {
         init_test_data_dir();
@@ -588,10 +659,12 @@ mod tests {
             pre_add: vec![HookEntry {
                 command: "echo 'test'".to_string(),
                 description: None,
+                ..Default::default()
             }],
             post_add: vec![HookEntry {
                 command: "npm install".to_string(),
                 description: None,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -626,6 +699,7 @@ mod tests {
             pre_add: vec![HookEntry {
                 command: "echo 'repo1'".to_string(),
                 description: None,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -635,6 +709,7 @@ mod tests {
             pre_add: vec![HookEntry {
                 command: "echo 'repo2'".to_string(),
                 description: None,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -679,19 +754,24 @@ mod tests {
             pre_add: vec![HookEntry {
                 command: "echo 'pre'".to_string(),
                 description: None,
+                ..Default::default()
             }],
             post_add: vec![HookEntry {
                 command: "npm install".to_string(),
                 description: None,
+                ..Default::default()
             }],
             pre_remove: vec![HookEntry {
                 command: "echo 'cleanup'".to_string(),
                 description: None,
+                ..Default::default()
             }],
             post_remove: vec![HookEntry {
                 command: "./scripts/cleanup.sh".to_string(),
                 description: None,
+                ..Default::default()
             }],
+            ..Default::default()
         };
 
         // Trust the config
@@ -728,6 +808,7 @@ mod tests {
             pre_add: vec![HookEntry {
                 command: "echo 'original'".to_string(),
                 description: None,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -742,6 +823,7 @@ mod tests {
             pre_add: vec![HookEntry {
                 command: "echo 'modified'".to_string(),
                 description: None,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -762,6 +844,7 @@ mod tests {
             pre_add: vec![HookEntry {
                 command: "echo 'test'".to_string(),
                 description: None,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -793,6 +876,7 @@ mod tests {
             pre_add: vec![HookEntry {
                 command: "echo 'test'".to_string(),
                 description: None,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -842,6 +926,7 @@ mod tests {
             pre_add: vec![HookEntry {
                 command: "echo 'test'".to_string(),
                 description: None,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -55,12 +55,25 @@ pub(crate) struct WorkspaceInfo {
     pub branch: Option<String>,
     /// Whether this is the main/primary workspace.
     pub is_main: bool,
-    /// Whether the workspace is locked (git only, always false for jj).
+    /// Whether the workspace is locked (by git, or by `kabu lock` for jj).
     pub is_locked: bool,
+    /// Reason given when the workspace was locked, if any.
+    pub lock_reason: Option<String>,
+    /// Whether git reports the worktree as prunable (always false for jj).
+    pub is_prunable: bool,
     /// Workspace name (jj only, None for git).
     pub workspace_name: Option<String>,
 }
 
+/// Revision checked out in a workspace, in short form.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Revision {
+    /// Change ID (jj only, None for git).
+    pub change_id: Option<String>,
+    /// Commit ID. None if there is no commit yet (e.g. an unborn branch).
+    pub commit_id: Option<String>,
+}
+
 /// Working copy status information.
 #[derive(Debug, Clone, Default)]
 pub(crate) struct WorkspaceStatus {
@@ -68,6 +81,8 @@ pub(crate) struct WorkspaceStatus {
     pub modified_count: usize,
     pub deleted_count: usize,
     pub untracked_count: usize,
+    /// Untracked paths relative to the workspace (git only; jj tracks new files)
+    pub untracked_paths: Vec<PathBuf>,
 }
 
 /// Unpushed commits information.
@@ -126,6 +141,21 @@ pub(crate) trait VcsProvider {
     /// Remove a workspace with error checking (returns error on failure).
     fn workspace_remove_checked(&self, path: &Path, force: bool) -> Result<()>;
 
+    /// Move a workspace to a new path, keeping the VCS metadata intact.
+    ///
+    /// For git, this calls `git worktree move`.
+    /// For jj, this moves the directory and renames the workspace after it.
+    fn workspace_move(&self, old_path: &Path, new_path: &Path) -> Result<()>;
+
+    /// Lock a workspace so it is not removed, moved or pruned.
+    ///
+    /// For git, this calls `git worktree lock`. jj has no locks, so kabu records them
+    /// in its state directory.
+    fn workspace_lock(&self, path: &Path, reason: Option<&str>) -> Result<()>;
+
+    /// Unlock a workspace locked with [`VcsProvider::workspace_lock`].
+    fn workspace_unlock(&self, path: &Path) -> Result<()>;
+
     /// List all workspaces.
     fn list_workspaces(&self) -> Result<Vec<WorkspaceInfo>>;
 
@@ -138,6 +168,9 @@ pub(crate) trait VcsProvider {
     /// Get the upstream branch name for a workspace.
     fn get_upstream(&self, path: &Path) -> Result<Option<String>>;
 
+    /// Get the revision checked out in a workspace.
+    fn workspace_revision(&self, path: &Path) -> Result<Revision>;
+
     /// List all files tracked by the VCS in the repository.
     fn list_tracked_files(&self, repo_root: &Path) -> Result<Vec<PathBuf>>;
 
@@ -152,6 +185,20 @@ pub(crate) trait VcsProvider {
 
     /// Validate a branch/bookmark name.
     fn validate_branch_name(&self, name: &str) -> Result<Option<String>>;
+
+    /// Get the default base that `kabu prune` checks for merged branches.
+    fn default_prune_base(&self, workspaces: &[WorkspaceInfo]) -> String;
+
+    /// Check whether a workspace's branch (git) or bookmark (jj) is merged into `base`.
+    ///
+    /// Returns false for detached workspaces.
+    fn is_merged(&self, workspace: &WorkspaceInfo, base: &str) -> Result<bool>;
+
+    /// Check whether a workspace's branch tracks an upstream that no longer exists.
+    fn is_upstream_gone(&self, workspace: &WorkspaceInfo) -> Result<bool>;
+
+    /// Drop the VCS metadata of a workspace whose directory no longer exists.
+    fn prune_workspace(&self, workspace: &WorkspaceInfo) -> Result<()>;
 }
 
 /// Get the appropriate VCS provider for the current directory.
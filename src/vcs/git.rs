@@ -2,7 +2,7 @@
 //!
 //! Provides workspace operations using git worktree commands.
 
-use super::{UnpushedInfo, VcsKind, VcsProvider, WorkspaceInfo, WorkspaceStatus};
+use super::{Revision, UnpushedInfo, VcsKind, VcsProvider, WorkspaceInfo, WorkspaceStatus};
 use crate::cli::AddArgs;
 use crate::error::{Error, Result};
 
@@ -41,6 +41,18 @@ impl VcsProvider for GitProvider {
         worktree_remove_checked(path, force)
     }
 
+    fn workspace_move(&self, old_path: &Path, new_path: &Path) -> Result<()> {
+        worktree_move(old_path, new_path)
+    }
+
+    fn workspace_lock(&self, path: &Path, reason: Option<&str>) -> Result<()> {
+        worktree_lock(path, reason)
+    }
+
+    fn workspace_unlock(&self, path: &Path) -> Result<()> {
+        worktree_unlock(path)
+    }
+
     fn list_workspaces(&self) -> Result<Vec<WorkspaceInfo>> {
         list_worktrees()
     }
@@ -57,6 +69,10 @@ impl VcsProvider for GitProvider {
         get_upstream_branch(path)
     }
 
+    fn workspace_revision(&self, path: &Path) -> Result<Revision> {
+        workspace_revision(path)
+    }
+
     fn list_tracked_files(&self, repo_root: &Path) -> Result<Vec<PathBuf>> {
         list_tracked_files(repo_root)
     }
@@ -76,6 +92,40 @@ impl VcsProvider for GitProvider {
     fn validate_branch_name(&self, name: &str) -> Result<Option<String>> {
         validate_branch_name(name)
     }
+
+    fn default_prune_base(&self, workspaces: &[WorkspaceInfo]) -> String {
+        // Compare against whatever the main worktree has checked out
+        workspaces
+            .iter()
+            .find(|ws| ws.is_main)
+            .map(|ws| match &ws.branch {
+                Some(branch) => branch
+                    .strip_prefix("refs/heads/")
+                    .unwrap_or(branch)
+                    .to_string(),
+                None => ws.head.clone(),
+            })
+            .unwrap_or_else(|| "HEAD".to_string())
+    }
+
+    fn is_merged(&self, workspace: &WorkspaceInfo, base: &str) -> Result<bool> {
+        match &workspace.branch {
+            Some(branch) => is_branch_merged(branch, base, &workspace.path),
+            None => Ok(false),
+        }
+    }
+
+    fn is_upstream_gone(&self, workspace: &WorkspaceInfo) -> Result<bool> {
+        match &workspace.branch {
+            Some(branch) => is_upstream_gone(branch, &workspace.path),
+            None => Ok(false),
+        }
+    }
+
+    fn prune_workspace(&self, _workspace: &WorkspaceInfo) -> Result<()> {
+        // `git worktree prune` drops every worktree whose directory is gone
+        worktree_prune()
+    }
 }
 
 /// Run `git worktree add` with CLI arguments.
@@ -187,6 +237,73 @@ fn worktree_remove_inner(path: &std::path::Path, force: bool) -> Result<std::pro
     Ok(cmd.output()?)
 }
 
+/// Run `git worktree move`.
+pub(crate) fn worktree_move(old_path: &Path, new_path: &Path) -> Result<()> {
+    let output = Command::new("git")
+        .args(["worktree", "move"])
+        .arg(old_path)
+        .arg(new_path)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(Error::GitWorktreeMoveFailed {
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Run `git worktree lock`.
+pub(crate) fn worktree_lock(path: &Path, reason: Option<&str>) -> Result<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["worktree", "lock"]);
+    if let Some(reason) = reason {
+        cmd.arg("--reason").arg(reason);
+    }
+    let output = cmd.arg(path).output()?;
+
+    if !output.status.success() {
+        return Err(Error::GitCommandFailed {
+            command: "git worktree lock".to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Run `git worktree unlock`.
+pub(crate) fn worktree_unlock(path: &Path) -> Result<()> {
+    let output = Command::new("git")
+        .args(["worktree", "unlock"])
+        .arg(path)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(Error::GitCommandFailed {
+            command: "git worktree unlock".to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Drop administrative files of worktrees whose directory no longer exists.
+pub(crate) fn worktree_prune() -> Result<()> {
+    let output = Command::new("git").args(["worktree", "prune"]).output()?;
+
+    if !output.status.success() {
+        return Err(Error::GitCommandFailed {
+            command: "git worktree prune".to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        });
+    }
+
+    Ok(())
+}
+
 /// Get recent commits for a branch or commitish.
 pub(crate) fn log_oneline(commitish: &str, limit: usize) -> Result<Vec<String>> {
     let output = Command::new("git")
@@ -405,6 +522,8 @@ fn parse_worktree_list(bytes: &[u8]) -> Result<Vec<WorkspaceInfo>> {
                 branch: None,
                 is_main: is_first,
                 is_locked: false,
+                lock_reason: None,
+                is_prunable: false,
                 workspace_name: None,
             });
             is_first = false;
@@ -418,10 +537,18 @@ fn parse_worktree_list(bytes: &[u8]) -> Result<Vec<WorkspaceInfo>> {
             }
         } else if line == "detached" {
             // HEAD is detached, branch remains None
-        } else if line.starts_with("locked")
+        } else if let Some(reason) = line.strip_prefix("locked")
             && let Some(ref mut wt) = current
         {
+            // `locked` alone, or `locked <reason>`
             wt.is_locked = true;
+            wt.lock_reason = Some(reason.trim_start())
+                .filter(|r| !r.is_empty())
+                .map(unquote_c_style);
+        } else if line.starts_with("prunable")
+            && let Some(ref mut wt) = current
+        {
+            wt.is_prunable = true;
         }
     }
 
@@ -432,6 +559,61 @@ fn parse_worktree_list(bytes: &[u8]) -> Result<Vec<WorkspaceInfo>> {
     Ok(worktrees)
 }
 
+/// Undo the C-style quoting git applies to porcelain values with special characters,
+/// e.g. `"on \"usb\"\tdrive"`. Values without surrounding quotes are returned as is.
+fn unquote_c_style(value: &str) -> String {
+    let Some(inner) = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    else {
+        return value.to_string();
+    };
+
+    let bytes = inner.as_bytes();
+    let mut unquoted = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        i += 1;
+        if byte != b'\\' {
+            unquoted.push(byte);
+            continue;
+        }
+        let Some(&escaped) = bytes.get(i) else {
+            unquoted.push(byte);
+            break;
+        };
+        i += 1;
+        let byte = match escaped {
+            b'a' => 0x07,
+            b'b' => 0x08,
+            b'f' => 0x0c,
+            b'n' => b'\n',
+            b'r' => b'\r',
+            b't' => b'\t',
+            b'v' => 0x0b,
+            // Bytes outside printable ASCII are written as three octal digits
+            b'0'..=b'3' => {
+                let mut value = escaped - b'0';
+                for _ in 0..2 {
+                    match bytes.get(i) {
+                        Some(&digit @ b'0'..=b'7') => {
+                            value = value * 8 + (digit - b'0');
+                            i += 1;
+                        }
+                        _ => break,
+                    }
+                }
+                value
+            }
+            // `\"` and `\\`
+            other => other,
+        };
+        unquoted.push(byte);
+    }
+    String::from_utf8_lossy(&unquoted).into_owned()
+}
+
 /// Get the status of a worktree.
 pub(crate) fn worktree_status(worktree_path: &std::path::Path) -> Result<WorkspaceStatus> {
     let output = Command::new("git")
@@ -451,6 +633,7 @@ fn parse_status_output(bytes: &[u8]) -> Result<WorkspaceStatus> {
     let mut modified_count = 0;
     let mut deleted_count = 0;
     let mut untracked_count = 0;
+    let mut untracked_paths = Vec::new();
 
     for line in text.lines() {
         if line.len() < 2 {
@@ -461,6 +644,11 @@ fn parse_status_output(bytes: &[u8]) -> Result<WorkspaceStatus> {
 
         if index == '?' && worktree == '?' {
             untracked_count += 1;
+            // Quoted (escaped) paths are counted but not recorded
+            let path = line.get(3..).unwrap_or("");
+            if !path.is_empty() && !path.starts_with('"') {
+                untracked_paths.push(PathBuf::from(path.trim_end_matches('/')));
+            }
         } else if index == 'M' || worktree == 'M' {
             modified_count += 1;
         } else if index == 'D' || worktree == 'D' {
@@ -477,6 +665,7 @@ fn parse_status_output(bytes: &[u8]) -> Result<WorkspaceStatus> {
         modified_count,
         deleted_count,
         untracked_count,
+        untracked_paths,
     })
 }
 
@@ -586,6 +775,118 @@ pub(crate) fn get_upstream_branch(worktree_path: &std::path::Path) -> Result<Opt
     }
 }
 
+/// Get the commit checked out in a worktree.
+pub(crate) fn workspace_revision(worktree_path: &Path) -> Result<Revision> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .current_dir(worktree_path)
+        .output()?;
+
+    // Fails on an unborn branch, which has no commit yet
+    if !output.status.success() {
+        return Ok(Revision::default());
+    }
+
+    let commit_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok(Revision {
+        change_id: None,
+        commit_id: (!commit_id.is_empty()).then_some(commit_id),
+    })
+}
+
+/// Check whether `branch` was merged into `base`.
+///
+/// The branch has to be an ancestor of `base` (same as `git branch --merged`) and its tip
+/// must not be on the first-parent history of `base`. A new branch points at a commit of
+/// that history, also after `base` moved on, while a branch merged with a merge commit is
+/// only reachable through a second parent. A fast-forward merge can't be told apart from a
+/// new branch, so such a branch is not reported as merged.
+pub(crate) fn is_branch_merged(branch: &str, base: &str, worktree_path: &Path) -> Result<bool> {
+    if !is_ancestor(branch, base, worktree_path)? {
+        return Ok(false);
+    }
+    Ok(!is_on_first_parent_history(branch, base, worktree_path)?)
+}
+
+/// Check whether `branch` is an ancestor of `base`.
+fn is_ancestor(branch: &str, base: &str, worktree_path: &Path) -> Result<bool> {
+    let output = Command::new("git")
+        .args(["merge-base", "--is-ancestor", branch, base])
+        .current_dir(worktree_path)
+        .output()?;
+
+    // Exit code 1 means "not an ancestor"; anything else is a real failure
+    match output.status.code() {
+        Some(0) => Ok(true),
+        Some(1) => Ok(false),
+        _ => Err(Error::GitCommandFailed {
+            command: format!("git merge-base --is-ancestor {} {}", branch, base),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        }),
+    }
+}
+
+/// Check whether the tip of `branch` is on the first-parent history of `base`.
+fn is_on_first_parent_history(branch: &str, base: &str, worktree_path: &Path) -> Result<bool> {
+    let tip = resolve_commit(branch, worktree_path)?;
+
+    // Excluding the parents of the tip stops the walk at the tip or at the fork point,
+    // so only the commits made on `base` since then are listed
+    let parents = format!("{}^@", tip);
+    let output = Command::new("git")
+        .args(["rev-list", "--first-parent", base, "--not", &parents])
+        .current_dir(worktree_path)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(Error::GitCommandFailed {
+            command: format!("git rev-list --first-parent {} --not {}", base, parents),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        });
+    }
+
+    Ok(parse_output_lines(&output.stdout).contains(&tip))
+}
+
+/// Resolve `rev` to a full commit id.
+fn resolve_commit(rev: &str, worktree_path: &Path) -> Result<String> {
+    let commit = format!("{}^{{commit}}", rev);
+    let output = Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet", &commit])
+        .current_dir(worktree_path)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(Error::GitCommandFailed {
+            command: format!("git rev-parse --verify {}", commit),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        });
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Check whether `branch` has an upstream configured that no longer exists.
+pub(crate) fn is_upstream_gone(branch: &str, worktree_path: &Path) -> Result<bool> {
+    let output = Command::new("git")
+        .args(["for-each-ref", "--format=%(upstream:track)", branch])
+        .current_dir(worktree_path)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(Error::GitCommandFailed {
+            command: format!("git for-each-ref --format=%(upstream:track) {}", branch),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        });
+    }
+
+    Ok(parse_upstream_track(&output.stdout))
+}
+
+fn parse_upstream_track(bytes: &[u8]) -> bool {
+    String::from_utf8_lossy(bytes).trim() == "[gone]"
+}
+
 fn parse_log_output(bytes: &[u8]) -> Result<UnpushedInfo> {
     let lines = parse_output_lines(bytes);
     let count = lines.len();
@@ -635,6 +936,20 @@ mod tests {
         assert_eq!(result[1].branch, Some("refs/heads/feature".to_string()));
     }
 
+    #[test]
+    fn test_parse_upstream_track() {
+        assert!(parse_upstream_track(b"[gone]\n"));
+        assert!(!parse_upstream_track(b"[ahead 1]\n"));
+        assert!(!parse_upstream_track(b"\n"));
+    }
+
+    #[test]
+    fn test_default_prune_base_uses_main_branch() {
+        let output = b"worktree /home/user/repo\nHEAD abc1234\nbranch refs/heads/develop\n\nworktree /home/user/feature\nHEAD def5678\nbranch refs/heads/feature\n\n";
+        let workspaces = parse_worktree_list(output).unwrap();
+        assert_eq!(GitProvider.default_prune_base(&workspaces), "develop");
+    }
+
     #[test]
     fn test_parse_worktree_list_detached() {
         let output = b"worktree /home/user/repo\nHEAD abc1234\nbranch refs/heads/main\n\nworktree /home/user/detached\nHEAD def5678\ndetached\n\n";
@@ -652,6 +967,36 @@ mod tests {
         assert_eq!(result.len(), 2);
         assert!(!result[0].is_locked);
         assert!(result[1].is_locked);
+        assert_eq!(result[1].lock_reason, None);
+    }
+
+    #[test]
+    fn test_parse_worktree_list_lock_reason_and_prunable() {
+        let output = b"worktree /home/user/repo\nHEAD abc1234\nbranch refs/heads/main\n\nworktree /home/user/usb\nHEAD def5678\nbranch refs/heads/usb\nlocked on removable drive\n\nworktree /home/user/gone\nHEAD 0123456\ndetached\nprunable gitdir file points to non-existent location\n\n";
+        let result = parse_worktree_list(output).unwrap();
+
+        assert_eq!(result.len(), 3);
+        assert!(result[1].is_locked);
+        assert_eq!(result[1].lock_reason.as_deref(), Some("on removable drive"));
+        assert!(!result[1].is_prunable);
+        assert!(!result[2].is_locked);
+        assert!(result[2].is_prunable);
+    }
+
+    #[test]
+    fn test_parse_worktree_list_quoted_lock_reason() {
+        let output = b"worktree /home/user/repo\nHEAD abc1234\nbranch refs/heads/main\n\nworktree /home/user/usb\nHEAD def5678\nbranch refs/heads/usb\nlocked \"on \\\"usb\\\"\\tdrive\"\n\n";
+        let result = parse_worktree_list(output).unwrap();
+
+        assert_eq!(result[1].lock_reason.as_deref(), Some("on \"usb\"\tdrive"));
+    }
+
+    #[test]
+    fn test_unquote_c_style() {
+        assert_eq!(unquote_c_style("plain reason"), "plain reason");
+        assert_eq!(unquote_c_style(r#""a\\b\nc""#), "a\\b\nc");
+        assert_eq!(unquote_c_style(r#""caf\303\251""#), "café");
+        assert_eq!(unquote_c_style(r#""trailing\""#), "trailing\\");
     }
 
     #[test]
@@ -696,6 +1041,19 @@ mod tests {
         assert_eq!(result.modified_count, 0);
         assert_eq!(result.deleted_count, 0);
         assert_eq!(result.untracked_count, 2);
+        assert_eq!(
+            result.untracked_paths,
+            vec![PathBuf::from("file1.txt"), PathBuf::from("file2.txt")]
+        );
+    }
+
+    #[test]
+    fn test_parse_status_output_untracked_dir_and_quoted() {
+        let output = b"?? build/\n?? \"with space.txt\"\n";
+        let result = parse_status_output(output).unwrap();
+
+        assert_eq!(result.untracked_count, 2);
+        assert_eq!(result.untracked_paths, vec![PathBuf::from("build")]);
     }
 
     #[test]
@@ -2,9 +2,10 @@
 //!
 //! Provides workspace operations using jj workspace commands.
 
-use super::{UnpushedInfo, VcsKind, VcsProvider, WorkspaceInfo, WorkspaceStatus};
+use super::{Revision, UnpushedInfo, VcsKind, VcsProvider, WorkspaceInfo, WorkspaceStatus};
 use crate::cli::AddArgs;
 use crate::error::{Error, Result};
+use crate::state;
 
 use std::path::{Path, PathBuf};
 use std::process::Command;
@@ -46,6 +47,20 @@ impl VcsProvider for JjProvider {
         workspace_remove_checked(path, force)
     }
 
+    fn workspace_move(&self, old_path: &Path, new_path: &Path) -> Result<()> {
+        workspace_move(old_path, new_path)
+    }
+
+    fn workspace_lock(&self, path: &Path, reason: Option<&str>) -> Result<()> {
+        let main_workspace_path = main_workspace_path_for(&repository_root()?)?;
+        state::lock_worktree(&main_workspace_path, path, reason)
+    }
+
+    fn workspace_unlock(&self, path: &Path) -> Result<()> {
+        let main_workspace_path = main_workspace_path_for(&repository_root()?)?;
+        state::unlock_worktree(&main_workspace_path, path)
+    }
+
     fn list_workspaces(&self) -> Result<Vec<WorkspaceInfo>> {
         list_workspaces()
     }
@@ -63,6 +78,10 @@ impl VcsProvider for JjProvider {
         Ok(None)
     }
 
+    fn workspace_revision(&self, path: &Path) -> Result<Revision> {
+        workspace_revision(path)
+    }
+
     fn list_tracked_files(&self, repo_root: &Path) -> Result<Vec<PathBuf>> {
         list_tracked_files(repo_root)
     }
@@ -82,6 +101,30 @@ impl VcsProvider for JjProvider {
     fn validate_branch_name(&self, name: &str) -> Result<Option<String>> {
         validate_bookmark_name(name)
     }
+
+    fn default_prune_base(&self, _workspaces: &[WorkspaceInfo]) -> String {
+        "trunk()".to_string()
+    }
+
+    fn is_merged(&self, workspace: &WorkspaceInfo, base: &str) -> Result<bool> {
+        match &workspace.branch {
+            Some(bookmark) => is_bookmark_merged(bookmark, base, &workspace.path),
+            None => Ok(false),
+        }
+    }
+
+    fn is_upstream_gone(&self, _workspace: &WorkspaceInfo) -> Result<bool> {
+        // `jj git fetch` deletes local bookmarks whose tracked remote bookmark was
+        // deleted, so there is no "gone" state left to detect.
+        Ok(false)
+    }
+
+    fn prune_workspace(&self, workspace: &WorkspaceInfo) -> Result<()> {
+        match &workspace.workspace_name {
+            Some(name) => workspace_forget_by_name(name),
+            None => Ok(()),
+        }
+    }
 }
 
 /// Check if current directory is inside a jj repository.
@@ -293,6 +336,70 @@ pub(crate) fn workspace_remove_checked(path: &Path, force: bool) -> Result<()> {
     Ok(())
 }
 
+/// Move a workspace directory and rename the workspace after its new directory name.
+///
+/// jj has no command for this: the directory is renamed, the `.jj/repo` pointer (which
+/// may be relative to the old location) is made absolute, and `jj workspace rename`
+/// keeps the name in sync since kabu derives workspace names from directory names.
+pub(crate) fn workspace_move(old_path: &Path, new_path: &Path) -> Result<()> {
+    let pointer = old_path.join(".jj").join("repo");
+    let original_pointer = if pointer.is_file() {
+        Some(std::fs::read(&pointer)?)
+    } else {
+        None
+    };
+    let repo_dir = match &original_pointer {
+        Some(content) => {
+            let repo_path = PathBuf::from(String::from_utf8_lossy(content).trim());
+            Some(old_path.join(".jj").join(repo_path).canonicalize()?)
+        }
+        None => None,
+    };
+
+    std::fs::rename(old_path, new_path)?;
+
+    // jj still knows the workspace under its old name and location until the rename
+    // succeeds, so a failure puts the pointer and the directory back
+    let result = update_moved_workspace(old_path, new_path, repo_dir.as_deref());
+    if result.is_err() {
+        if let Some(content) = &original_pointer {
+            let _ = std::fs::write(new_path.join(".jj").join("repo"), content);
+        }
+        let _ = std::fs::rename(new_path, old_path);
+    }
+    result
+}
+
+/// Point a moved workspace at its repository and rename it after its new directory.
+fn update_moved_workspace(old_path: &Path, new_path: &Path, repo_dir: Option<&Path>) -> Result<()> {
+    if let Some(repo_dir) = repo_dir {
+        std::fs::write(
+            new_path.join(".jj").join("repo"),
+            repo_dir.to_string_lossy().as_bytes(),
+        )?;
+    }
+
+    let old_name = old_path.file_name().and_then(|n| n.to_str());
+    let new_name = new_path.file_name().and_then(|n| n.to_str());
+    if let Some(new_name) = new_name
+        && old_name != Some(new_name)
+    {
+        let output = Command::new("jj")
+            .args(["workspace", "rename", new_name])
+            .current_dir(new_path)
+            .output()?;
+
+        if !output.status.success() {
+            return Err(Error::JjCommandFailed {
+                command: format!("jj workspace rename {new_name}"),
+                stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
 fn workspace_forget_inner(path: &Path) -> Result<std::process::Output> {
     // Get workspace name from path
     let workspace_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
@@ -308,6 +415,23 @@ fn workspace_forget_inner(path: &Path) -> Result<std::process::Output> {
     Ok(output)
 }
 
+/// Forget a workspace by name, e.g. after its directory was deleted.
+pub(crate) fn workspace_forget_by_name(name: &str) -> Result<()> {
+    let repo_root = repository_root()?;
+    let output = Command::new("jj")
+        .args(["workspace", "forget", name])
+        .current_dir(&repo_root)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(Error::JjWorkspaceForgetFailed {
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        });
+    }
+
+    Ok(())
+}
+
 /// Find the repository root from a workspace path.
 fn find_repo_root_from_workspace(workspace_path: &Path) -> Result<PathBuf> {
     // Try to get root from the workspace
@@ -364,7 +488,30 @@ fn list_workspaces_at(repo_root: &Path) -> Result<Vec<WorkspaceInfo>> {
         });
     }
 
-    parse_workspace_list(&output.stdout, repo_root)
+    let mut workspaces = parse_workspace_list(&output.stdout, repo_root)?;
+    apply_locks(&mut workspaces);
+    Ok(workspaces)
+}
+
+/// Mark the workspaces locked with `kabu lock`, which jj itself knows nothing about.
+fn apply_locks(workspaces: &mut [WorkspaceInfo]) {
+    let Some(main_path) = workspaces
+        .iter()
+        .find(|ws| ws.is_main)
+        .or(workspaces.first())
+        .map(|ws| ws.path.clone())
+    else {
+        return;
+    };
+    // Listing must keep working when the state directory cannot be read
+    let locks = state::read_locks(&main_path).unwrap_or_default();
+    for ws in workspaces.iter_mut() {
+        let path = state::normalize_worktree_path(&ws.path);
+        if let Some(lock) = locks.iter().find(|lock| lock.worktree_path == path) {
+            ws.is_locked = true;
+            ws.lock_reason = lock.reason.clone();
+        }
+    }
 }
 
 fn parse_workspace_list(bytes: &[u8], repo_root: &Path) -> Result<Vec<WorkspaceInfo>> {
@@ -411,7 +558,10 @@ fn parse_workspace_list(bytes: &[u8], repo_root: &Path) -> Result<Vec<WorkspaceI
             branch,
             // In jj, "default" workspace is the main workspace
             is_main: name == "default",
-            is_locked: false, // jj doesn't have workspace locking
+            // Set from kabu's state by apply_locks
+            is_locked: false,
+            lock_reason: None,
+            is_prunable: false,
             workspace_name: Some(name),
         });
     }
@@ -558,6 +708,40 @@ fn get_workspace_bookmark(workspace_path: &Path) -> Result<Option<String>> {
     Ok(None)
 }
 
+/// Get the change and commit of the working copy of a workspace.
+fn workspace_revision(workspace_path: &Path) -> Result<Revision> {
+    let output = Command::new("jj")
+        .args([
+            "log",
+            "-r",
+            "@",
+            "--no-graph",
+            "-T",
+            r#"change_id.short(12) ++ "\t" ++ commit_id.short(12)"#,
+        ])
+        .current_dir(workspace_path)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(Error::JjCommandFailed {
+            command: "jj log -r @".to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        });
+    }
+
+    Ok(parse_revision(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse the `change_id<TAB>commit_id` line printed by [`workspace_revision`].
+fn parse_revision(text: &str) -> Revision {
+    let line = text.lines().next().unwrap_or("").trim();
+    let mut parts = line.split('\t').map(str::trim).filter(|id| !id.is_empty());
+    Revision {
+        change_id: parts.next().map(String::from),
+        commit_id: parts.next().map(String::from),
+    }
+}
+
 /// Get the status of a workspace.
 pub(crate) fn workspace_status(workspace_path: &Path) -> Result<WorkspaceStatus> {
     // Use jj status to check for changes
@@ -603,6 +787,7 @@ fn parse_jj_status(bytes: &[u8]) -> Result<WorkspaceStatus> {
         modified_count,
         deleted_count,
         untracked_count,
+        untracked_paths: Vec::new(),
     })
 }
 
@@ -732,6 +917,52 @@ pub(crate) fn log_oneline(revset: &str, limit: usize) -> Result<Vec<String>> {
         .collect())
 }
 
+/// Check whether a bookmark was merged into the `base` revset.
+///
+/// Uses the same rule as git: the bookmark has to be an ancestor of `base` without being on
+/// its first-parent history. A new bookmark at a commit of that history is not merged, while
+/// one merged with a merge commit is only reachable through a second parent.
+pub(crate) fn is_bookmark_merged(
+    bookmark: &str,
+    base: &str,
+    workspace_path: &Path,
+) -> Result<bool> {
+    let revset = merged_check_revset(bookmark, base);
+    let output = Command::new("jj")
+        .args([
+            "log",
+            "-r",
+            &revset,
+            "--no-graph",
+            "-T",
+            r#"commit_id ++ "\n""#,
+        ])
+        .current_dir(workspace_path)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(Error::JjCommandFailed {
+            command: format!("jj log -r {}", revset),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        });
+    }
+
+    // The revset only keeps the bookmark's commit when it is merged
+    Ok(!String::from_utf8_lossy(&output.stdout).trim().is_empty())
+}
+
+/// Build a revset selecting the bookmark's commit if it is an ancestor of `base` that is
+/// not on the first-parent history of `base`.
+fn merged_check_revset(bookmark: &str, base: &str) -> String {
+    // The bookmarks template marks diverged/conflicted bookmarks with `*`/`??`
+    let bookmark = bookmark.trim_end_matches(['*', '?']);
+    let escaped = bookmark.replace('\\', "\\\\").replace('"', "\\\"");
+    format!(
+        "bookmarks(exact:\"{}\") & (::({}) ~ first_ancestors({}))",
+        escaped, base, base
+    )
+}
+
 /// Validate a bookmark name.
 ///
 /// jj is more permissive with bookmark names, but we do basic validation.
@@ -755,6 +986,35 @@ pub(crate) fn validate_bookmark_name(name: &str) -> Result<Option<String>> {
     Ok(None)
 }
 
+#[cfg(all(test, feature = "impure-test"))]
+#[allow(clippy::unwrap_used)]
+mod impure_tests {
+    use super::*;
+
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_workspace_move_restores_workspace_on_failure() {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join("main/.jj/repo")).unwrap();
+        let old_path = temp.path().join("old-ws");
+        std::fs::create_dir_all(old_path.join(".jj")).unwrap();
+        std::fs::write(old_path.join(".jj/repo"), "../../main/.jj/repo").unwrap();
+        let new_path = temp.path().join("moved/new-ws");
+        std::fs::create_dir_all(temp.path().join("moved")).unwrap();
+
+        // Not a real repository, so `jj workspace rename` fails after the directory
+        // was moved and its pointer rewritten
+        assert!(workspace_move(&old_path, &new_path).is_err());
+
+        assert!(!new_path.exists());
+        assert_eq!(
+            std::fs::read_to_string(old_path.join(".jj/repo")).unwrap(),
+            "../../main/.jj/repo"
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -776,6 +1036,34 @@ mod tests {
         assert!(validate_bookmark_name("-invalid").unwrap().is_some());
     }
 
+    #[test]
+    fn test_parse_revision() {
+        assert_eq!(
+            parse_revision("kxqpmnztwyol\t3f2a1b9c8d7e\n"),
+            Revision {
+                change_id: Some("kxqpmnztwyol".to_string()),
+                commit_id: Some("3f2a1b9c8d7e".to_string()),
+            }
+        );
+        assert_eq!(parse_revision(""), Revision::default());
+    }
+
+    #[test]
+    fn test_merged_check_revset() {
+        assert_eq!(
+            merged_check_revset("feature", "trunk()"),
+            r#"bookmarks(exact:"feature") & (::(trunk()) ~ first_ancestors(trunk()))"#
+        );
+    }
+
+    #[test]
+    fn test_merged_check_revset_strips_markers_and_escapes() {
+        assert_eq!(
+            merged_check_revset("say\"hi\"*", "main"),
+            r#"bookmarks(exact:"say\"hi\"") & (::(main) ~ first_ancestors(main))"#
+        );
+    }
+
     #[test]
     fn test_parse_jj_status_empty() {
         let output = b"";
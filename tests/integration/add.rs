@@ -202,6 +202,38 @@ fn test_add_missing_source_file() {
         );
 }
 
+#[test]
+fn test_add_skips_missing_global_source() {
+    let mut repo = TestRepo::new();
+    repo.create_file(".claude", "personal\n");
+    let config_home = repo.worktree_path("config-home");
+    std::fs::create_dir_all(config_home.join("kabu")).unwrap();
+    std::fs::write(
+        config_home.join("kabu/config.yaml"),
+        "link:\n  - source: .envrc\n  - source: .claude\n",
+    )
+    .unwrap();
+
+    let worktree_path = repo.worktree_path("global-missing");
+    repo.kabu()
+        .env("XDG_CONFIG_HOME", &config_home)
+        .args([
+            "add",
+            worktree_path.to_str().unwrap(),
+            "-b",
+            "global-missing",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "(global config source not found: .envrc)",
+        ));
+    repo.register_worktree(worktree_path);
+
+    assert!(!repo.worktree_symlink_exists("global-missing", ".envrc"));
+    assert!(repo.worktree_symlink_exists("global-missing", ".claude"));
+}
+
 #[test]
 fn test_add_on_conflict_skip() {
     use crate::common::CONFIG_WITH_CONFLICT_SKIP;
@@ -443,3 +475,270 @@ fn test_add_on_conflict_overwrite() {
     // No backup file should exist
     assert!(!worktree_path.join("local.env.bak").exists());
 }
+
+#[test]
+fn test_add_with_rendered_copy() {
+    let mut repo = TestRepo::with_config(
+        r#"
+copy:
+  - source: .env.example
+    target: .env
+    render: true
+"#,
+    );
+    repo.create_file_and_commit(
+        ".env.example",
+        "PROJECT={{repository}}-{{worktree_name}}\nBRANCH={{branch}}\nKEEP={{unknown}}\n",
+        "Add env template",
+    );
+
+    let worktree_path = repo.worktree_path("wt-render");
+
+    repo.kabu()
+        .args([
+            "add",
+            worktree_path.to_str().unwrap(),
+            "-b",
+            "feature/render",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Rendering"));
+
+    repo.register_worktree(worktree_path.clone());
+
+    let repository = repo.path().file_name().unwrap().to_str().unwrap();
+    let content = repo.read_worktree_file("wt-render", ".env");
+    assert_eq!(
+        content,
+        format!("PROJECT={repository}-wt-render\nBRANCH=feature/render\nKEEP={{{{unknown}}}}\n")
+    );
+}
+
+#[test]
+fn test_add_from_linked_worktree_renders_main_repository_name() {
+    let mut repo = TestRepo::with_config(
+        r#"
+copy:
+  - source: .env.example
+    target: .env
+    render: true
+"#,
+    );
+    repo.create_file_and_commit(
+        ".env.example",
+        "PROJECT={{repository}}\n",
+        "Add env template",
+    );
+
+    let first = repo.worktree_path("wt-first");
+    repo.kabu()
+        .args(["add", first.to_str().unwrap(), "-b", "first"])
+        .assert()
+        .success();
+    repo.register_worktree(first.clone());
+
+    let second = repo.worktree_path("wt-second");
+    repo.kabu()
+        .current_dir(&first)
+        .args(["add", second.to_str().unwrap(), "-b", "second"])
+        .assert()
+        .success();
+    repo.register_worktree(second);
+
+    let repository = repo.path().file_name().unwrap().to_str().unwrap();
+    assert_eq!(
+        repo.read_worktree_file("wt-second", ".env"),
+        format!("PROJECT={repository}\n")
+    );
+}
+
+#[test]
+fn test_add_with_source_outside_repository() {
+    let mut repo = TestRepo::new();
+    let secrets = repo.worktree_path("secrets");
+    repo.write_config(&format!(
+        r#"
+link:
+  - source: .env
+    source_root: "{}/{{{{repository}}}}"
+"#,
+        secrets.display()
+    ));
+    let repository = repo.path().file_name().unwrap().to_str().unwrap();
+    std::fs::create_dir_all(secrets.join(repository)).expect("Failed to create secrets dir");
+    std::fs::write(secrets.join(repository).join(".env"), "TOKEN=1\n")
+        .expect("Failed to write secret");
+
+    let worktree_path = repo.worktree_path("wt-secrets");
+
+    // Reaching outside the repository requires trust, like hooks
+    repo.kabu()
+        .args(["add", worktree_path.to_str().unwrap(), "-b", "secrets"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("outside the repository"))
+        .stderr(predicate::str::contains("link: "));
+
+    repo.kabu()
+        .args(["trust", "--show"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Sources outside the repository:"));
+    repo.trust_config();
+
+    repo.kabu()
+        .args(["add", worktree_path.to_str().unwrap(), "-b", "secrets"])
+        .assert()
+        .success();
+    repo.register_worktree(worktree_path);
+
+    assert!(repo.worktree_symlink_exists("wt-secrets", ".env"));
+    assert_eq!(repo.read_worktree_file("wt-secrets", ".env"), "TOKEN=1\n");
+}
+
+#[test]
+fn test_add_with_filtered_copy() {
+    let mut repo = TestRepo::with_config(
+        r#"
+copy:
+  - source: .venv
+    exclude: [__pycache__]
+    max_size: 1KB
+"#,
+    );
+    repo.create_file(".venv/lib/app.py", "print(1)\n");
+    repo.create_file(".venv/lib/__pycache__/app.pyc", "bytecode");
+    repo.create_file("build/output.bin", "more than ten bytes");
+
+    let worktree_path = repo.worktree_path("wt-venv");
+    repo.kabu()
+        .args(["add", worktree_path.to_str().unwrap(), "-b", "venv"])
+        .assert()
+        .success();
+    repo.register_worktree(worktree_path);
+
+    assert!(repo.worktree_file_exists("wt-venv", ".venv/lib/app.py"));
+    assert!(!repo.worktree_dir_exists("wt-venv", ".venv/lib/__pycache__"));
+
+    // An oversized copy fails the add and rolls the worktree back
+    repo.write_config(
+        r#"
+copy:
+  - source: build
+    max_size: 10
+"#,
+    );
+    let worktree_path = repo.worktree_path("wt-build");
+    repo.kabu()
+        .args(["add", worktree_path.to_str().unwrap(), "-b", "build"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Copy exceeds max_size"));
+
+    assert!(!worktree_path.exists());
+}
+
+#[test]
+fn test_add_with_glob_copy() {
+    let mut repo = TestRepo::with_config(
+        r#"
+copy:
+  - source: "config/*.local.json"
+    ignore_tracked: true
+"#,
+    );
+    repo.create_file_and_commit(
+        "config/shared.local.json",
+        "{\"tracked\": true}",
+        "Add shared config",
+    );
+    repo.create_file("config/app.local.json", "{\"debug\": true}");
+    repo.create_file("config/app.json", "{}");
+
+    let worktree_path = repo.worktree_path("wt-glob-copy");
+    repo.kabu()
+        .args(["add", worktree_path.to_str().unwrap(), "-b", "glob-copy"])
+        .assert()
+        .success();
+    repo.register_worktree(worktree_path);
+
+    // Copied, not linked, so the worktree can change it independently
+    assert!(repo.worktree_file_exists("wt-glob-copy", "config/app.local.json"));
+    assert!(!repo.worktree_symlink_exists("wt-glob-copy", "config/app.local.json"));
+    assert_eq!(
+        repo.read_worktree_file("wt-glob-copy", "config/app.local.json"),
+        "{\"debug\": true}"
+    );
+    // The tracked match is checked out by git itself, the untracked non-match is left out
+    assert_eq!(
+        repo.read_worktree_file("wt-glob-copy", "config/shared.local.json"),
+        "{\"tracked\": true}"
+    );
+    assert!(!repo.worktree_file_exists("wt-glob-copy", "config/app.json"));
+}
+
+#[test]
+fn test_add_with_worktreeinclude() {
+    let mut repo = TestRepo::with_config(
+        r#"
+include_file:
+  mode: copy
+"#,
+    );
+    repo.create_file_and_commit(
+        ".worktreeinclude",
+        "# Local files for new worktrees\n.env*\n!.env.example\nconfig/*.local\n",
+        "Add worktreeinclude",
+    );
+    repo.create_file_and_commit(".env.shared", "SHARED=1\n", "Add tracked env");
+    repo.create_file(".env", "SECRET=1\n");
+    repo.create_file(".env.example", "SECRET=\n");
+    repo.create_file("config/db.local", "local\n");
+    repo.create_file("config/db.yaml", "shared\n");
+
+    let worktree_path = repo.worktree_path("wt-include");
+    repo.kabu()
+        .args(["add", worktree_path.to_str().unwrap(), "-b", "include"])
+        .assert()
+        .success();
+    repo.register_worktree(worktree_path);
+
+    assert_eq!(repo.read_worktree_file("wt-include", ".env"), "SECRET=1\n");
+    assert!(!repo.worktree_symlink_exists("wt-include", ".env"));
+    assert!(repo.worktree_file_exists("wt-include", "config/db.local"));
+    // Negated and unlisted files stay out
+    assert!(!repo.worktree_file_exists("wt-include", ".env.example"));
+    assert!(!repo.worktree_file_exists("wt-include", "config/db.yaml"));
+    // Tracked matches come from the checkout itself
+    assert_eq!(
+        repo.read_worktree_file("wt-include", ".env.shared"),
+        "SHARED=1\n"
+    );
+}
+
+#[test]
+fn test_add_with_kabu_include_links() {
+    let mut repo = TestRepo::with_config(
+        r#"
+include_file:
+  mode: link
+link:
+  - source: .envrc
+"#,
+    );
+    repo.create_file(".kabu/include", "/.envrc\n/fixtures/\n");
+    repo.create_file(".envrc", "use nix\n");
+    repo.create_file("fixtures/large.bin", "data");
+
+    let worktree_path = repo.worktree_path("wt-include-link");
+    repo.kabu()
+        .args(["add", worktree_path.to_str().unwrap(), "-b", "include-link"])
+        .assert()
+        .success();
+    repo.register_worktree(worktree_path);
+
+    // A directory match is linked as a whole; .envrc is left to its link entry
+    assert!(repo.worktree_symlink_exists("wt-include-link", "fixtures"));
+    assert!(repo.worktree_symlink_exists("wt-include-link", ".envrc"));
+}
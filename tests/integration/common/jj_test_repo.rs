@@ -111,6 +111,11 @@ impl JjTestRepo {
         run_jj(&self.repo_path, &["new"]);
     }
 
+    /// Run a jj command in the given directory
+    pub fn jj(&self, dir: &Path, args: &[&str]) {
+        run_jj(dir, args);
+    }
+
     /// Get a kabu Command configured for this repository
     pub fn kabu(&self) -> Command {
         let mut cmd = Command::cargo_bin("kabu").expect("Failed to find kabu binary");
@@ -119,6 +124,8 @@ impl JjTestRepo {
         if let Ok(trust_dir) = std::env::var("KABU_TRUST_DIR") {
             cmd.env("KABU_TRUST_DIR", trust_dir);
         }
+        // Keep slot allocations and other state inside the test directory
+        cmd.env("KABU_STATE_DIR", self.temp_dir.path().join("state"));
         cmd
     }
 
@@ -69,6 +69,11 @@ impl TestRepo {
         run_git(&self.repo_path, &["commit", "-m", message]);
     }
 
+    /// Run a git command in the given directory (panics on failure)
+    pub fn git(&self, dir: &Path, args: &[&str]) {
+        run_git(dir, args);
+    }
+
     /// Get a kabu Command configured for this repository
     pub fn kabu(&self) -> Command {
         let mut cmd = Command::cargo_bin("kabu").expect("Failed to find kabu binary");
@@ -77,9 +82,69 @@ impl TestRepo {
         if let Ok(trust_dir) = std::env::var("KABU_TRUST_DIR") {
             cmd.env("KABU_TRUST_DIR", trust_dir);
         }
+        // Keep slot allocations and other state inside the test directory
+        cmd.env("KABU_STATE_DIR", self.temp_dir.path().join("state"));
         cmd
     }
 
+    /// Run kabu with a pseudo terminal as its controlling terminal and stdio, as from an
+    /// interactive shell. Returns whether it succeeded and everything it printed.
+    #[cfg(unix)]
+    pub fn kabu_in_terminal(&self, args: &[&str]) -> (bool, String) {
+        use std::io::Read;
+        use std::os::fd::FromRawFd;
+        use std::os::unix::process::CommandExt;
+
+        let mut master = -1;
+        let mut slave = -1;
+        // SAFETY: openpty only writes the two descriptors
+        let opened = unsafe {
+            libc::openpty(
+                &mut master,
+                &mut slave,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        };
+        assert_eq!(opened, 0, "Failed to open a pty");
+        // SAFETY: both descriptors were just opened and are owned by nothing else
+        let (mut master, slave) =
+            unsafe { (fs::File::from_raw_fd(master), fs::File::from_raw_fd(slave)) };
+
+        let mut cmd = StdCommand::new(assert_cmd::cargo::cargo_bin("kabu"));
+        cmd.args(args)
+            .current_dir(&self.repo_path)
+            .env("KABU_STATE_DIR", self.temp_dir.path().join("state"))
+            .stdin(slave.try_clone().expect("Failed to clone pty"))
+            .stdout(slave.try_clone().expect("Failed to clone pty"))
+            .stderr(slave);
+        if let Ok(trust_dir) = std::env::var("KABU_TRUST_DIR") {
+            cmd.env("KABU_TRUST_DIR", trust_dir);
+        }
+        // SAFETY: setsid(2) and ioctl(2) are async-signal-safe
+        unsafe {
+            cmd.pre_exec(|| {
+                if libc::setsid() == -1 || libc::ioctl(0, libc::TIOCSCTTY, 0) == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+        let mut child = cmd.spawn().expect("Failed to run kabu");
+        // Close our copies of the slave, so that reading ends when kabu is done
+        drop(cmd);
+
+        // Reading fails with EIO once the terminal is closed; the output is kept
+        let mut output = Vec::new();
+        let _ = master.read_to_end(&mut output);
+        let status = child.wait().expect("Failed to wait for kabu");
+        (
+            status.success(),
+            String::from_utf8_lossy(&output).into_owned(),
+        )
+    }
+
     /// Trust the configuration (auto-accept with --yes flag)
     pub fn trust_config(&self) {
         self.kabu().args(["trust", "--yes"]).assert().success();
@@ -104,3 +104,89 @@ fn test_config_validate_no_config_file() {
     // No config file is treated as valid (empty default config)
     repo.kabu().args(["config", "validate"]).assert().success();
 }
+
+#[test]
+fn test_config_validate_reports_outdated_setup() {
+    let mut repo = TestRepo::with_config(
+        r#"
+copy:
+  - source: config.template
+    target: config.local
+"#,
+    );
+    repo.create_file("config.template", "# Config\n");
+    let worktree_path = repo.worktree_path("outdated-setup");
+
+    repo.kabu()
+        .args([
+            "add",
+            worktree_path.to_str().unwrap(),
+            "-b",
+            "outdated-setup",
+        ])
+        .assert()
+        .success();
+    repo.register_worktree(worktree_path.clone());
+
+    repo.kabu()
+        .args(["config", "validate"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("out of date").not());
+
+    repo.write_config(MINIMAL_CONFIG);
+
+    repo.kabu()
+        .args(["config", "validate"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Setup is out of date in 1 worktree(s)",
+        ))
+        .stdout(predicate::str::contains(
+            "no longer configured: config.local (copy)",
+        ));
+}
+
+#[test]
+fn test_config_validate_extends_reports_file() {
+    let repo = TestRepo::with_config("extends: [.kabu/base.yaml]\n");
+    repo.create_file(".kabu/base.yaml", "link:\n  - source: /etc/hosts\n");
+
+    repo.kabu()
+        .args(["config", "validate"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            ".kabu/base.yaml:\n  - link[0].source",
+        ));
+}
+
+#[test]
+fn test_config_extends_bare_name_from_repository_root() {
+    let repo = TestRepo::with_config("extends: [base.yaml]\n");
+    let config_home = repo.path().join("config-home");
+    repo.create_file("config-home/kabu/base.yaml", "on_conflict: backup\n");
+    repo.create_file("base.yaml", "on_conflict: overwrite\n");
+
+    repo.kabu()
+        .env("XDG_CONFIG_HOME", &config_home)
+        .args(["config", "get", "on_conflict"])
+        .assert()
+        .success()
+        .stdout("overwrite\n");
+}
+
+#[test]
+fn test_config_extends_global_file() {
+    let repo = TestRepo::with_config("extends: [global:rust.yaml]\n");
+    let config_home = repo.path().join("config-home");
+    repo.create_file("config-home/kabu/rust.yaml", "on_conflict: backup\n");
+
+    repo.kabu()
+        .env("XDG_CONFIG_HOME", &config_home)
+        .args(["config", "get", "on_conflict"])
+        .assert()
+        .success()
+        .stdout("backup\n");
+}
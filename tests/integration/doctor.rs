@@ -0,0 +1,215 @@
+use crate::common::{CONFIG_WITH_HOOKS, TestRepo};
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+const LINK_CONFIG: &str = r#"
+link:
+  - source: local.env
+"#;
+
+/// Create a worktree with setup applied.
+fn add_worktree(repo: &mut TestRepo, name: &str) -> std::path::PathBuf {
+    let worktree_path = repo.worktree_path(name);
+    repo.kabu()
+        .args([
+            "add",
+            worktree_path.to_str().expect("Non-UTF8 path"),
+            "-b",
+            name,
+        ])
+        .assert()
+        .success();
+    repo.register_worktree(worktree_path.clone());
+    worktree_path
+}
+
+/// Run `kabu doctor` with its own trust store, so stale entries of other tests don't show up.
+fn doctor(repo: &TestRepo, trust_dir: &TempDir) -> assert_cmd::Command {
+    let mut cmd = repo.kabu();
+    cmd.env("KABU_TRUST_DIR", trust_dir.path()).arg("doctor");
+    cmd
+}
+
+#[test]
+fn test_doctor_reports_no_problems() {
+    let mut repo = TestRepo::with_config(LINK_CONFIG);
+    repo.create_file("local.env", "A=1\n");
+    add_worktree(&mut repo, "doctor-clean");
+    let trust_dir = TempDir::new().unwrap();
+
+    doctor(&repo, &trust_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No problems found"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_doctor_fixes_dangling_symlink() {
+    let mut repo = TestRepo::with_config(LINK_CONFIG);
+    repo.create_file("local.env", "A=1\n");
+    let worktree_path = add_worktree(&mut repo, "doctor-dangling");
+    let trust_dir = TempDir::new().unwrap();
+
+    // Simulate the main repository having moved away from the old link target
+    let link = worktree_path.join("local.env");
+    std::fs::remove_file(&link).unwrap();
+    std::os::unix::fs::symlink("/nonexistent/repo/local.env", &link).unwrap();
+
+    doctor(&repo, &trust_dir)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("dangling symlink"))
+        .stdout(predicate::str::contains("kabu doctor --fix"))
+        .stderr(predicate::str::contains("1 problem(s) found"));
+
+    doctor(&repo, &trust_dir)
+        .arg("--fix")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Fixed:"));
+
+    assert_eq!(
+        std::fs::read_link(&link).unwrap(),
+        repo.path().join("local.env")
+    );
+    doctor(&repo, &trust_dir).assert().success();
+}
+
+#[test]
+fn test_doctor_recreates_missing_symlink() {
+    let mut repo = TestRepo::with_config(LINK_CONFIG);
+    repo.create_file("local.env", "A=1\n");
+    let worktree_path = add_worktree(&mut repo, "doctor-missing-link");
+    let trust_dir = TempDir::new().unwrap();
+
+    std::fs::remove_file(worktree_path.join("local.env")).unwrap();
+
+    doctor(&repo, &trust_dir)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("configured symlink is missing"));
+
+    doctor(&repo, &trust_dir).arg("--fix").assert().success();
+    assert!(repo.worktree_symlink_exists("doctor-missing-link", "local.env"));
+}
+
+#[test]
+fn test_doctor_relinks_to_main_sources_from_linked_worktree() {
+    let mut repo = TestRepo::with_config(LINK_CONFIG);
+    repo.create_file("local.env", "A=1\n");
+    let worktree_path = add_worktree(&mut repo, "doctor-inside");
+    let trust_dir = TempDir::new().unwrap();
+
+    std::fs::remove_file(worktree_path.join("local.env")).unwrap();
+
+    doctor(&repo, &trust_dir)
+        .current_dir(&worktree_path)
+        .arg("--fix")
+        .assert()
+        .success();
+    let link = std::fs::read_link(worktree_path.join("local.env")).unwrap();
+    assert_eq!(link, repo.path().join("local.env"));
+}
+
+#[test]
+fn test_doctor_reports_missing_link_source() {
+    let mut repo = TestRepo::with_config(LINK_CONFIG);
+    repo.create_file("local.env", "A=1\n");
+    add_worktree(&mut repo, "doctor-source");
+    let trust_dir = TempDir::new().unwrap();
+
+    std::fs::remove_file(repo.path().join("local.env")).unwrap();
+
+    // Nothing to fix automatically: the source has to be restored by the user
+    doctor(&repo, &trust_dir)
+        .arg("--fix")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("link source does not exist"))
+        .stdout(predicate::str::contains("Fixed:").not());
+    assert!(repo.worktree_symlink_exists("doctor-source", "local.env"));
+}
+
+#[test]
+fn test_doctor_prunes_deleted_worktree() {
+    let mut repo = TestRepo::with_config(LINK_CONFIG);
+    repo.create_file("local.env", "A=1\n");
+    let worktree_path = add_worktree(&mut repo, "doctor-deleted");
+    let trust_dir = TempDir::new().unwrap();
+
+    std::fs::remove_dir_all(&worktree_path).unwrap();
+
+    doctor(&repo, &trust_dir)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("directory no longer exists"))
+        .stdout(predicate::str::contains("git worktree prune"));
+
+    doctor(&repo, &trust_dir).arg("--fix").assert().success();
+
+    let worktrees = repo.list_worktrees();
+    assert!(
+        !worktrees
+            .iter()
+            .any(|path| path.ends_with("doctor-deleted")),
+        "deleted worktree should be pruned: {worktrees:?}"
+    );
+}
+
+#[test]
+fn test_doctor_removes_stale_trust_entry() {
+    let trust_dir = TempDir::new().unwrap();
+    {
+        let gone = TestRepo::with_config(CONFIG_WITH_HOOKS);
+        gone.kabu()
+            .env("KABU_TRUST_DIR", trust_dir.path())
+            .args(["trust", "--yes"])
+            .assert()
+            .success();
+    }
+
+    let repo = TestRepo::new();
+    doctor(&repo, &trust_dir)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "trusted repository no longer exists",
+        ));
+
+    doctor(&repo, &trust_dir).arg("--fix").assert().success();
+    doctor(&repo, &trust_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No problems found"));
+}
+
+#[test]
+fn test_doctor_reports_stale_global_trust_entry() {
+    let trust_dir = TempDir::new().unwrap();
+    let repo = TestRepo::new();
+    let config_home = repo.worktree_path("config-home");
+    std::fs::create_dir_all(config_home.join("kabu")).unwrap();
+    std::fs::write(
+        config_home.join("kabu/config.yaml"),
+        "hooks:\n  post_add:\n    - command: echo hi\n",
+    )
+    .unwrap();
+    repo.kabu()
+        .env("KABU_TRUST_DIR", trust_dir.path())
+        .env("XDG_CONFIG_HOME", &config_home)
+        .args(["trust", "--global", "--yes"])
+        .assert()
+        .success();
+
+    std::fs::remove_file(config_home.join("kabu/config.yaml")).unwrap();
+
+    doctor(&repo, &trust_dir)
+        .env("XDG_CONFIG_HOME", &config_home)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "trusted global config no longer exists",
+        ))
+        .stdout(predicate::str::contains("trusted repository").not());
+}
@@ -181,3 +181,514 @@ fn test_pre_remove_and_post_remove_hooks() {
 
     repo.clear_registered_worktrees();
 }
+
+#[test]
+fn test_ports_allocated_per_worktree_and_released() {
+    let mut repo = TestRepo::with_config(
+        r#"
+ports:
+  web: 3000
+  debug:
+    base: 9200
+    size: 10
+hooks:
+  post_add:
+    - command: echo {{slot}} {{port.web}} {{port.debug}}-{{port.debug.end}} > ports.txt
+"#,
+    );
+    repo.trust_config();
+
+    let first = repo.worktree_path("ports-first");
+    let second = repo.worktree_path("ports-second");
+    for (path, branch) in [(&first, "ports-first"), (&second, "ports-second")] {
+        repo.kabu()
+            .args(["add", path.to_str().unwrap(), "-b", branch])
+            .assert()
+            .success();
+        repo.register_worktree(path.clone());
+    }
+
+    assert_eq!(
+        repo.read_worktree_file("ports-first", "ports.txt"),
+        "1 3001 9210-9219\n"
+    );
+    assert_eq!(
+        repo.read_worktree_file("ports-second", "ports.txt"),
+        "2 3002 9220-9229\n"
+    );
+
+    // Removing the first worktree frees slot 1 for the next one
+    repo.kabu()
+        .args(["remove", "--force", first.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let third = repo.worktree_path("ports-third");
+    repo.kabu()
+        .args(["add", third.to_str().unwrap(), "-b", "ports-third"])
+        .assert()
+        .success();
+    repo.register_worktree(third);
+
+    assert_eq!(
+        repo.read_worktree_file("ports-third", "ports.txt"),
+        "1 3001 9210-9219\n"
+    );
+}
+
+#[test]
+fn test_ports_released_when_pre_add_fails() {
+    let mut repo = TestRepo::with_config(
+        r#"
+ports:
+  web: 3000
+hooks:
+  pre_add:
+    - command: test -f allow-add
+  post_add:
+    - command: echo {{port.web}} > port.txt
+"#,
+    );
+    repo.trust_config();
+
+    let failed = repo.worktree_path("ports-failed");
+    repo.kabu()
+        .args(["add", failed.to_str().unwrap(), "-b", "ports-failed"])
+        .assert()
+        .failure();
+
+    repo.create_file("allow-add", "");
+    let path = repo.worktree_path("ports-ok");
+    repo.kabu()
+        .args(["add", path.to_str().unwrap(), "-b", "ports-ok"])
+        .assert()
+        .success();
+    repo.register_worktree(path);
+
+    assert_eq!(repo.read_worktree_file("ports-ok", "port.txt"), "3001\n");
+}
+
+#[test]
+fn test_hook_timeout_kills_process() {
+    let mut repo = TestRepo::with_config(
+        r#"
+hooks:
+  post_add:
+    - command: sleep 30
+      timeout: 1
+"#,
+    );
+    repo.trust_config();
+
+    let path = repo.worktree_path("hook-timeout");
+    let started = std::time::Instant::now();
+    repo.kabu()
+        .args(["add", path.to_str().unwrap(), "-b", "hook-timeout"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("timed out after 1s"));
+    repo.register_worktree(path);
+
+    assert!(started.elapsed() < std::time::Duration::from_secs(20));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_timed_hook_can_use_the_terminal() {
+    let mut repo = TestRepo::with_config(
+        r#"
+hooks:
+  post_add:
+    - command: stty -echo && stty echo && echo terminal-ok | tr a-z A-Z
+      timeout: 10
+"#,
+    );
+    repo.trust_config();
+
+    // Changing terminal settings from a background process group stops the hook
+    let path = repo.worktree_path("hook-timed-tty");
+    let (success, output) =
+        repo.kabu_in_terminal(&["add", path.to_str().unwrap(), "-b", "hook-timed-tty"]);
+    repo.register_worktree(path);
+
+    assert!(success, "{output}");
+    assert!(output.contains("TERMINAL-OK"), "{output}");
+    assert!(!output.contains("timed out"), "{output}");
+}
+
+#[test]
+fn test_hook_env_and_cwd() {
+    let mut repo = TestRepo::with_config(
+        r#"
+mkdir:
+  - path: sub
+hooks:
+  post_add:
+    - command: echo "$GREETING" > greeting.txt
+      cwd: sub
+      env:
+        GREETING: "hello {{worktree_name}}"
+"#,
+    );
+    repo.trust_config();
+
+    let path = repo.worktree_path("hook-env");
+    repo.kabu()
+        .args(["add", path.to_str().unwrap(), "-b", "hook-env"])
+        .assert()
+        .success();
+    repo.register_worktree(path);
+
+    assert_eq!(
+        repo.read_worktree_file("hook-env", "sub/greeting.txt"),
+        "hello hook-env\n"
+    );
+}
+
+#[test]
+fn test_hook_retries_until_success() {
+    let mut repo = TestRepo::with_config(
+        r#"
+hooks:
+  post_add:
+    - command: "test -f attempted || { touch attempted; exit 1; }"
+      retries: 1
+"#,
+    );
+    repo.trust_config();
+
+    let path = repo.worktree_path("hook-retry");
+    repo.kabu()
+        .args(["add", path.to_str().unwrap(), "-b", "hook-retry"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Retrying (1/1)"))
+        .stdout(predicate::str::contains("Worktree created successfully"));
+    repo.register_worktree(path);
+}
+
+#[test]
+fn test_hook_continue_on_error() {
+    let mut repo = TestRepo::with_config(
+        r#"
+hooks:
+  pre_add:
+    - command: exit 3
+      continue_on_error: true
+    - command: "true"
+  post_add:
+    - command: touch after-failure
+"#,
+    );
+    repo.trust_config();
+
+    let path = repo.worktree_path("hook-continue");
+    repo.kabu()
+        .args(["add", path.to_str().unwrap(), "-b", "hook-continue"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("pre_add hook failed"));
+    repo.register_worktree(path);
+
+    assert!(repo.worktree_file_exists("hook-continue", "after-failure"));
+}
+
+#[test]
+fn test_hook_when_conditions() {
+    let mut repo = TestRepo::with_config(
+        r#"
+hooks:
+  post_add:
+    - command: touch on-release
+      when:
+        branch: "release/*"
+    - command: touch on-feature
+      when:
+        branch: "feature/*"
+        vcs: git
+    - command: touch has-readme
+      when:
+        file_exists: README.md
+    - command: npm install
+      when:
+        file_exists: package.json
+"#,
+    );
+    repo.trust_config();
+
+    let path = repo.worktree_path("hook-when");
+    repo.kabu()
+        .args(["add", path.to_str().unwrap(), "-b", "feature/when"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Skipped post_add hook [1/4]: touch on-release (when: branch: release/*)",
+        ))
+        .stdout(predicate::str::contains(
+            "Skipped post_add hook [4/4]: npm install (when: file_exists: package.json)",
+        ));
+    repo.register_worktree(path);
+
+    assert!(!repo.worktree_file_exists("hook-when", "on-release"));
+    assert!(repo.worktree_file_exists("hook-when", "on-feature"));
+    assert!(repo.worktree_file_exists("hook-when", "has-readme"));
+}
+
+#[test]
+fn test_results_summary_counts_only_hooks_that_ran() {
+    let mut repo = TestRepo::with_config(
+        r#"
+hooks:
+  post_create:
+    - command: touch created
+    - command: touch on-release
+      when:
+        branch: "release/*"
+  post_add:
+    - command: exit 1
+"#,
+    );
+    repo.trust_config();
+
+    let path = repo.worktree_path("hook-summary");
+    repo.kabu()
+        .args(["add", path.to_str().unwrap(), "-b", "feature/summary"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("post_create hooks (1 succeeded)"));
+    repo.register_worktree(path);
+}
+
+#[test]
+fn test_parallel_hooks_run_concurrently_and_aggregate_failures() {
+    let mut repo = TestRepo::with_config(
+        r#"
+hooks:
+  post_add:
+    - parallel:
+        # Each command waits for the other's marker, so they only finish together
+        - command: touch a && for i in $(seq 50); do [ -f b ] && exit 0; sleep 0.1; done; exit 1
+          description: first
+        - command: touch b && for i in $(seq 50); do [ -f a ] && exit 0; sleep 0.1; done; exit 1
+          description: second
+        - command: echo broken && exit 4
+          description: broken
+        - command: exit 5
+          description: also broken
+"#,
+    );
+    repo.trust_config();
+
+    let path = repo.worktree_path("hook-parallel");
+    repo.kabu()
+        .args(["add", path.to_str().unwrap(), "-b", "hook-parallel"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[broken     ] broken"))
+        .stdout(predicate::str::contains("post_add hooks (2 failed)"))
+        .stdout(predicate::str::contains("Exit code: 4"))
+        .stdout(predicate::str::contains("Exit code: 5"))
+        .stderr(predicate::str::contains("2 parallel hook(s) failed"));
+    repo.register_worktree(path);
+}
+
+#[test]
+fn test_parallel_hooks_buffered_output() {
+    let mut repo = TestRepo::with_config(
+        r#"
+hooks:
+  post_add:
+    - output: buffer
+      parallel:
+        - command: echo one && sleep 0.2 && echo two
+          description: slow
+        - command: echo three >&2
+"#,
+    );
+    repo.trust_config();
+
+    let path = repo.worktree_path("hook-buffer");
+    repo.kabu()
+        .args(["add", path.to_str().unwrap(), "-b", "hook-buffer"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Output of slow (succeeded):\none\ntwo\n",
+        ))
+        .stdout(predicate::str::contains("three"));
+    repo.register_worktree(path);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_hook_context_is_exported_to_scripts() {
+    let mut repo = TestRepo::with_config(
+        r#"
+hooks:
+  post_add:
+    - command: sh {{repo_root}}/context.sh
+"#,
+    );
+    repo.create_file(
+        "context.sh",
+        "echo \"event=$KABU_HOOK_EVENT vcs=$KABU_VCS_TYPE branch=$KABU_BRANCH\"\n\
+         echo \"path=$KABU_WORKTREE_PATH\"\n\
+         echo \"change=${KABU_CHANGE_ID-unset}\"\n",
+    );
+    repo.trust_config();
+
+    let path = repo.worktree_path("hook-context");
+    repo.kabu()
+        // Unavailable values must not leak in from kabu's own environment
+        .env("KABU_CHANGE_ID", "stale")
+        .args(["add", path.to_str().unwrap(), "-b", "hook-context"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "event=post_add vcs=git branch=hook-context",
+        ))
+        .stdout(predicate::str::contains(format!("path={}", path.display())))
+        .stdout(predicate::str::contains("change=unset"));
+    repo.register_worktree(path);
+}
+
+#[test]
+fn test_remove_hooks_see_branch_and_commit() {
+    let mut repo = TestRepo::with_config(
+        r#"
+hooks:
+  post_add:
+    - command: echo added {{branch}} at {{commit_id}}
+  pre_remove:
+    - command: echo removing {{branch}} at {{commit_id}}
+  post_remove:
+    - command: echo removed {{branch}} at {{commit_id}}
+"#,
+    );
+    repo.trust_config();
+
+    let head = std::process::Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .current_dir(repo.path())
+        .output()
+        .unwrap();
+    let head = String::from_utf8(head.stdout).unwrap().trim().to_string();
+
+    let path = repo.worktree_path("hook-revision");
+    repo.kabu()
+        .args(["add", path.to_str().unwrap(), "-b", "feature/revision"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "added feature/revision at {head}"
+        )));
+    repo.register_worktree(path.clone());
+
+    repo.kabu()
+        .args(["remove", path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "removing feature/revision at {head}"
+        )))
+        .stdout(predicate::str::contains(format!(
+            "removed feature/revision at {head}"
+        )));
+    repo.clear_registered_worktrees();
+}
+
+#[test]
+fn test_post_create_runs_before_setup() {
+    let mut repo = TestRepo::with_config(
+        r#"
+link:
+  - source: shared.txt
+hooks:
+  post_create:
+    - command: test ! -e shared.txt && echo created in {{worktree_name}}
+"#,
+    );
+    repo.create_file("shared.txt", "shared");
+    repo.trust_config();
+
+    let path = repo.worktree_path("post-create");
+    repo.kabu()
+        .args(["add", path.to_str().unwrap(), "-b", "post-create"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("created in post-create"));
+    repo.register_worktree(path);
+
+    assert!(repo.worktree_symlink_exists("post-create", "shared.txt"));
+}
+
+#[test]
+fn test_post_create_failure_rolls_back() {
+    let repo = TestRepo::with_config(
+        r#"
+hooks:
+  post_create:
+    - command: exit 3
+"#,
+    );
+    repo.trust_config();
+
+    let path = repo.worktree_path("post-create-fail");
+    repo.kabu()
+        .args(["add", path.to_str().unwrap(), "-b", "post-create-fail"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("rolling back"));
+
+    assert!(!path.exists());
+    assert!(
+        !repo
+            .list_worktrees()
+            .iter()
+            .any(|wt| wt.contains("post-create-fail"))
+    );
+}
+
+#[test]
+fn test_enter_runs_on_enter_hooks() {
+    let mut repo = TestRepo::with_config(
+        r#"
+hooks:
+  on_enter:
+    - command: echo entered {{worktree_name}} $KABU_HOOK_EVENT
+"#,
+    );
+    repo.trust_config();
+
+    let path = repo.worktree_path("enter-test");
+    repo.kabu()
+        .args(["add", path.to_str().unwrap(), "-b", "enter-test"])
+        .assert()
+        .success();
+    repo.register_worktree(path.clone());
+
+    repo.kabu()
+        .arg("enter")
+        .current_dir(&path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("entered enter-test on_enter"));
+}
+
+#[test]
+fn test_enter_skips_untrusted_hooks() {
+    let repo = TestRepo::with_config(
+        r#"
+hooks:
+  on_enter:
+    - command: echo should not run
+"#,
+    );
+
+    repo.kabu()
+        .arg("enter")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("should not run").not())
+        .stderr(predicate::str::contains("on_enter hooks skipped"));
+}
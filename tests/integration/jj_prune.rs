@@ -0,0 +1,75 @@
+use crate::common::{JjTestRepo, MINIMAL_CONFIG, jj_available};
+use predicates::prelude::*;
+
+#[test]
+fn test_jj_prune_reports_merged_bookmark() {
+    if !jj_available() {
+        eprintln!("Skipping test: jj not available");
+        return;
+    }
+
+    let mut repo = JjTestRepo::with_config(MINIMAL_CONFIG);
+    repo.jj(repo.path(), &["bookmark", "create", "main", "-r", "@-"]);
+
+    let ws_path = repo.workspace_path("prune-merged-ws");
+    repo.kabu()
+        .args(["add", ws_path.to_str().unwrap(), "-b", "prune-merged"])
+        .assert()
+        .success();
+    repo.register_workspace(ws_path.clone());
+
+    std::fs::write(ws_path.join("feature.txt"), "feature\n").unwrap();
+    repo.jj(&ws_path, &["describe", "-m", "Work on bookmark"]);
+
+    // Merge the bookmark into main with a merge commit
+    repo.jj(
+        repo.path(),
+        &["new", "main", "prune-merged", "-m", "Merge bookmark"],
+    );
+    repo.jj(repo.path(), &["bookmark", "set", "main", "-r", "@"]);
+
+    repo.kabu()
+        .args(["prune", "--dry-run", "--base", "main"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("prune-merged-ws"))
+        .stdout(predicate::str::contains("[merged]"));
+}
+
+#[test]
+fn test_jj_prune_keeps_new_bookmark() {
+    if !jj_available() {
+        eprintln!("Skipping test: jj not available");
+        return;
+    }
+
+    let mut repo = JjTestRepo::with_config(MINIMAL_CONFIG);
+    repo.jj(repo.path(), &["bookmark", "create", "main", "-r", "@-"]);
+
+    let ws_path = repo.workspace_path("prune-new-ws");
+    repo.kabu()
+        .args(["add", ws_path.to_str().unwrap(), "-b", "prune-new"])
+        .assert()
+        .success();
+    repo.register_workspace(ws_path.clone());
+
+    // A bookmark on a commit of main's history is an ancestor without anything merged
+    repo.jj(
+        &ws_path,
+        &[
+            "bookmark",
+            "set",
+            "prune-new",
+            "-r",
+            "main",
+            "--allow-backwards",
+        ],
+    );
+    repo.jj(&ws_path, &["edit", "prune-new"]);
+
+    repo.kabu()
+        .args(["prune", "--dry-run", "--base", "main"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No workspaces to prune"));
+}
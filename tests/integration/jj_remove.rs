@@ -207,3 +207,34 @@ fn test_jj_remove_multiple() {
     assert_eq!(workspaces.len(), 1);
     assert!(workspaces.contains(&"default".to_string()));
 }
+
+#[test]
+fn test_jj_remove_hooks_see_change_id() {
+    if !jj_available() {
+        eprintln!("Skipping test: jj not available");
+        return;
+    }
+
+    let mut repo = JjTestRepo::with_config(
+        r#"
+hooks:
+  pre_remove:
+    - command: echo change={{change_id}} commit={{commit_id}}
+"#,
+    );
+    repo.trust_config();
+    let ws_path = repo.workspace_path("remove-change-id");
+
+    repo.kabu()
+        .args(["add", ws_path.to_str().unwrap()])
+        .assert()
+        .success();
+    repo.register_workspace(ws_path.clone());
+
+    repo.kabu()
+        .args(["remove", ws_path.to_str().unwrap(), "--force"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"change=[a-z]{12} commit=[0-9a-f]{12}").unwrap());
+    repo.clear_registered_workspaces();
+}
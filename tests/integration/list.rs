@@ -112,3 +112,41 @@ fn test_list_shows_detached_head() {
         .success()
         .stdout(predicate::str::contains("HEAD detached").or(predicate::str::contains("detached")));
 }
+
+#[test]
+fn test_list_format_json() {
+    let mut repo = TestRepo::with_config(MINIMAL_CONFIG);
+    let wt_path = repo.worktree_path("json-wt");
+
+    repo.kabu()
+        .args(["add", wt_path.to_str().unwrap(), "-b", "json-wt"])
+        .assert()
+        .success();
+    repo.register_worktree(wt_path);
+
+    let output = repo
+        .kabu()
+        .args(["list", "--format", "json"])
+        .output()
+        .expect("Failed to run kabu list");
+    assert!(output.status.success());
+
+    let value: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Output should be valid JSON");
+    let entries = value.as_array().expect("Output should be a JSON array");
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["is_main"], true);
+    assert_eq!(entries[1]["branch"], "json-wt");
+    assert!(entries[1]["workspace_name"].is_null());
+}
+
+#[test]
+fn test_list_format_tsv_with_header() {
+    let repo = TestRepo::with_config(MINIMAL_CONFIG);
+
+    repo.kabu()
+        .args(["list", "--format", "tsv", "--header"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("path\tbranch\thead\t"));
+}
@@ -0,0 +1,145 @@
+use crate::common::TestRepo;
+use predicates::prelude::*;
+
+#[test]
+fn test_lock_shows_reason_and_blocks_remove() {
+    let mut repo = TestRepo::new();
+    let path = repo.worktree_path("locked");
+    repo.kabu()
+        .args(["add", path.to_str().unwrap(), "-b", "locked"])
+        .assert()
+        .success();
+    repo.register_worktree(path.clone());
+
+    repo.kabu()
+        .args(["lock", path.to_str().unwrap(), "--reason", "on a USB drive"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Locked"));
+
+    repo.kabu()
+        .args(["list", "--no-color"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("locked: on a USB drive"));
+    repo.kabu()
+        .args(["list", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#""lock_reason": "on a USB drive""#,
+        ));
+
+    repo.kabu()
+        .args(["lock", path.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Already locked"));
+
+    repo.kabu()
+        .args(["remove", path.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Is locked (on a USB drive)"))
+        .stderr(predicate::str::contains("--force"));
+    assert!(path.exists());
+
+    repo.kabu()
+        .args(["remove", "--force", path.to_str().unwrap()])
+        .assert()
+        .success();
+    assert!(!path.exists());
+    repo.clear_registered_worktrees();
+}
+
+#[test]
+fn test_failed_forced_remove_keeps_lock() {
+    let mut repo = TestRepo::new();
+    let path = repo.worktree_path("stays-locked");
+    repo.kabu()
+        .args(["add", path.to_str().unwrap(), "-b", "stays-locked"])
+        .assert()
+        .success();
+    repo.register_worktree(path.clone());
+    repo.kabu()
+        .args([
+            "lock",
+            path.to_str().unwrap(),
+            "--reason",
+            "on \"usb\"\tdrive",
+        ])
+        .assert()
+        .success();
+
+    // A broken .git file makes git refuse the removal
+    std::fs::write(path.join(".git"), "gitdir: /nonexistent\n").unwrap();
+
+    repo.kabu()
+        .args(["remove", "--force", path.to_str().unwrap()])
+        .assert()
+        .failure();
+
+    assert!(path.exists());
+    repo.kabu()
+        .args(["list", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#""lock_reason": "on \"usb\"\tdrive""#,
+        ));
+}
+
+#[test]
+fn test_unlock_current_worktree() {
+    let mut repo = TestRepo::new();
+    let path = repo.worktree_path("unlock-me");
+    repo.kabu()
+        .args(["add", path.to_str().unwrap(), "-b", "unlock-me"])
+        .assert()
+        .success();
+    repo.register_worktree(path.clone());
+
+    repo.kabu()
+        .arg("lock")
+        .current_dir(&path)
+        .assert()
+        .success();
+
+    let new_path = repo.worktree_path("unlock-me-moved");
+    repo.kabu()
+        .args(["move", path.to_str().unwrap(), new_path.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("kabu unlock"));
+
+    repo.kabu()
+        .arg("unlock")
+        .current_dir(&path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Unlocked"));
+
+    repo.kabu()
+        .arg("unlock")
+        .current_dir(&path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Not locked"));
+
+    repo.kabu()
+        .args(["remove", path.to_str().unwrap()])
+        .assert()
+        .success();
+    repo.clear_registered_worktrees();
+}
+
+#[test]
+fn test_lock_refuses_main_worktree() {
+    let repo = TestRepo::new();
+
+    repo.kabu()
+        .args(["lock", "."])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be locked"));
+}
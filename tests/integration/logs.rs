@@ -0,0 +1,140 @@
+use crate::common::TestRepo;
+use predicates::prelude::*;
+
+const FAILING_HOOK_CONFIG: &str = r#"
+hooks:
+  post_add:
+    - command: echo installing && echo broken dependency >&2 && exit 3
+      description: Install dependencies
+"#;
+
+#[test]
+fn test_failed_hook_points_at_log() {
+    let mut repo = TestRepo::with_config(FAILING_HOOK_CONFIG);
+    repo.trust_config();
+
+    let path = repo.worktree_path("logs-failed");
+    repo.kabu()
+        .args(["add", path.to_str().unwrap(), "-b", "logs-failed"])
+        .assert()
+        .success()
+        // Output still reaches the terminal while it is logged
+        .stdout(predicate::str::contains("installing"))
+        .stderr(predicate::str::contains("broken dependency"))
+        .stdout(predicate::str::contains("Exit code: 3"))
+        .stdout(predicate::str::is_match(r"Log: .*-post_add\.log").unwrap());
+    repo.register_worktree(path.clone());
+
+    repo.kabu()
+        .args(["logs", path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("# post_add hooks for"))
+        .stdout(predicate::str::contains(
+            "$ echo installing && echo broken dependency >&2 && exit 3\n",
+        ))
+        // stdout and stderr lines are read concurrently, so their order may vary
+        .stdout(predicate::str::contains("\ninstalling\n"))
+        .stdout(predicate::str::contains("\nbroken dependency\n"))
+        .stdout(predicate::str::contains("# failed:"));
+}
+
+#[test]
+fn test_logs_defaults_to_current_worktree_and_lists_runs() {
+    let mut repo = TestRepo::with_config(
+        r#"
+hooks:
+  post_add:
+    - command: echo setting up {{worktree_name}}
+"#,
+    );
+    repo.trust_config();
+
+    let path = repo.worktree_path("logs-current");
+    repo.kabu()
+        .args(["add", path.to_str().unwrap(), "-b", "logs-current"])
+        .assert()
+        .success();
+    repo.register_worktree(path.clone());
+    repo.kabu()
+        .args(["setup", path.to_str().unwrap(), "--run-hooks"])
+        .assert()
+        .success();
+
+    repo.kabu()
+        .current_dir(&path)
+        .arg("logs")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("setting up logs-current"))
+        .stdout(predicate::str::contains("# succeeded"));
+
+    let listed = repo
+        .kabu()
+        .current_dir(&path)
+        .args(["logs", "--list"])
+        .output()
+        .unwrap();
+    assert!(listed.status.success());
+    let stdout = String::from_utf8(listed.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), 2, "{stdout}");
+    assert!(stdout.lines().all(|line| line.ends_with("-post_add.log")));
+}
+
+#[test]
+fn test_logs_event_filter_skips_later_on_enter_runs() {
+    let mut repo = TestRepo::with_config(
+        r#"
+hooks:
+  post_add:
+    - command: echo broken && exit 3
+  on_enter:
+    - command: echo entered
+"#,
+    );
+    repo.trust_config();
+
+    let path = repo.worktree_path("logs-event");
+    repo.kabu()
+        .args(["add", path.to_str().unwrap(), "-b", "logs-event"])
+        .assert()
+        .success();
+    repo.register_worktree(path.clone());
+    repo.kabu()
+        .arg("enter")
+        .current_dir(&path)
+        .assert()
+        .success();
+
+    repo.kabu()
+        .current_dir(&path)
+        .arg("logs")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("# on_enter hooks for"));
+    repo.kabu()
+        .current_dir(&path)
+        .args(["logs", "--event", "post_add"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("# post_add hooks for"))
+        .stdout(predicate::str::contains("# failed:"));
+    repo.kabu()
+        .current_dir(&path)
+        .args(["logs", "--event", "pre_remove"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No hook logs found"));
+}
+
+#[test]
+fn test_logs_without_hook_runs() {
+    let repo = TestRepo::new();
+
+    repo.kabu()
+        .current_dir(repo.path())
+        .arg("logs")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No hook logs found"));
+}
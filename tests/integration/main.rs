@@ -4,12 +4,20 @@ mod common;
 
 mod add;
 mod config;
+mod doctor;
 mod hooks;
 mod list;
+mod lock;
+mod logs;
+mod mv;
+mod profile;
+mod prune;
 mod remove;
+mod setup;
 mod trust;
 
 // jj (Jujutsu) integration tests
 mod jj_add;
 mod jj_list;
+mod jj_prune;
 mod jj_remove;
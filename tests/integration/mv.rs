@@ -0,0 +1,190 @@
+use crate::common::TestRepo;
+use predicates::prelude::*;
+use std::fs;
+
+#[test]
+fn test_move_worktree_keeps_setup() {
+    let mut repo = TestRepo::with_config(
+        r#"
+link:
+  - source: local.env
+ports:
+  web: 3000
+hooks:
+  post_add:
+    - command: echo added on port {{port.web}}
+  pre_move:
+    - command: echo leaving {{worktree_name}} on port {{port.web}}
+  post_move:
+    - command: echo arrived in {{worktree_name}} on port {{port.web}}
+"#,
+    );
+    repo.create_file("local.env", "FOO=bar\n");
+    repo.trust_config();
+
+    let old_path = repo.worktree_path("move-old");
+    repo.kabu()
+        .args(["add", old_path.to_str().unwrap(), "-b", "move-test"])
+        .assert()
+        .success();
+    repo.register_worktree(old_path.clone());
+
+    let new_path = repo.worktree_path("nested/move-new");
+    repo.kabu()
+        .args([
+            "move",
+            old_path.to_str().unwrap(),
+            new_path.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("leaving move-old on port 3001"))
+        .stdout(predicate::str::contains("arrived in move-new on port 3001"));
+    repo.clear_registered_worktrees();
+    repo.register_worktree(new_path.clone());
+
+    assert!(!old_path.exists());
+    assert!(new_path.join("local.env").is_symlink());
+
+    // Hook logs from before the move are kept
+    repo.kabu()
+        .args(["logs", "--list", new_path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("-post_add.log"))
+        .stdout(predicate::str::contains("-pre_move.log"))
+        .stdout(predicate::str::contains("-post_move.log"));
+    assert!(
+        repo.list_worktrees()
+            .iter()
+            .any(|wt| wt.ends_with("nested/move-new"))
+    );
+
+    // The moved worktree keeps its slot, so a new one gets the next
+    let other_path = repo.worktree_path("move-other");
+    repo.kabu()
+        .args(["add", other_path.to_str().unwrap(), "-b", "move-other"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("added on port 3002"));
+    repo.register_worktree(other_path.clone());
+
+    // The setup manifest moved along: kabu-made files don't block removal
+    repo.kabu()
+        .args(["remove", new_path.to_str().unwrap()])
+        .assert()
+        .success();
+    repo.clear_registered_worktrees();
+    repo.register_worktree(other_path);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_move_relinks_symlinks_into_moved_worktree() {
+    let mut repo = TestRepo::with_config(
+        r#"
+link:
+  - source: local.env
+"#,
+    );
+    repo.create_file("local.env", "FOO=bar\n");
+
+    let source_path = repo.worktree_path("link-source");
+    repo.kabu()
+        .args(["add", source_path.to_str().unwrap(), "-b", "link-source"])
+        .assert()
+        .success();
+    repo.register_worktree(source_path.clone());
+
+    // Created from inside the first worktree, so its link points there
+    let linked_path = repo.worktree_path("linked");
+    repo.kabu()
+        .current_dir(&source_path)
+        .args(["add", linked_path.to_str().unwrap(), "-b", "linked"])
+        .assert()
+        .success();
+    repo.register_worktree(linked_path.clone());
+    let link = linked_path.join("local.env");
+    assert!(fs::read_link(&link).unwrap().starts_with(&source_path));
+
+    let moved_path = repo.worktree_path("link-source-moved");
+    repo.kabu()
+        .args([
+            "mv",
+            source_path.to_str().unwrap(),
+            moved_path.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+    repo.clear_registered_worktrees();
+    repo.register_worktree(moved_path.clone());
+    repo.register_worktree(linked_path.clone());
+
+    let canonical_moved = moved_path.canonicalize().unwrap();
+    assert_eq!(
+        fs::read_link(&link).unwrap(),
+        canonical_moved.join("local.env")
+    );
+    assert_eq!(fs::read_to_string(&link).unwrap(), "FOO=bar\n");
+}
+
+#[test]
+fn test_move_refuses_existing_destination() {
+    let mut repo = TestRepo::new();
+    let old_path = repo.worktree_path("move-exists");
+    repo.kabu()
+        .args(["add", old_path.to_str().unwrap(), "-b", "move-exists"])
+        .assert()
+        .success();
+    repo.register_worktree(old_path.clone());
+
+    let taken = repo.worktree_path("taken");
+    fs::create_dir_all(&taken).unwrap();
+
+    repo.kabu()
+        .args(["move", old_path.to_str().unwrap(), taken.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Destination already exists"));
+    assert!(old_path.exists());
+}
+
+#[test]
+fn test_move_refuses_main_worktree() {
+    let repo = TestRepo::new();
+    let new_path = repo.worktree_path("main-moved");
+
+    repo.kabu()
+        .args(["move", ".", new_path.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "main worktree/workspace cannot be moved",
+        ));
+    assert!(!new_path.exists());
+}
+
+#[test]
+fn test_move_dry_run() {
+    let mut repo = TestRepo::new();
+    let old_path = repo.worktree_path("move-dry");
+    repo.kabu()
+        .args(["add", old_path.to_str().unwrap(), "-b", "move-dry"])
+        .assert()
+        .success();
+    repo.register_worktree(old_path.clone());
+
+    let new_path = repo.worktree_path("move-dry-new");
+    repo.kabu()
+        .args([
+            "move",
+            "--dry-run",
+            old_path.to_str().unwrap(),
+            new_path.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Would move"));
+    assert!(old_path.exists());
+    assert!(!new_path.exists());
+}
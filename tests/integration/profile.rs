@@ -0,0 +1,154 @@
+use crate::common::TestRepo;
+use predicates::prelude::*;
+
+const PROFILES_CONFIG: &str = r#"
+default_profile: full
+
+link:
+  - source: local.env
+
+profiles:
+  full:
+    mkdir:
+      - path: node_modules
+  review:
+    description: Read-only review checkout
+    copy:
+      - source: review.env
+        target: .env
+"#;
+
+fn profiles_repo() -> TestRepo {
+    let repo = TestRepo::with_config(PROFILES_CONFIG);
+    repo.create_file("local.env", "export FOO=bar\n");
+    repo.create_file("review.env", "REVIEW=1\n");
+    repo
+}
+
+#[test]
+fn test_add_applies_default_profile() {
+    let mut repo = profiles_repo();
+    let worktree_path = repo.worktree_path("profile-default");
+
+    repo.kabu()
+        .args([
+            "add",
+            worktree_path.to_str().unwrap(),
+            "-b",
+            "profile-default",
+        ])
+        .assert()
+        .success();
+    repo.register_worktree(worktree_path);
+
+    assert!(repo.worktree_symlink_exists("profile-default", "local.env"));
+    assert!(repo.worktree_dir_exists("profile-default", "node_modules"));
+    assert!(!repo.worktree_file_exists("profile-default", ".env"));
+}
+
+#[test]
+fn test_add_with_profile() {
+    let mut repo = profiles_repo();
+    let worktree_path = repo.worktree_path("profile-review");
+
+    repo.kabu()
+        .args([
+            "add",
+            "--profile",
+            "review",
+            worktree_path.to_str().unwrap(),
+            "-b",
+            "profile-review",
+        ])
+        .assert()
+        .success();
+    repo.register_worktree(worktree_path.clone());
+
+    assert!(repo.worktree_symlink_exists("profile-review", "local.env"));
+    assert!(!repo.worktree_dir_exists("profile-review", "node_modules"));
+    assert_eq!(
+        repo.read_worktree_file("profile-review", ".env"),
+        "REVIEW=1\n"
+    );
+
+    // Re-applying setup keeps the profile the worktree was created with
+    std::fs::remove_file(worktree_path.join(".env")).expect("Failed to remove .env");
+    repo.kabu()
+        .args(["setup", worktree_path.to_str().unwrap()])
+        .assert()
+        .success();
+    assert!(repo.worktree_file_exists("profile-review", ".env"));
+    assert!(!repo.worktree_dir_exists("profile-review", "node_modules"));
+
+    repo.kabu()
+        .args(["config", "validate"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("out of date").not());
+}
+
+#[test]
+fn test_add_with_unknown_profile() {
+    let repo = profiles_repo();
+    let worktree_path = repo.worktree_path("profile-unknown");
+
+    repo.kabu()
+        .args([
+            "add",
+            "--profile",
+            "missing",
+            worktree_path.to_str().unwrap(),
+            "-b",
+            "profile-unknown",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown profile: missing"))
+        .stderr(predicate::str::contains("Available profiles: full, review"));
+
+    assert!(!worktree_path.exists());
+}
+
+#[test]
+fn test_profile_hooks_require_trust() {
+    let mut repo = TestRepo::with_config(
+        r#"
+profiles:
+  full:
+    hooks:
+      post_add:
+        - command: touch installed.txt
+"#,
+    );
+    let worktree_path = repo.worktree_path("profile-hooks");
+
+    repo.kabu()
+        .args([
+            "add",
+            "--profile",
+            "full",
+            worktree_path.to_str().unwrap(),
+            "-b",
+            "profile-hooks",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("profiles.full.post_add"))
+        .stderr(predicate::str::contains("not trusted"));
+
+    repo.trust_config();
+    repo.kabu()
+        .args([
+            "add",
+            "--profile",
+            "full",
+            worktree_path.to_str().unwrap(),
+            "-b",
+            "profile-hooks",
+        ])
+        .assert()
+        .success();
+    repo.register_worktree(worktree_path);
+
+    assert!(repo.worktree_file_exists("profile-hooks", "installed.txt"));
+}
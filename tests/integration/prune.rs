@@ -0,0 +1,211 @@
+use crate::common::{CONFIG_WITH_HOOKS, MINIMAL_CONFIG, TestRepo};
+use predicates::prelude::*;
+
+/// Create a worktree on a new branch with one commit, then merge it into main.
+fn add_merged_worktree(repo: &mut TestRepo, name: &str) -> std::path::PathBuf {
+    let worktree_path = repo.worktree_path(name);
+    repo.kabu()
+        .args([
+            "add",
+            worktree_path.to_str().expect("Non-UTF8 path"),
+            "-b",
+            name,
+        ])
+        .assert()
+        .success();
+    repo.register_worktree(worktree_path.clone());
+
+    std::fs::write(worktree_path.join(format!("{name}.txt")), "content\n")
+        .expect("Failed to write file");
+    repo.git(&worktree_path, &["add", "."]);
+    repo.git(&worktree_path, &["commit", "-m", "Work on branch"]);
+    repo.git(
+        repo.path(),
+        &["merge", "--no-ff", "-m", "Merge branch", name],
+    );
+
+    worktree_path
+}
+
+#[test]
+fn test_prune_removes_merged_worktree() {
+    let mut repo = TestRepo::with_config(MINIMAL_CONFIG);
+    let merged_path = add_merged_worktree(&mut repo, "prune-merged");
+
+    // Unmerged worktree with its own commit should be kept
+    let unmerged_path = repo.worktree_path("prune-unmerged");
+    repo.kabu()
+        .args([
+            "add",
+            unmerged_path.to_str().unwrap(),
+            "-b",
+            "prune-unmerged",
+        ])
+        .assert()
+        .success();
+    repo.register_worktree(unmerged_path.clone());
+    std::fs::write(unmerged_path.join("wip.txt"), "wip\n").unwrap();
+    repo.git(&unmerged_path, &["add", "."]);
+    repo.git(&unmerged_path, &["commit", "-m", "Unmerged work"]);
+
+    repo.kabu()
+        .args(["prune", "--yes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[merged]"));
+
+    assert!(!merged_path.exists());
+    assert!(unmerged_path.exists());
+}
+
+#[test]
+fn test_prune_keeps_new_worktree() {
+    let mut repo = TestRepo::with_config(MINIMAL_CONFIG);
+
+    // No commits yet, so the branch is an ancestor of main without being merged
+    let new_path = repo.worktree_path("prune-new");
+    repo.kabu()
+        .args(["add", new_path.to_str().unwrap(), "-b", "prune-new"])
+        .assert()
+        .success();
+    repo.register_worktree(new_path.clone());
+
+    repo.kabu()
+        .args(["prune", "--yes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No worktrees to prune"));
+
+    assert!(new_path.exists());
+}
+
+#[test]
+fn test_prune_keeps_new_worktree_after_base_moved() {
+    let mut repo = TestRepo::with_config(MINIMAL_CONFIG);
+
+    let new_path = repo.worktree_path("prune-behind");
+    repo.kabu()
+        .args(["add", new_path.to_str().unwrap(), "-b", "prune-behind"])
+        .assert()
+        .success();
+    repo.register_worktree(new_path.clone());
+    repo.create_file_and_commit("later.txt", "later\n", "Later work on main");
+
+    repo.kabu()
+        .args(["prune", "--yes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No worktrees to prune"));
+
+    assert!(new_path.exists());
+}
+
+#[test]
+fn test_prune_removes_merged_worktree_without_reflog() {
+    let mut repo = TestRepo::with_config(MINIMAL_CONFIG);
+    let merged_path = add_merged_worktree(&mut repo, "prune-expired");
+
+    // Expired reflogs (gc.reflogExpire) leave nothing to tell how the branch moved
+    repo.git(repo.path(), &["reflog", "expire", "--expire=now", "--all"]);
+
+    repo.kabu()
+        .args(["prune", "--yes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[merged]"));
+
+    assert!(!merged_path.exists());
+}
+
+#[test]
+fn test_prune_dry_run_keeps_worktrees() {
+    let mut repo = TestRepo::with_config(MINIMAL_CONFIG);
+    let merged_path = add_merged_worktree(&mut repo, "prune-dry-run");
+
+    repo.kabu()
+        .args(["prune", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("prune-dry-run"))
+        .stdout(predicate::str::contains("Would remove"));
+
+    assert!(merged_path.exists());
+}
+
+#[test]
+fn test_prune_nothing_to_prune() {
+    let repo = TestRepo::with_config(MINIMAL_CONFIG);
+
+    repo.kabu()
+        .args(["prune", "--yes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No worktrees to prune"));
+}
+
+#[test]
+fn test_prune_upstream_gone() {
+    let mut repo = TestRepo::with_config(MINIMAL_CONFIG);
+    let remote_path = repo.worktree_path("remote.git");
+    repo.git(
+        repo.path(),
+        &["init", "--bare", remote_path.to_str().unwrap()],
+    );
+    repo.git(
+        repo.path(),
+        &["remote", "add", "origin", remote_path.to_str().unwrap()],
+    );
+
+    let worktree_path = repo.worktree_path("prune-gone");
+    repo.kabu()
+        .args(["add", worktree_path.to_str().unwrap(), "-b", "prune-gone"])
+        .assert()
+        .success();
+    repo.register_worktree(worktree_path.clone());
+    std::fs::write(worktree_path.join("gone.txt"), "gone\n").unwrap();
+    repo.git(&worktree_path, &["add", "."]);
+    repo.git(&worktree_path, &["commit", "-m", "Pushed work"]);
+    repo.git(&worktree_path, &["push", "-u", "origin", "prune-gone"]);
+
+    // Branch is deleted on the remote (e.g. after the PR was squash-merged)
+    repo.git(repo.path(), &["push", "origin", "--delete", "prune-gone"]);
+    repo.git(repo.path(), &["fetch", "--prune", "origin"]);
+
+    repo.kabu()
+        .args(["prune", "--yes", "--force"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[upstream gone]"));
+
+    assert!(!worktree_path.exists());
+}
+
+#[test]
+fn test_prune_runs_remove_hooks() {
+    let mut repo = TestRepo::with_config(CONFIG_WITH_HOOKS);
+    repo.trust_config();
+    let merged_path = add_merged_worktree(&mut repo, "prune-hooks");
+
+    repo.kabu()
+        .args(["prune", "--yes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pre_remove"))
+        .stdout(predicate::str::contains("post_remove"));
+
+    assert!(!merged_path.exists());
+}
+
+#[test]
+fn test_prune_requires_tty_without_yes() {
+    let mut repo = TestRepo::with_config(MINIMAL_CONFIG);
+    let merged_path = add_merged_worktree(&mut repo, "prune-no-tty");
+
+    repo.kabu()
+        .args(["prune"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--yes"));
+
+    assert!(merged_path.exists());
+}
@@ -178,3 +178,66 @@ fn test_remove_multiple_worktrees() {
     assert!(!wt1_path.exists());
     assert!(!wt2_path.exists());
 }
+
+const CONFIG_WITH_UNTRACKED_SETUP: &str = r#"
+mkdir:
+  - path: tmp
+link:
+  - source: local.env
+copy:
+  - source: config.template
+    target: config.local
+"#;
+
+#[test]
+fn test_remove_ignores_unmodified_setup_files() {
+    let mut repo = TestRepo::with_config(CONFIG_WITH_UNTRACKED_SETUP);
+    repo.create_file("local.env", "FOO=bar\n");
+    repo.create_file("config.template", "# Config\n");
+    let worktree_path = repo.worktree_path("setup-files");
+
+    repo.kabu()
+        .args(["add", worktree_path.to_str().unwrap(), "-b", "setup-files"])
+        .assert()
+        .success();
+    repo.register_worktree(worktree_path.clone());
+
+    // Symlinks and copies made by kabu are not user changes
+    repo.kabu()
+        .args(["remove", worktree_path.to_str().unwrap()])
+        .assert()
+        .success();
+
+    repo.clear_registered_worktrees();
+    assert!(!worktree_path.exists());
+}
+
+#[test]
+fn test_remove_keeps_modified_setup_files() {
+    let mut repo = TestRepo::with_config(CONFIG_WITH_UNTRACKED_SETUP);
+    repo.create_file("local.env", "FOO=bar\n");
+    repo.create_file("config.template", "# Config\n");
+    let worktree_path = repo.worktree_path("modified-setup");
+
+    repo.kabu()
+        .args([
+            "add",
+            worktree_path.to_str().unwrap(),
+            "-b",
+            "modified-setup",
+        ])
+        .assert()
+        .success();
+    repo.register_worktree(worktree_path.clone());
+
+    std::fs::write(worktree_path.join("config.local"), "# Edited\n").expect("Failed to write file");
+
+    repo.kabu()
+        .args(["remove", worktree_path.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("uncommitted"));
+
+    assert!(worktree_path.join("config.local").exists());
+    assert!(worktree_path.join("local.env").is_symlink());
+}
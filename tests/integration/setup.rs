@@ -0,0 +1,299 @@
+use crate::common::{BASIC_CONFIG, CONFIG_WITH_HOOKS, MINIMAL_CONFIG, TestRepo};
+use predicates::prelude::*;
+
+#[test]
+fn test_setup_applies_config_to_existing_worktree() {
+    let mut repo = TestRepo::with_config(BASIC_CONFIG);
+    repo.create_file("local.env", "export FOO=bar\n");
+    repo.create_file("config.template", "# Config\n");
+    let worktree_path = repo.worktree_path("setup-existing");
+
+    // Create worktree without setup
+    repo.kabu()
+        .args([
+            "add",
+            "--no-setup",
+            worktree_path.to_str().unwrap(),
+            "-b",
+            "setup-existing",
+        ])
+        .assert()
+        .success();
+    repo.register_worktree(worktree_path.clone());
+    assert!(!repo.worktree_symlink_exists("setup-existing", "local.env"));
+
+    repo.kabu()
+        .args(["setup", worktree_path.to_str().unwrap()])
+        .assert()
+        .success();
+
+    assert!(repo.worktree_dir_exists("setup-existing", ".cache"));
+    assert!(repo.worktree_symlink_exists("setup-existing", "local.env"));
+    assert!(repo.worktree_file_exists("setup-existing", "config.local"));
+}
+
+#[test]
+fn test_setup_is_idempotent_for_links() {
+    let config = r#"
+link:
+  - source: local.env
+"#;
+    let mut repo = TestRepo::with_config(config);
+    repo.create_file("local.env", "export FOO=bar\n");
+    let worktree_path = repo.worktree_path("setup-idempotent");
+
+    repo.kabu()
+        .args([
+            "add",
+            worktree_path.to_str().unwrap(),
+            "-b",
+            "setup-idempotent",
+        ])
+        .assert()
+        .success();
+    repo.register_worktree(worktree_path.clone());
+
+    // Existing symlinks pointing at the source are not conflicts
+    repo.kabu()
+        .args(["setup", worktree_path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Unchanged"));
+
+    assert!(repo.worktree_symlink_exists("setup-idempotent", "local.env"));
+}
+
+#[test]
+fn test_setup_all_picks_up_new_link_entry() {
+    let mut repo = TestRepo::with_config(MINIMAL_CONFIG);
+    repo.create_file("local.env", "export FOO=bar\n");
+    let wt1_path = repo.worktree_path("setup-all-1");
+    let wt2_path = repo.worktree_path("setup-all-2");
+
+    for (path, branch) in [(&wt1_path, "setup-all-1"), (&wt2_path, "setup-all-2")] {
+        repo.kabu()
+            .args(["add", path.to_str().unwrap(), "-b", branch])
+            .assert()
+            .success();
+        repo.register_worktree(path.clone());
+    }
+
+    // A teammate adds a new link entry
+    repo.write_config(
+        r#"
+link:
+  - source: local.env
+"#,
+    );
+
+    repo.kabu().args(["setup", "--all"]).assert().success();
+
+    assert!(repo.worktree_symlink_exists("setup-all-1", "local.env"));
+    assert!(repo.worktree_symlink_exists("setup-all-2", "local.env"));
+}
+
+#[test]
+fn test_setup_rejects_main_worktree() {
+    let repo = TestRepo::with_config(MINIMAL_CONFIG);
+
+    repo.kabu()
+        .args(["setup", repo.path().to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("main worktree"));
+}
+
+#[test]
+fn test_setup_run_hooks_requires_trust() {
+    let mut repo = TestRepo::with_config(CONFIG_WITH_HOOKS);
+    let worktree_path = repo.worktree_path("setup-hooks");
+
+    repo.trust_config();
+    repo.kabu()
+        .args(["add", worktree_path.to_str().unwrap(), "-b", "setup-hooks"])
+        .assert()
+        .success();
+    repo.register_worktree(worktree_path.clone());
+
+    // Re-running post_add hooks prints the hook output
+    repo.kabu()
+        .args(["setup", "--run-hooks", worktree_path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("post_add"));
+
+    // Modifying the config invalidates trust
+    repo.write_config(
+        r#"
+hooks:
+  post_add:
+    - command: "echo changed"
+"#,
+    );
+    repo.kabu()
+        .args(["setup", "--run-hooks", worktree_path.to_str().unwrap()])
+        .assert()
+        .failure();
+
+    // Without --run-hooks, trust is not required
+    repo.kabu()
+        .args(["setup", worktree_path.to_str().unwrap()])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_setup_skips_entries_whose_condition_fails() {
+    let config = r#"
+mkdir:
+  - path: node_modules
+    when:
+      file_exists: package.json
+link:
+  - source: jj-only.env
+    when:
+      vcs: jj
+  - source: local.env
+    when:
+      vcs: git
+copy:
+  - source: release.env
+    target: .env
+    when:
+      branch: "release/*"
+"#;
+    let mut repo = TestRepo::with_config(config);
+    repo.create_file("local.env", "export FOO=bar\n");
+    let worktree_path = repo.worktree_path("setup-when");
+
+    // Sources of entries that don't apply are not required to exist
+    repo.kabu()
+        .args(["add", worktree_path.to_str().unwrap(), "-b", "feature/when"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "(when: file_exists: package.json)",
+        ))
+        .stdout(predicate::str::contains("(when: vcs: jj)"))
+        .stdout(predicate::str::contains("(when: branch: release/*)"));
+    repo.register_worktree(worktree_path.clone());
+
+    assert!(!repo.worktree_dir_exists("setup-when", "node_modules"));
+    assert!(!repo.worktree_symlink_exists("setup-when", "jj-only.env"));
+    assert!(repo.worktree_symlink_exists("setup-when", "local.env"));
+    assert!(!repo.worktree_file_exists("setup-when", ".env"));
+}
+
+#[test]
+fn test_setup_condition_sees_earlier_entries() {
+    let config = r#"
+link:
+  - source: shared.env
+    target: .env
+  - source: direnv.rc
+    target: .envrc
+    when:
+      file_exists: .env
+"#;
+    let mut repo = TestRepo::with_config(config);
+    repo.create_file("shared.env", "export FOO=bar\n");
+    repo.create_file("direnv.rc", "dotenv\n");
+    let worktree_path = repo.worktree_path("setup-when-order");
+
+    repo.kabu()
+        .args(["add", worktree_path.to_str().unwrap(), "-b", "when-order"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(when: file_exists: .env)").not());
+    repo.register_worktree(worktree_path);
+
+    assert!(repo.worktree_symlink_exists("setup-when-order", ".env"));
+    assert!(repo.worktree_symlink_exists("setup-when-order", ".envrc"));
+}
+
+#[test]
+fn test_setup_from_linked_worktree_uses_main_sources() {
+    let config = r#"
+link:
+  - source: local.env
+"#;
+    let mut repo = TestRepo::with_config(config);
+    repo.create_file("local.env", "export FOO=bar\n");
+    let worktree_path = repo.worktree_path("setup-inside");
+    repo.kabu()
+        .args([
+            "add",
+            "--no-setup",
+            worktree_path.to_str().unwrap(),
+            "-b",
+            "setup-inside",
+        ])
+        .assert()
+        .success();
+    repo.register_worktree(worktree_path.clone());
+    std::fs::write(worktree_path.join("local.env"), "export FOO=mine\n").unwrap();
+
+    repo.kabu()
+        .current_dir(&worktree_path)
+        .args(["setup", "--on-conflict", "overwrite"])
+        .assert()
+        .success();
+
+    let link = std::fs::read_link(worktree_path.join("local.env")).unwrap();
+    assert_eq!(link, repo.path().join("local.env"));
+    assert_eq!(
+        repo.read_worktree_file("setup-inside", "local.env"),
+        "export FOO=bar\n"
+    );
+}
+
+#[test]
+fn test_setup_requires_trust_for_sources_outside_repository() {
+    let mut repo = TestRepo::new();
+    let worktree_path = repo.worktree_path("setup-outside");
+    repo.kabu()
+        .args([
+            "add",
+            worktree_path.to_str().unwrap(),
+            "-b",
+            "setup-outside",
+        ])
+        .assert()
+        .success();
+    repo.register_worktree(worktree_path.clone());
+
+    let secrets = repo.worktree_path("secrets");
+    std::fs::create_dir_all(&secrets).unwrap();
+    std::fs::write(secrets.join("secret.txt"), "TOKEN=1\n").unwrap();
+    repo.write_config(&format!(
+        r#"
+copy:
+  - source: secret.txt
+    source_root: "{}"
+"#,
+        secrets.display()
+    ));
+
+    // No hooks run, but the copy reaches outside the repository
+    repo.kabu()
+        .args(["setup", worktree_path.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("kabu trust"));
+    assert!(!repo.worktree_file_exists("setup-outside", "secret.txt"));
+    repo.kabu()
+        .args(["doctor"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("kabu trust"));
+
+    repo.trust_config();
+    repo.kabu()
+        .args(["setup", worktree_path.to_str().unwrap()])
+        .assert()
+        .success();
+    assert_eq!(
+        repo.read_worktree_file("setup-outside", "secret.txt"),
+        "TOKEN=1\n"
+    );
+}
@@ -143,3 +143,73 @@ fn test_re_trust_unchanged_config() {
             predicate::str::contains("already trusted").or(predicate::str::contains("unchanged")),
         );
 }
+
+#[test]
+fn test_trust_covers_extended_files() {
+    let repo = TestRepo::with_config("extends: [.kabu/hooks.yaml]\n");
+    repo.create_file(
+        ".kabu/hooks.yaml",
+        "hooks:\n  post_add:\n    - command: echo one\n",
+    );
+
+    repo.kabu()
+        .args(["trust", "--show"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Extends:\n  .kabu/hooks.yaml"));
+
+    repo.trust_config();
+    repo.kabu().args(["trust", "--check"]).assert().success();
+
+    repo.create_file(
+        ".kabu/hooks.yaml",
+        "hooks:\n  post_add:\n    - command: echo two\n",
+    );
+    repo.kabu()
+        .args(["trust", "--check"])
+        .assert()
+        .failure()
+        .code(1);
+}
+
+#[test]
+fn test_global_hooks_require_global_trust() {
+    let mut repo = TestRepo::new();
+    repo.create_file(".claude", "personal\n");
+    let config_home = repo.worktree_path("config-home");
+    std::fs::create_dir_all(config_home.join("kabu")).expect("Failed to create config dir");
+    std::fs::write(
+        config_home.join("kabu/config.yaml"),
+        "link:\n  - source: .claude\nhooks:\n  post_add:\n    - command: touch global-hook-ran\n",
+    )
+    .expect("Failed to write global config");
+    let worktree_path = repo.worktree_path("global-hooks");
+
+    repo.kabu()
+        .env("XDG_CONFIG_HOME", &config_home)
+        .args(["add", worktree_path.to_str().unwrap(), "-b", "global-hooks"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("kabu trust --global"));
+
+    repo.kabu()
+        .env("XDG_CONFIG_HOME", &config_home)
+        .args(["trust", "--global", "--yes"])
+        .assert()
+        .success();
+    repo.kabu()
+        .env("XDG_CONFIG_HOME", &config_home)
+        .args(["trust", "--global", "--check"])
+        .assert()
+        .success();
+
+    repo.kabu()
+        .env("XDG_CONFIG_HOME", &config_home)
+        .args(["add", worktree_path.to_str().unwrap(), "-b", "global-hooks"])
+        .assert()
+        .success();
+    repo.register_worktree(worktree_path);
+
+    assert!(repo.worktree_symlink_exists("global-hooks", ".claude"));
+    assert!(repo.worktree_file_exists("global-hooks", "global-hook-ran"));
+}